@@ -79,7 +79,18 @@ impl ResolvedTable {
             let pattern_rust = pattern_to_rust(&entry.match_.pattern, entry.match_.count);
             let result_rust =
                 result_to_rust(&entry.result).with_context(|| format!("flag '{}'", entry.match_.pattern))?;
-            out.push_str(&format!("    FlagRule::new({}, {}),\n", pattern_rust, result_rust));
+            entry.validate_path_value()?;
+            let rule_rust = match entry.clang_to_rust()? {
+                Some(clang_rust) => {
+                    format!("FlagRule::with_clang({}, {}, {})", pattern_rust, result_rust, clang_rust)
+                }
+                None => format!("FlagRule::new({}, {})", pattern_rust, result_rust),
+            };
+            if entry.path_value {
+                out.push_str(&format!("    {}.with_path_value(),\n", rule_rust));
+            } else {
+                out.push_str(&format!("    {},\n", rule_rust));
+            }
         }
         out.push_str("];\n");
         Ok(out)
@@ -310,6 +321,62 @@ mod tests {
         assert_eq!(m.name_len(), 5);
     }
 
+    // -- FlagEntry::clang_to_rust tests --
+
+    #[test]
+    fn clang_to_rust_without_annotation() {
+        let entry = make_test_flag("-Wall", "none");
+        assert_eq!(entry.clang_to_rust().unwrap(), None);
+    }
+
+    #[test]
+    fn clang_to_rust_unsupported() {
+        let entry = FlagEntry { clang_unsupported: true, ..make_test_flag("-fconserve-stack", "none") };
+        assert_eq!(entry.clang_to_rust().unwrap().as_deref(), Some("ClangCompatibility::Unsupported"));
+    }
+
+    #[test]
+    fn clang_to_rust_equivalent_uses_flag_name() {
+        let entry = FlagEntry {
+            clang_equivalent: Some("-mretpoline".to_string()),
+            ..make_test_flag("-mindirect-branch=*", "configures_compiling")
+        };
+        assert_eq!(
+            entry.clang_to_rust().unwrap().as_deref(),
+            Some(
+                "ClangCompatibility::Equivalent { flag: \"-mindirect-branch\", equivalent: \"-mretpoline\" }"
+            )
+        );
+    }
+
+    #[test]
+    fn clang_to_rust_both_annotations_is_err() {
+        let entry = FlagEntry {
+            clang_equivalent: Some("-Wno-uninitialized".to_string()),
+            clang_unsupported: true,
+            ..make_test_flag("-Wno-maybe-uninitialized", "none")
+        };
+        let err = entry.clang_to_rust().unwrap_err();
+        assert!(err.to_string().contains("has both"), "{}", err);
+    }
+
+    #[test]
+    fn validate_path_value_needs_a_value() {
+        let entry = FlagEntry { path_value: true, ..make_test_flag("-I{ }*", "configures_preprocessing") };
+        assert!(entry.validate_path_value().is_ok());
+
+        let entry = FlagEntry {
+            path_value: true,
+            match_: FlagMatch { pattern: "-MF".to_string(), count: Some(1) },
+            ..make_test_flag("-MF", "configures_preprocessing")
+        };
+        assert!(entry.validate_path_value().is_ok());
+
+        let entry = FlagEntry { path_value: true, ..make_test_flag("-nostdinc", "configures_preprocessing") };
+        let err = entry.validate_path_value().unwrap_err();
+        assert!(err.to_string().contains("without value"), "{}", err);
+    }
+
     // -- resolve tests --
 
     #[test]
@@ -604,6 +671,9 @@ mod tests {
         FlagEntry {
             match_: FlagMatch { pattern: pattern.to_string(), count: None },
             result: result.to_string(),
+            clang_equivalent: None,
+            clang_unsupported: false,
+            path_value: false,
         }
     }
 
@@ -40,6 +40,47 @@ pub struct FlagEntry {
     #[serde(rename = "match")]
     pub match_: FlagMatch,
     pub result: String,
+    /// The name Clang knows this flag by, when it differs.
+    #[serde(default)]
+    pub clang_equivalent: Option<String>,
+    /// When true, Clang does not understand this flag.
+    #[serde(default)]
+    pub clang_unsupported: bool,
+    /// When true, the value of this flag is a file system path.
+    #[serde(default)]
+    pub path_value: bool,
+}
+
+impl FlagEntry {
+    /// Convert the Clang annotations to a Rust `ClangCompatibility` expression string.
+    ///
+    /// Returns `None` when the flag has no annotation (Clang understands it as is).
+    pub fn clang_to_rust(&self) -> Result<Option<String>> {
+        match (&self.clang_equivalent, self.clang_unsupported) {
+            (Some(_), true) => {
+                bail!("flag '{}': has both 'clang_equivalent' and 'clang_unsupported'", self.match_.pattern)
+            }
+            (Some(equivalent), false) => {
+                let flag = &self.match_.pattern[..self.match_.name_len()];
+                Ok(Some(format!(
+                    "ClangCompatibility::Equivalent {{ flag: \"{}\", equivalent: \"{}\" }}",
+                    flag, equivalent
+                )))
+            }
+            (None, true) => Ok(Some("ClangCompatibility::Unsupported".to_string())),
+            (None, false) => Ok(None),
+        }
+    }
+
+    /// Check that the `path_value` annotation is on a flag which takes a value.
+    pub fn validate_path_value(&self) -> Result<()> {
+        let has_value =
+            self.match_.pattern.ends_with('*') || self.match_.count.is_some_and(|count| count > 0);
+        if self.path_value && !has_value {
+            bail!("flag '{}': 'path_value' on a flag without value", self.match_.pattern)
+        }
+        Ok(())
+    }
 }
 
 #[derive(Deserialize, Clone, Debug)]
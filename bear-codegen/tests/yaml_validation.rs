@@ -69,6 +69,20 @@ fn all_flag_patterns_produce_valid_codegen() {
     }
 }
 
+/// Every flag entry has consistent Clang compatibility and path value annotations.
+#[test]
+fn all_flag_clang_annotations_are_valid() {
+    let tables = load_tables().unwrap();
+    for config in TABLES {
+        let key = config.yaml_file.strip_suffix(".yaml").unwrap();
+        let table = &tables[key];
+        for entry in &table.flags {
+            entry.clang_to_rust().unwrap_or_else(|e| panic!("{}: {}", config.yaml_file, e));
+            entry.validate_path_value().unwrap_or_else(|e| panic!("{}: {}", config.yaml_file, e));
+        }
+    }
+}
+
 /// Every environment entry in every YAML file passes validation.
 #[test]
 fn all_env_entries_are_valid() {
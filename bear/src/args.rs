@@ -6,12 +6,22 @@
 //! The module defines types to represent a structured form of program invocation.
 //! The `Arguments` type is used to represent all possible invocations of the program.
 
+use crate::config;
 use clap::{ArgAction, ArgMatches, Command, arg, command};
 use std::fmt;
 
 /// Common constants used in the module.
 const MODE_INTERCEPT_SUBCOMMAND: &str = "intercept";
 const MODE_SEMANTIC_SUBCOMMAND: &str = "semantic";
+const MODE_PRUNE_SUBCOMMAND: &str = "prune";
+const MODE_CONVERT_SUBCOMMAND: &str = "convert";
+const MODE_GRAPH_SUBCOMMAND: &str = "graph";
+const MODE_EXPORT_SUBCOMMAND: &str = "export";
+const MODE_MERGE_SUBCOMMAND: &str = "merge";
+const MODE_DIFF_SUBCOMMAND: &str = "diff";
+const MODE_CHECK_SUBCOMMAND: &str = "check";
+const MODE_NORMALIZE_SUBCOMMAND: &str = "normalize";
+const MODE_IMPORT_SUBCOMMAND: &str = "import";
 const DEFAULT_OUTPUT_FILE: &str = "compile_commands.json";
 const DEFAULT_EVENT_FILE: &str = "events.json";
 
@@ -30,6 +40,15 @@ pub enum Mode {
     Intercept { input: BuildCommand, output: BuildEvents },
     Semantic { input: BuildEvents, output: BuildSemantic },
     Combined { input: BuildCommand, output: BuildSemantic },
+    Prune { output: BuildSemantic },
+    Convert { input: BuildEvents, output: ConvertDatabase },
+    Graph { input: BuildEvents, output: ExportGraph },
+    Export { input: ExportInput, output: ExportScript },
+    Merge { inputs: Vec<std::path::PathBuf>, output: BuildSemantic },
+    Diff { old: std::path::PathBuf, new: std::path::PathBuf, output: DiffReport },
+    Check { input: std::path::PathBuf, output: CheckReport },
+    Normalize { input: std::path::PathBuf, output: BuildSemantic },
+    Import { input: ImportLog, output: BuildEvents },
 }
 
 /// Represents the execution of a command.
@@ -48,6 +67,112 @@ pub struct BuildSemantic {
     pub append: bool,
 }
 
+/// Represents the compilation database conversion output.
+#[derive(Debug, PartialEq)]
+pub struct ConvertDatabase {
+    /// The output file path.
+    pub path: std::path::PathBuf,
+    /// The file format of the output.
+    pub format: config::DatabaseFormat,
+}
+
+/// Represents the build artifact graph output.
+#[derive(Debug, PartialEq)]
+pub struct ExportGraph {
+    /// The output file path.
+    pub path: std::path::PathBuf,
+    /// The file format of the output.
+    pub format: GraphFormat,
+}
+
+/// The file formats of the build artifact graph.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum GraphFormat {
+    /// JSON object with the nodes and edges arrays.
+    Json,
+    /// Graphviz DOT language.
+    Dot,
+}
+
+/// Represents the input of the build script export.
+#[derive(Debug, PartialEq)]
+pub enum ExportInput {
+    /// A compilation database (in either JSON array or JSON lines format).
+    Database { path: std::path::PathBuf },
+    /// An event file, which is analyzed the same way as in semantic mode.
+    Events { path: std::path::PathBuf },
+}
+
+/// Represents the build script export output.
+#[derive(Debug, PartialEq)]
+pub struct ExportScript {
+    /// The output file path.
+    pub path: std::path::PathBuf,
+    /// The file format of the output.
+    pub format: ScriptFormat,
+    /// The compiler to run instead of the recorded one.
+    pub compiler: Option<String>,
+    /// The flags to append to each command.
+    pub flags: Vec<String>,
+}
+
+/// The file formats of the build script export.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ScriptFormat {
+    /// Ninja build file.
+    Ninja,
+    /// POSIX shell script.
+    Shell,
+}
+
+/// Represents the compilation database difference report.
+#[derive(Debug, PartialEq)]
+pub struct DiffReport {
+    /// The output file path. The report is printed to the standard output when missing.
+    pub path: Option<std::path::PathBuf>,
+    /// The format of the report.
+    pub format: DiffFormat,
+}
+
+/// The formats of the compilation database difference report.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum DiffFormat {
+    /// Human-readable text.
+    Text,
+    /// JSON object with the added, removed and changed arrays.
+    Json,
+}
+
+/// Represents the compilation database check report.
+#[derive(Debug, PartialEq)]
+pub struct CheckReport {
+    /// The output file path. The report is printed to the standard output when missing.
+    pub path: Option<std::path::PathBuf>,
+}
+
+/// Represents the build log to import as events.
+#[derive(Debug, PartialEq)]
+pub struct ImportLog {
+    /// The path to the log file.
+    pub path: std::path::PathBuf,
+    /// The format of the log file.
+    pub format: ImportFormat,
+    /// The working directory of the traced build. The current directory is
+    /// used when missing.
+    pub directory: Option<std::path::PathBuf>,
+}
+
+/// The formats of the build logs to import.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ImportFormat {
+    /// The output of `strace -f -e trace=process,chdir`.
+    Strace,
+    /// The output of `bazel aquery --output=jsonproto`.
+    AqueryJson,
+    /// The output of `bazel aquery --output=textproto`.
+    AqueryText,
+}
+
 /// Represents the build events configuration.
 #[derive(Debug, PartialEq)]
 pub struct BuildEvents {
@@ -84,6 +209,53 @@ impl fmt::Display for Mode {
                 writeln!(f, "  Input: {}", input)?;
                 write!(f, "  Output: {}", output)
             }
+            Mode::Prune { output } => {
+                writeln!(f, "Prune")?;
+                write!(f, "  Output: {}", output)
+            }
+            Mode::Convert { input, output } => {
+                writeln!(f, "Convert")?;
+                writeln!(f, "  Input: {}", input.path.display())?;
+                write!(f, "  Output: {}", output)
+            }
+            Mode::Graph { input, output } => {
+                writeln!(f, "Graph")?;
+                writeln!(f, "  Input: {}", input)?;
+                write!(f, "  Output: {}", output)
+            }
+            Mode::Export { input, output } => {
+                writeln!(f, "Export")?;
+                writeln!(f, "  Input: {}", input)?;
+                write!(f, "  Output: {}", output)
+            }
+            Mode::Merge { inputs, output } => {
+                writeln!(f, "Merge")?;
+                for input in inputs {
+                    writeln!(f, "  Input: {}", input.display())?;
+                }
+                write!(f, "  Output: {}", output)
+            }
+            Mode::Diff { old, new, output } => {
+                writeln!(f, "Diff")?;
+                writeln!(f, "  Old: {}", old.display())?;
+                writeln!(f, "  New: {}", new.display())?;
+                write!(f, "  Output: {}", output)
+            }
+            Mode::Check { input, output } => {
+                writeln!(f, "Check")?;
+                writeln!(f, "  Input: {}", input.display())?;
+                write!(f, "  Output: {}", output)
+            }
+            Mode::Normalize { input, output } => {
+                writeln!(f, "Normalize")?;
+                writeln!(f, "  Input: {}", input.display())?;
+                write!(f, "  Output: {}", output)
+            }
+            Mode::Import { input, output } => {
+                writeln!(f, "Import")?;
+                writeln!(f, "  Input: {}", input)?;
+                write!(f, "  Output: {}", output)
+            }
         }
     }
 }
@@ -100,6 +272,68 @@ impl fmt::Display for BuildSemantic {
     }
 }
 
+impl fmt::Display for ConvertDatabase {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Database Output: {} (format: {:?})", self.path.display(), self.format)
+    }
+}
+
+impl fmt::Display for ExportGraph {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Graph Output: {} (format: {:?})", self.path.display(), self.format)
+    }
+}
+
+impl fmt::Display for ExportInput {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ExportInput::Database { path } => write!(f, "Database Input: {}", path.display()),
+            ExportInput::Events { path } => write!(f, "Events Input: {}", path.display()),
+        }
+    }
+}
+
+impl fmt::Display for ExportScript {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Script Output: {} (format: {:?})", self.path.display(), self.format)?;
+        if let Some(compiler) = &self.compiler {
+            write!(f, " (compiler: {compiler})")?;
+        }
+        if !self.flags.is_empty() {
+            write!(f, " (flags: {})", self.flags.join(" "))?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for DiffReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.path {
+            Some(path) => write!(f, "Report Output: {} (format: {:?})", path.display(), self.format),
+            None => write!(f, "Report Output: <stdout> (format: {:?})", self.format),
+        }
+    }
+}
+
+impl fmt::Display for CheckReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.path {
+            Some(path) => write!(f, "Report Output: {}", path.display()),
+            None => write!(f, "Report Output: <stdout>"),
+        }
+    }
+}
+
+impl fmt::Display for ImportLog {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Log Input: {} (format: {:?})", self.path.display(), self.format)?;
+        if let Some(directory) = &self.directory {
+            write!(f, " (directory: {})", directory.display())?;
+        }
+        Ok(())
+    }
+}
+
 impl fmt::Display for BuildEvents {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "Events Output: {}", self.path.display())
@@ -140,6 +374,146 @@ impl TryFrom<ArgMatches> for Mode {
                 let output = BuildSemantic::try_from(semantic_matches)?;
                 Ok(Mode::Semantic { input: BuildEvents { path }, output })
             }
+            Some((MODE_PRUNE_SUBCOMMAND, prune_matches)) => {
+                let path = prune_matches
+                    .get_one::<String>("output")
+                    .map(std::path::PathBuf::from)
+                    .expect("output is defaulted");
+
+                // The existing entries are read the same way as in append mode.
+                Ok(Mode::Prune { output: BuildSemantic { path, append: true } })
+            }
+            Some((MODE_CONVERT_SUBCOMMAND, convert_matches)) => {
+                let input = convert_matches
+                    .get_one::<String>("input")
+                    .map(std::path::PathBuf::from)
+                    .expect("input is required");
+                let path = convert_matches
+                    .get_one::<String>("output")
+                    .map(std::path::PathBuf::from)
+                    .expect("output is required");
+                let format = match convert_matches.get_one::<String>("format").map(String::as_str) {
+                    Some("json-lines") => config::DatabaseFormat::JsonLines,
+                    _ => config::DatabaseFormat::Json,
+                };
+
+                Ok(Mode::Convert {
+                    input: BuildEvents { path: input },
+                    output: ConvertDatabase { path, format },
+                })
+            }
+            Some((MODE_GRAPH_SUBCOMMAND, graph_matches)) => {
+                let input = graph_matches
+                    .get_one::<String>("input")
+                    .map(std::path::PathBuf::from)
+                    .expect("input is defaulted");
+                let path = graph_matches
+                    .get_one::<String>("output")
+                    .map(std::path::PathBuf::from)
+                    .expect("output is required");
+                let format = match graph_matches.get_one::<String>("format").map(String::as_str) {
+                    Some("dot") => GraphFormat::Dot,
+                    _ => GraphFormat::Json,
+                };
+
+                Ok(Mode::Graph { input: BuildEvents { path: input }, output: ExportGraph { path, format } })
+            }
+            Some((MODE_EXPORT_SUBCOMMAND, export_matches)) => {
+                let input = match export_matches.get_one::<String>("events") {
+                    Some(events) => ExportInput::Events { path: events.into() },
+                    None => {
+                        let path = export_matches
+                            .get_one::<String>("input")
+                            .map(std::path::PathBuf::from)
+                            .expect("input is defaulted");
+                        ExportInput::Database { path }
+                    }
+                };
+                let path = export_matches
+                    .get_one::<String>("output")
+                    .map(std::path::PathBuf::from)
+                    .expect("output is required");
+                let format = match export_matches.get_one::<String>("format").map(String::as_str) {
+                    Some("shell") => ScriptFormat::Shell,
+                    _ => ScriptFormat::Ninja,
+                };
+                let compiler = export_matches.get_one::<String>("compiler").cloned();
+                let flags = export_matches
+                    .get_many::<String>("flag")
+                    .map(|flags| flags.cloned().collect())
+                    .unwrap_or_default();
+
+                Ok(Mode::Export { input, output: ExportScript { path, format, compiler, flags } })
+            }
+            Some((MODE_MERGE_SUBCOMMAND, merge_matches)) => {
+                let inputs = merge_matches
+                    .get_many::<String>("INPUT")
+                    .expect("inputs are required")
+                    .map(std::path::PathBuf::from)
+                    .collect();
+                let path = merge_matches
+                    .get_one::<String>("output")
+                    .map(std::path::PathBuf::from)
+                    .expect("output is required");
+
+                Ok(Mode::Merge { inputs, output: BuildSemantic { path, append: false } })
+            }
+            Some((MODE_DIFF_SUBCOMMAND, diff_matches)) => {
+                let old = diff_matches
+                    .get_one::<String>("OLD")
+                    .map(std::path::PathBuf::from)
+                    .expect("old is required");
+                let new = diff_matches
+                    .get_one::<String>("NEW")
+                    .map(std::path::PathBuf::from)
+                    .expect("new is required");
+                let path = diff_matches.get_one::<String>("output").map(std::path::PathBuf::from);
+                let format = match diff_matches.get_one::<String>("format").map(String::as_str) {
+                    Some("json") => DiffFormat::Json,
+                    _ => DiffFormat::Text,
+                };
+
+                Ok(Mode::Diff { old, new, output: DiffReport { path, format } })
+            }
+            Some((MODE_CHECK_SUBCOMMAND, check_matches)) => {
+                let input = check_matches
+                    .get_one::<String>("INPUT")
+                    .map(std::path::PathBuf::from)
+                    .expect("input is defaulted");
+                let path = check_matches.get_one::<String>("output").map(std::path::PathBuf::from);
+
+                Ok(Mode::Check { input, output: CheckReport { path } })
+            }
+            Some((MODE_NORMALIZE_SUBCOMMAND, normalize_matches)) => {
+                let input = normalize_matches
+                    .get_one::<String>("input")
+                    .map(std::path::PathBuf::from)
+                    .expect("input is required");
+
+                let output = BuildSemantic::try_from(normalize_matches)?;
+                Ok(Mode::Normalize { input, output })
+            }
+            Some((MODE_IMPORT_SUBCOMMAND, import_matches)) => {
+                let input = import_matches
+                    .get_one::<String>("input")
+                    .map(std::path::PathBuf::from)
+                    .expect("input is required");
+                let format = match import_matches.get_one::<String>("format").map(String::as_str) {
+                    Some("aquery-jsonproto") => ImportFormat::AqueryJson,
+                    Some("aquery-textproto") => ImportFormat::AqueryText,
+                    _ => ImportFormat::Strace,
+                };
+                let directory = import_matches.get_one::<String>("directory").map(std::path::PathBuf::from);
+                let path = import_matches
+                    .get_one::<String>("output")
+                    .map(std::path::PathBuf::from)
+                    .expect("output is defaulted");
+
+                Ok(Mode::Import {
+                    input: ImportLog { path: input, format, directory },
+                    output: BuildEvents { path },
+                })
+            }
             None => {
                 let input = BuildCommand::try_from(&matches)?;
                 let output = BuildSemantic::try_from(&matches)?;
@@ -186,7 +560,15 @@ pub enum ParseError {
 /// This describes how the user can interact with the application.
 /// The different modes of the application are represented as subcommands.
 /// The application can be run in intercept mode, semantic mode, or the
-/// default mode where both intercept and semantic are executed.
+/// default mode where both intercept and semantic are executed. The prune
+/// and convert modes maintain existing compilation databases. The graph
+/// mode exports the build artifact graph from an event file, the export mode
+/// turns the compilations into a runnable build script. The merge mode
+/// combines existing compilation databases, the diff mode compares them, and
+/// the check mode validates one in depth. The normalize mode analyzes the
+/// commands of an existing compilation database, the same way as the events
+/// of a build. The import mode turns the logs of other tracing tools (or the
+/// action graph of Bazel) into an event file.
 pub fn cli() -> Command {
     // The binary is `bear-driver` but users invoke it as `bear` via a
     // shell wrapper, so we hardcode the user-facing name instead of
@@ -228,6 +610,128 @@ pub fn cli() -> Command {
                 ])
                 .arg_required_else_help(false),
         )
+        .subcommand(
+            Command::new(MODE_PRUNE_SUBCOMMAND)
+                .about("drop entries of deleted files from a compilation database")
+                .args(&[arg!(-o --output <FILE> "Path of the result file")
+                    .default_value(DEFAULT_OUTPUT_FILE)
+                    .hide_default_value(false)])
+                .arg_required_else_help(false),
+        )
+        .subcommand(
+            Command::new(MODE_CONVERT_SUBCOMMAND)
+                .about("convert a compilation database between JSON array and JSON lines formats")
+                .args(&[
+                    arg!(-i --input <FILE> "Path of the compilation database to convert").required(true),
+                    arg!(-o --output <FILE> "Path of the result file").required(true),
+                    arg!(-f --format <FORMAT> "File format of the result")
+                        .value_parser(["json", "json-lines"])
+                        .default_value("json")
+                        .hide_default_value(false),
+                ])
+                .arg_required_else_help(true),
+        )
+        .subcommand(
+            Command::new(MODE_GRAPH_SUBCOMMAND)
+                .about("export the build artifact graph from command executions")
+                .args(&[
+                    arg!(-i --input <FILE> "Path of the event file")
+                        .default_value(DEFAULT_EVENT_FILE)
+                        .hide_default_value(false),
+                    arg!(-o --output <FILE> "Path of the result file").required(true),
+                    arg!(-f --format <FORMAT> "File format of the result")
+                        .value_parser(["json", "dot"])
+                        .default_value("json")
+                        .hide_default_value(false),
+                ])
+                .arg_required_else_help(true),
+        )
+        .subcommand(
+            Command::new(MODE_EXPORT_SUBCOMMAND)
+                .about("export the compilations as a Ninja build file or shell script")
+                .args(&[
+                    arg!(-i --input <FILE> "Path of the compilation database")
+                        .default_value(DEFAULT_OUTPUT_FILE)
+                        .hide_default_value(false),
+                    arg!(-e --events <FILE> "Path of the event file (instead of a compilation database)")
+                        .conflicts_with("input"),
+                    arg!(-o --output <FILE> "Path of the result file").required(true),
+                    arg!(-f --format <FORMAT> "File format of the result")
+                        .value_parser(["ninja", "shell"])
+                        .default_value("ninja")
+                        .hide_default_value(false),
+                    arg!(--compiler <PROGRAM> "Run this compiler instead of the recorded one"),
+                    arg!(--flag <FLAG> "Append this flag to each command (can be repeated)")
+                        .action(ArgAction::Append)
+                        .allow_hyphen_values(true),
+                ])
+                .arg_required_else_help(true),
+        )
+        .subcommand(
+            Command::new(MODE_MERGE_SUBCOMMAND)
+                .about("merge compilation databases into one")
+                .args(&[
+                    arg!(<INPUT> "Paths of the compilation databases to merge")
+                        .action(ArgAction::Append)
+                        .num_args(1..)
+                        .required(true),
+                    arg!(-o --output <FILE> "Path of the result file").required(true),
+                ])
+                .arg_required_else_help(true),
+        )
+        .subcommand(
+            Command::new(MODE_DIFF_SUBCOMMAND)
+                .about("compare two compilation databases, exits with failure when they differ")
+                .args(&[
+                    arg!(<OLD> "Path of the old compilation database").required(true),
+                    arg!(<NEW> "Path of the new compilation database").required(true),
+                    arg!(-o --output <FILE> "Path of the report file (default: standard output)"),
+                    arg!(-f --format <FORMAT> "Format of the report")
+                        .value_parser(["text", "json"])
+                        .default_value("text")
+                        .hide_default_value(false),
+                ])
+                .arg_required_else_help(true),
+        )
+        .subcommand(
+            Command::new(MODE_CHECK_SUBCOMMAND)
+                .about("validate a compilation database, exits with failure when it has errors")
+                .args(&[
+                    arg!([INPUT] "Path of the compilation database")
+                        .default_value(DEFAULT_OUTPUT_FILE)
+                        .hide_default_value(false),
+                    arg!(-o --output <FILE> "Path of the report file (default: standard output)"),
+                ])
+                .arg_required_else_help(false),
+        )
+        .subcommand(
+            Command::new(MODE_NORMALIZE_SUBCOMMAND)
+                .about("rewrite a compilation database with the semantic analysis of its commands")
+                .args(&[
+                    arg!(-i --input <FILE> "Path of the compilation database to normalize").required(true),
+                    arg!(-o --output <FILE> "Path of the result file")
+                        .default_value(DEFAULT_OUTPUT_FILE)
+                        .hide_default_value(false),
+                    arg!(-a --append "Append result to an existing output file").action(ArgAction::SetTrue),
+                ])
+                .arg_required_else_help(true),
+        )
+        .subcommand(
+            Command::new(MODE_IMPORT_SUBCOMMAND)
+                .about("import the log of a build tracing tool or a Bazel action graph as an event file")
+                .args(&[
+                    arg!(-i --input <FILE> "Path of the log file").required(true),
+                    arg!(-f --format <FORMAT> "Format of the log file")
+                        .value_parser(["strace", "aquery-jsonproto", "aquery-textproto"])
+                        .default_value("strace")
+                        .hide_default_value(false),
+                    arg!(-d --directory <DIR> "Working directory of the traced build, the execution root for Bazel (default: current directory)"),
+                    arg!(-o --output <FILE> "Path of the event file")
+                        .default_value(DEFAULT_EVENT_FILE)
+                        .hide_default_value(false),
+                ])
+                .arg_required_else_help(true),
+        )
         .args(&[
             arg!(<BUILD_COMMAND> "Build command")
                 .action(ArgAction::Append)
@@ -369,4 +873,366 @@ mod test {
             }
         );
     }
+
+    #[test]
+    fn test_prune_call() {
+        let execution = vec!["bear", "-c", "~/bear.yaml", "prune", "-o", "result.json"];
+
+        let matches = cli().get_matches_from(execution);
+        let arguments = Arguments::try_from(matches).unwrap();
+
+        assert_eq!(
+            arguments,
+            Arguments {
+                config: Some("~/bear.yaml".into()),
+                mode: Mode::Prune { output: BuildSemantic { path: "result.json".into(), append: true } },
+            }
+        );
+    }
+
+    #[test]
+    fn test_prune_defaults() {
+        let execution = vec!["bear", "prune"];
+
+        let matches = cli().get_matches_from(execution);
+        let arguments = Arguments::try_from(matches).unwrap();
+
+        assert_eq!(
+            arguments,
+            Arguments {
+                config: None,
+                mode: Mode::Prune {
+                    output: BuildSemantic { path: "compile_commands.json".into(), append: true }
+                },
+            }
+        );
+    }
+
+    #[test]
+    fn test_convert_call() {
+        let execution = vec!["bear", "convert", "-i", "input.json", "-o", "output.jsonl", "-f", "json-lines"];
+
+        let matches = cli().get_matches_from(execution);
+        let arguments = Arguments::try_from(matches).unwrap();
+
+        assert_eq!(
+            arguments,
+            Arguments {
+                config: None,
+                mode: Mode::Convert {
+                    input: BuildEvents { path: "input.json".into() },
+                    output: ConvertDatabase {
+                        path: "output.jsonl".into(),
+                        format: config::DatabaseFormat::JsonLines
+                    },
+                },
+            }
+        );
+    }
+
+    #[test]
+    fn test_convert_defaults() {
+        let execution = vec!["bear", "convert", "-i", "input.jsonl", "-o", "output.json"];
+
+        let matches = cli().get_matches_from(execution);
+        let arguments = Arguments::try_from(matches).unwrap();
+
+        assert_eq!(
+            arguments,
+            Arguments {
+                config: None,
+                mode: Mode::Convert {
+                    input: BuildEvents { path: "input.jsonl".into() },
+                    output: ConvertDatabase {
+                        path: "output.json".into(),
+                        format: config::DatabaseFormat::Json
+                    },
+                },
+            }
+        );
+    }
+
+    #[test]
+    fn test_graph_call() {
+        let execution = vec!["bear", "graph", "-i", "custom.json", "-o", "graph.dot", "-f", "dot"];
+
+        let matches = cli().get_matches_from(execution);
+        let arguments = Arguments::try_from(matches).unwrap();
+
+        assert_eq!(
+            arguments,
+            Arguments {
+                config: None,
+                mode: Mode::Graph {
+                    input: BuildEvents { path: "custom.json".into() },
+                    output: ExportGraph { path: "graph.dot".into(), format: GraphFormat::Dot },
+                },
+            }
+        );
+    }
+
+    #[test]
+    fn test_graph_defaults() {
+        let execution = vec!["bear", "graph", "-o", "graph.json"];
+
+        let matches = cli().get_matches_from(execution);
+        let arguments = Arguments::try_from(matches).unwrap();
+
+        assert_eq!(
+            arguments,
+            Arguments {
+                config: None,
+                mode: Mode::Graph {
+                    input: BuildEvents { path: "events.json".into() },
+                    output: ExportGraph { path: "graph.json".into(), format: GraphFormat::Json },
+                },
+            }
+        );
+    }
+
+    #[test]
+    fn test_export_call() {
+        let execution = vec![
+            "bear",
+            "export",
+            "-e",
+            "events.json",
+            "-o",
+            "build.sh",
+            "-f",
+            "shell",
+            "--compiler",
+            "clang",
+            "--flag",
+            "-fsyntax-only",
+            "--flag=-w",
+        ];
+
+        let matches = cli().get_matches_from(execution);
+        let arguments = Arguments::try_from(matches).unwrap();
+
+        assert_eq!(
+            arguments,
+            Arguments {
+                config: None,
+                mode: Mode::Export {
+                    input: ExportInput::Events { path: "events.json".into() },
+                    output: ExportScript {
+                        path: "build.sh".into(),
+                        format: ScriptFormat::Shell,
+                        compiler: Some("clang".into()),
+                        flags: vec!["-fsyntax-only".into(), "-w".into()],
+                    },
+                },
+            }
+        );
+    }
+
+    #[test]
+    fn test_export_defaults() {
+        let execution = vec!["bear", "export", "-o", "build.ninja"];
+
+        let matches = cli().get_matches_from(execution);
+        let arguments = Arguments::try_from(matches).unwrap();
+
+        assert_eq!(
+            arguments,
+            Arguments {
+                config: None,
+                mode: Mode::Export {
+                    input: ExportInput::Database { path: "compile_commands.json".into() },
+                    output: ExportScript {
+                        path: "build.ninja".into(),
+                        format: ScriptFormat::Ninja,
+                        compiler: None,
+                        flags: vec![],
+                    },
+                },
+            }
+        );
+    }
+
+    #[test]
+    fn test_export_input_conflicts_with_events() {
+        let execution = vec!["bear", "export", "-i", "db.json", "-e", "events.json", "-o", "build.ninja"];
+
+        assert!(cli().try_get_matches_from(execution).is_err());
+    }
+
+    #[test]
+    fn test_merge_call() {
+        let execution = vec!["bear", "-c", "~/bear.yaml", "merge", "a.json", "b/b.json", "-o", "out.json"];
+
+        let matches = cli().get_matches_from(execution);
+        let arguments = Arguments::try_from(matches).unwrap();
+
+        assert_eq!(
+            arguments,
+            Arguments {
+                config: Some("~/bear.yaml".into()),
+                mode: Mode::Merge {
+                    inputs: vec!["a.json".into(), "b/b.json".into()],
+                    output: BuildSemantic { path: "out.json".into(), append: false },
+                },
+            }
+        );
+    }
+
+    #[test]
+    fn test_merge_requires_output() {
+        let execution = vec!["bear", "merge", "a.json", "b.json"];
+
+        assert!(cli().try_get_matches_from(execution).is_err());
+    }
+
+    #[test]
+    fn test_diff_call() {
+        let execution = vec!["bear", "diff", "old.json", "new.json"];
+
+        let matches = cli().get_matches_from(execution);
+        let arguments = Arguments::try_from(matches).unwrap();
+
+        assert_eq!(
+            arguments,
+            Arguments {
+                config: None,
+                mode: Mode::Diff {
+                    old: "old.json".into(),
+                    new: "new.json".into(),
+                    output: DiffReport { path: None, format: DiffFormat::Text },
+                },
+            }
+        );
+    }
+
+    #[test]
+    fn test_check_call() {
+        let execution = vec!["bear", "check"];
+
+        let matches = cli().get_matches_from(execution);
+        let arguments = Arguments::try_from(matches).unwrap();
+
+        assert_eq!(
+            arguments,
+            Arguments {
+                config: None,
+                mode: Mode::Check {
+                    input: "compile_commands.json".into(),
+                    output: CheckReport { path: None },
+                },
+            }
+        );
+    }
+
+    #[test]
+    fn test_check_call_with_report() {
+        let execution = vec!["bear", "check", "build/db.json", "-o", "findings.json"];
+
+        let matches = cli().get_matches_from(execution);
+        let arguments = Arguments::try_from(matches).unwrap();
+
+        assert_eq!(
+            arguments,
+            Arguments {
+                config: None,
+                mode: Mode::Check {
+                    input: "build/db.json".into(),
+                    output: CheckReport { path: Some("findings.json".into()) },
+                },
+            }
+        );
+    }
+
+    #[test]
+    fn test_normalize_call() {
+        let execution = vec!["bear", "normalize", "-i", "cmake/compile_commands.json", "-o", "result.json"];
+
+        let matches = cli().get_matches_from(execution);
+        let arguments = Arguments::try_from(matches).unwrap();
+
+        assert_eq!(
+            arguments,
+            Arguments {
+                config: None,
+                mode: Mode::Normalize {
+                    input: "cmake/compile_commands.json".into(),
+                    output: BuildSemantic { path: "result.json".into(), append: false },
+                },
+            }
+        );
+    }
+
+    #[test]
+    fn test_normalize_requires_input() {
+        let execution = vec!["bear", "normalize", "-o", "result.json"];
+
+        assert!(cli().try_get_matches_from(execution).is_err());
+    }
+
+    #[test]
+    fn test_import_call() {
+        let execution = vec!["bear", "import", "-i", "build.log", "-d", "/home/user/project"];
+
+        let matches = cli().get_matches_from(execution);
+        let arguments = Arguments::try_from(matches).unwrap();
+
+        assert_eq!(
+            arguments,
+            Arguments {
+                config: None,
+                mode: Mode::Import {
+                    input: ImportLog {
+                        path: "build.log".into(),
+                        format: ImportFormat::Strace,
+                        directory: Some("/home/user/project".into()),
+                    },
+                    output: BuildEvents { path: "events.json".into() },
+                },
+            }
+        );
+    }
+
+    #[test]
+    fn test_import_aquery_call() {
+        let execution =
+            vec!["bear", "import", "-i", "actions.json", "-f", "aquery-jsonproto", "-o", "bazel.json"];
+
+        let matches = cli().get_matches_from(execution);
+        let arguments = Arguments::try_from(matches).unwrap();
+
+        assert_eq!(
+            arguments,
+            Arguments {
+                config: None,
+                mode: Mode::Import {
+                    input: ImportLog {
+                        path: "actions.json".into(),
+                        format: ImportFormat::AqueryJson,
+                        directory: None
+                    },
+                    output: BuildEvents { path: "bazel.json".into() },
+                },
+            }
+        );
+    }
+
+    #[test]
+    fn test_diff_call_with_json_report() {
+        let execution = vec!["bear", "diff", "old.json", "new.json", "-f", "json", "-o", "diff.json"];
+
+        let matches = cli().get_matches_from(execution);
+        let arguments = Arguments::try_from(matches).unwrap();
+
+        assert_eq!(
+            arguments,
+            Arguments {
+                config: None,
+                mode: Mode::Diff {
+                    old: "old.json".into(),
+                    new: "new.json".into(),
+                    output: DiffReport { path: Some("diff.json".into()), format: DiffFormat::Json },
+                },
+            }
+        );
+    }
 }
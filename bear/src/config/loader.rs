@@ -1,7 +1,7 @@
 // SPDX-License-Identifier: GPL-3.0-or-later
 
-use super::Main;
 use super::validation::Validator;
+use super::{Main, OutputRoute};
 use directories::{BaseDirs, ProjectDirs};
 use log::{debug, info};
 use std::fs::OpenOptions;
@@ -73,9 +73,12 @@ impl Loader {
             .open(path)
             .map_err(|source| ConfigError::FileAccess { path: path.to_path_buf(), source })?;
 
-        let content: Main = Self::from_reader(reader)
+        let mut content: Main = Self::from_reader(reader)
             .map_err(|source| ConfigError::ParseError { path: path.to_path_buf(), source })?;
 
+        // The project root is relative to the configuration file location.
+        Self::resolve_project_root(&mut content, path);
+
         // Validate the loaded configuration
         Main::validate(&content)
             .map_err(|source| ConfigError::ValidationError { path: path.to_path_buf(), source })?;
@@ -83,6 +86,30 @@ impl Loader {
         Ok(content)
     }
 
+    /// Makes the project root of the path format absolute.
+    ///
+    /// A relative root is taken relative to the directory of the configuration
+    /// file. When the root is needed but not given, that directory is used.
+    ///
+    /// The path format overrides of the output routes are resolved the same way.
+    fn resolve_project_root(config: &mut Main, path: &Path) {
+        let Some(base) = std::path::absolute(path).ok().and_then(|path| path.parent().map(Path::to_path_buf))
+        else {
+            return;
+        };
+        let route_paths = config.outputs.iter_mut().filter_map(OutputRoute::paths_mut);
+        for paths in std::iter::once(&mut config.format.paths).chain(route_paths) {
+            if paths.root.is_none() && !paths.needs_root() {
+                continue;
+            }
+            paths.root = match paths.root.take() {
+                Some(root) if root.is_relative() => Some(base.join(root)),
+                Some(root) => Some(root),
+                None => Some(base.clone()),
+            };
+        }
+    }
+
     /// Define the deserialization format of the config file.
     pub(crate) fn from_reader<R, T>(rdr: R) -> Result<T, serde_saphyr::Error>
     where
@@ -156,14 +183,64 @@ mod test {
 
             duplicates:
                 match_on: [file, directory]
+                keep: last
+                normalize_arguments: true
+
+            append:
+                strategy: update
+                match_output: true
+                prune_missing: true
 
             format:
+                database: json-lines
                 paths:
                     directory: canonical
                     file: canonical
+                    remap:
+                      - from: /work
+                        to: /home/dev/project
                 entries:
                     use_array_format: true
                     include_output_field: true
+                    sort: directory-file
+                arguments:
+                  - action: remove
+                    match: { exact: "-Werror" }
+                  - action: replace
+                    match: { regex: "-std=gnu\\+\\+(.*)" }
+                    arguments: ["-std=c++$1"]
+                    compiler: gcc
+                  - action: append
+                    arguments: ["-Wno-unknown-warning-option"]
+                executables:
+                  - path: /usr/lib/ccache/gcc
+                    replacement: { path: /usr/bin/gcc }
+                  - compiler: clang
+                    replacement: basename
+
+            compile_flags:
+                enabled: true
+
+            link_commands:
+                enabled: true
+                path: build/link_commands.json
+
+            outputs:
+              - path: frontend/compile_commands.json
+                sources:
+                  directories:
+                    - path: /opt/project/frontend
+                      action: include
+                paths:
+                  directory: absolute
+                  file: relative
+              - name: legacy
+                path: legacy.json
+                duplicates:
+                  match_on: [file]
+                format:
+                  entries:
+                    use_array_format: false
             "#;
 
         let result = Loader::from_reader(content).unwrap();
@@ -192,11 +269,95 @@ mod test {
                     },
                 ],
             },
-            duplicates: DuplicateFilter { match_on: vec![OutputFields::File, OutputFields::Directory] },
+            duplicates: DuplicateFilter {
+                match_on: vec![OutputFields::File, OutputFields::Directory],
+                keep: DuplicateKeep::Last,
+                normalize_arguments: true,
+            },
+            append: Append { strategy: AppendStrategy::Update, match_output: true, prune_missing: true },
             format: Format {
-                paths: PathFormat { directory: PathResolver::Canonical, file: PathResolver::Canonical },
-                entries: EntryFormat { use_array_format: true, include_output_field: true },
+                database: DatabaseFormat::JsonLines,
+                paths: PathFormat {
+                    directory: PathResolver::Canonical,
+                    file: PathResolver::Canonical,
+                    remap: vec![PathRemap {
+                        from: PathBuf::from("/work"),
+                        to: PathBuf::from("/home/dev/project"),
+                    }],
+                    ..PathFormat::default()
+                },
+                entries: EntryFormat {
+                    use_array_format: true,
+                    include_output_field: true,
+                    sort: Some(EntrySort::DirectoryFile),
+                    ..EntryFormat::default()
+                },
+                query_driver: DriverQuery::default(),
+                arguments: vec![
+                    ArgumentRule {
+                        action: ArgumentAction::Remove,
+                        match_: Some(ArgumentMatch::Exact("-Werror".into())),
+                        arguments: vec![],
+                        compiler: None,
+                    },
+                    ArgumentRule {
+                        action: ArgumentAction::Replace,
+                        match_: Some(ArgumentMatch::Regex("-std=gnu\\+\\+(.*)".into())),
+                        arguments: vec!["-std=c++$1".into()],
+                        compiler: Some(CompilerType::Gcc),
+                    },
+                    ArgumentRule {
+                        action: ArgumentAction::Append,
+                        match_: None,
+                        arguments: vec!["-Wno-unknown-warning-option".into()],
+                        compiler: None,
+                    },
+                ],
+                executables: vec![
+                    ExecutableRule {
+                        path: Some(PathBuf::from("/usr/lib/ccache/gcc")),
+                        compiler: None,
+                        replacement: ExecutableReplacement::Path(PathBuf::from("/usr/bin/gcc")),
+                    },
+                    ExecutableRule {
+                        path: None,
+                        compiler: Some(CompilerType::Clang),
+                        replacement: ExecutableReplacement::Basename,
+                    },
+                ],
             },
+            compile_flags: CompileFlags { enabled: true },
+            link_commands: LinkCommands { enabled: true, path: PathBuf::from("build/link_commands.json") },
+            outputs: vec![
+                OutputRoute {
+                    path: PathBuf::from("frontend/compile_commands.json"),
+                    sources: SourceFilter {
+                        directories: vec![DirectoryRule {
+                            path: PathBuf::from("/opt/project/frontend"),
+                            action: DirectoryAction::Include,
+                        }],
+                    },
+                    paths: Some(PathFormat {
+                        directory: PathResolver::Absolute,
+                        file: PathResolver::Relative,
+                        ..PathFormat::default()
+                    }),
+                    ..OutputRoute::default()
+                },
+                OutputRoute {
+                    name: Some(String::from("legacy")),
+                    path: PathBuf::from("legacy.json"),
+                    duplicates: Some(DuplicateFilter {
+                        match_on: vec![OutputFields::File],
+                        ..DuplicateFilter::default()
+                    }),
+                    format: Some(Format {
+                        entries: EntryFormat { use_array_format: false, ..EntryFormat::default() },
+                        ..Format::default()
+                    }),
+                    ..OutputRoute::default()
+                },
+            ],
         };
 
         assert_eq!(expected, result);
@@ -225,11 +386,28 @@ mod test {
             sources: SourceFilter { directories: vec![] },
             duplicates: DuplicateFilter {
                 match_on: vec![OutputFields::Directory, OutputFields::File, OutputFields::Arguments],
+                ..DuplicateFilter::default()
             },
+            append: Append::default(),
             format: Format {
-                paths: PathFormat { directory: PathResolver::AsIs, file: PathResolver::AsIs },
-                entries: EntryFormat { use_array_format: true, include_output_field: true },
+                database: DatabaseFormat::Json,
+                paths: PathFormat {
+                    directory: PathResolver::AsIs,
+                    file: PathResolver::AsIs,
+                    ..PathFormat::default()
+                },
+                entries: EntryFormat {
+                    use_array_format: true,
+                    include_output_field: true,
+                    ..EntryFormat::default()
+                },
+                query_driver: DriverQuery::default(),
+                arguments: vec![],
+                executables: vec![],
             },
+            compile_flags: CompileFlags::default(),
+            link_commands: LinkCommands::default(),
+            outputs: vec![],
         };
 
         assert_eq!(expected, result);
@@ -257,11 +435,28 @@ mod test {
             sources: SourceFilter { directories: vec![] },
             duplicates: DuplicateFilter {
                 match_on: vec![OutputFields::Directory, OutputFields::File, OutputFields::Arguments],
+                ..DuplicateFilter::default()
             },
+            append: Append::default(),
             format: Format {
-                paths: PathFormat { directory: PathResolver::Absolute, file: PathResolver::Absolute },
-                entries: EntryFormat { use_array_format: true, include_output_field: true },
+                database: DatabaseFormat::Json,
+                paths: PathFormat {
+                    directory: PathResolver::Absolute,
+                    file: PathResolver::Absolute,
+                    ..PathFormat::default()
+                },
+                entries: EntryFormat {
+                    use_array_format: true,
+                    include_output_field: true,
+                    ..EntryFormat::default()
+                },
+                query_driver: DriverQuery::default(),
+                arguments: vec![],
+                executables: vec![],
             },
+            compile_flags: CompileFlags::default(),
+            link_commands: LinkCommands::default(),
+            outputs: vec![],
         };
 
         assert_eq!(expected, result);
@@ -277,7 +472,11 @@ mod test {
             compilers: vec![],
             sources: SourceFilter::default(),
             duplicates: DuplicateFilter::default(),
+            append: Append::default(),
             format: Format::default(),
+            compile_flags: CompileFlags::default(),
+            link_commands: LinkCommands::default(),
+            outputs: vec![],
         };
 
         assert_eq!(expected, result);
@@ -362,6 +561,38 @@ mod test {
         }
     }
 
+    // Requirements: output-path-root
+    #[test]
+    fn test_project_root_relative_to_config_file() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let config_file = temp_dir.path().join("bear.yml");
+
+        fs::write(
+            &config_file,
+            "schema: \"4.1\"\nformat:\n  paths:\n    directory: root-relative\n    file: relative\n",
+        )
+        .unwrap();
+        let result = Loader::from_file(&config_file).unwrap();
+        assert_eq!(result.format.paths.root, Some(temp_dir.path().to_path_buf()));
+
+        fs::write(&config_file, "schema: \"4.1\"\nformat:\n  paths:\n    root: project\n").unwrap();
+        let result = Loader::from_file(&config_file).unwrap();
+        assert_eq!(result.format.paths.root, Some(temp_dir.path().join("project")));
+
+        fs::write(&config_file, "schema: \"4.1\"\n").unwrap();
+        let result = Loader::from_file(&config_file).unwrap();
+        assert_eq!(result.format.paths.root, None);
+
+        fs::write(
+            &config_file,
+            "schema: \"4.1\"\noutputs:\n  - path: sub.json\n    paths:\n      directory: root-relative\n      file: relative\n",
+        )
+        .unwrap();
+        let result = Loader::from_file(&config_file).unwrap();
+        assert_eq!(result.format.paths.root, None);
+        assert_eq!(result.outputs[0].paths.as_ref().unwrap().root, Some(temp_dir.path().to_path_buf()));
+    }
+
     #[test]
     fn test_compiler_type_serialization() {
         fn assert_compiler_type_deserializes(json_str: &str, expected: CompilerType) {
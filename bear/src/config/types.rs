@@ -5,7 +5,7 @@ use std::fmt;
 use std::path::PathBuf;
 
 /// Represents the application configuration with flattened structure.
-#[derive(Debug, PartialEq, serde::Deserialize, serde::Serialize)]
+#[derive(Clone, Debug, PartialEq, serde::Deserialize, serde::Serialize)]
 pub struct Main {
     #[serde(deserialize_with = "validate_schema_version")]
     pub schema: String,
@@ -18,7 +18,15 @@ pub struct Main {
     #[serde(default)]
     pub duplicates: DuplicateFilter,
     #[serde(default)]
+    pub append: Append,
+    #[serde(default)]
     pub format: Format,
+    #[serde(default)]
+    pub compile_flags: CompileFlags,
+    #[serde(default)]
+    pub link_commands: LinkCommands,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub outputs: Vec<OutputRoute>,
 }
 
 impl Default for Main {
@@ -29,7 +37,11 @@ impl Default for Main {
             compilers: vec![],
             sources: SourceFilter::default(),
             duplicates: DuplicateFilter::default(),
+            append: Append::default(),
             format: Format::default(),
+            compile_flags: CompileFlags::default(),
+            link_commands: LinkCommands::default(),
+            outputs: vec![],
         }
     }
 }
@@ -50,7 +62,7 @@ impl fmt::Display for Main {
 }
 
 /// Simplified intercept configuration with mode.
-#[derive(Debug, PartialEq, serde::Deserialize, serde::Serialize)]
+#[derive(Clone, Debug, PartialEq, serde::Deserialize, serde::Serialize)]
 #[serde(tag = "mode")]
 pub enum Intercept {
     #[serde(rename = "wrapper")]
@@ -73,7 +85,7 @@ impl Default for Intercept {
 }
 
 /// Represents compiler configuration matching the YAML format.
-#[derive(Debug, PartialEq, serde::Deserialize, serde::Serialize)]
+#[derive(Clone, Debug, PartialEq, serde::Deserialize, serde::Serialize)]
 pub struct Compiler {
     pub path: PathBuf,
     #[serde(rename = "as", skip_serializing_if = "Option::is_none")]
@@ -176,15 +188,71 @@ pub struct SourceFilter {
 /// Duplicate filter configuration matching the YAML format.
 #[derive(Clone, Debug, PartialEq, serde::Deserialize, serde::Serialize)]
 pub struct DuplicateFilter {
+    #[serde(default = "DuplicateFilter::default_match_on")]
     pub match_on: Vec<OutputFields>,
+    #[serde(default)]
+    pub keep: DuplicateKeep,
+    /// Ignore the output file names (`-o`, `-MF`, `-MT`, `-MQ` values) and the
+    /// order of the `-D` and `-I` flags when comparing the arguments.
+    #[serde(default)]
+    pub normalize_arguments: bool,
+}
+
+impl DuplicateFilter {
+    fn default_match_on() -> Vec<OutputFields> {
+        vec![OutputFields::Directory, OutputFields::File, OutputFields::Arguments]
+    }
 }
 
 impl Default for DuplicateFilter {
     fn default() -> Self {
-        Self { match_on: vec![OutputFields::Directory, OutputFields::File, OutputFields::Arguments] }
+        Self {
+            match_on: Self::default_match_on(),
+            keep: DuplicateKeep::default(),
+            normalize_arguments: false,
+        }
     }
 }
 
+/// Which entry is kept from the duplicates.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, serde::Deserialize, serde::Serialize)]
+pub enum DuplicateKeep {
+    /// Keep the first occurrence. (Default)
+    #[default]
+    #[serde(rename = "first")]
+    First,
+    /// Keep the last occurrence, so the entries of a rebuild win.
+    #[serde(rename = "last")]
+    Last,
+}
+
+/// Append mode configuration matching the YAML format.
+///
+/// Only used when the output is appended to an existing compilation database.
+#[derive(Clone, Debug, Default, PartialEq, serde::Deserialize, serde::Serialize)]
+pub struct Append {
+    #[serde(default)]
+    pub strategy: AppendStrategy,
+    /// With the `update` strategy, match the `output` field too, not only the `file`.
+    #[serde(default)]
+    pub match_output: bool,
+    /// Drop the existing entries whose `file` or `directory` no longer exists.
+    #[serde(default)]
+    pub prune_missing: bool,
+}
+
+/// How the existing entries are combined with the new ones.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, serde::Deserialize, serde::Serialize)]
+pub enum AppendStrategy {
+    /// Keep all existing entries and add the new ones after them. (Default)
+    #[default]
+    #[serde(rename = "concat")]
+    Concat,
+    /// Drop the existing entries which are replaced by a new entry for the same file.
+    #[serde(rename = "update")]
+    Update,
+}
+
 /// Represent the fields of the JSON compilation database record.
 #[derive(Copy, Clone, Debug, Eq, Hash, PartialEq, serde::Deserialize, serde::Serialize)]
 pub enum OutputFields {
@@ -200,13 +268,106 @@ pub enum OutputFields {
     Output,
 }
 
+/// Configuration of the `compile_flags.txt` output matching the YAML format.
+///
+/// When enabled, a `compile_flags.txt` file is written into each directory
+/// which contains compiled source files (next to the compilation database).
+#[derive(Clone, Debug, Default, PartialEq, serde::Deserialize, serde::Serialize)]
+pub struct CompileFlags {
+    #[serde(default)]
+    pub enabled: bool,
+}
+
+/// Configuration of the link command database matching the YAML format.
+///
+/// When enabled, the linker and archiver invocations are written into a
+/// separate JSON file, with their output, input files, libraries and library
+/// search paths. These commands are not part of the compilation database.
+#[derive(Clone, Debug, PartialEq, serde::Deserialize, serde::Serialize)]
+pub struct LinkCommands {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default = "LinkCommands::default_path")]
+    pub path: PathBuf,
+}
+
+impl LinkCommands {
+    fn default_path() -> PathBuf {
+        PathBuf::from("link_commands.json")
+    }
+}
+
+impl Default for LinkCommands {
+    fn default() -> Self {
+        Self { enabled: false, path: Self::default_path() }
+    }
+}
+
+/// Output route configuration matching the YAML format.
+///
+/// A route writes an additional compilation database from the same build.
+/// The entries are selected by the `sources` rules of the route, which are
+/// evaluated after the global `sources` rules (the last matching rule wins).
+/// Each route has its own duplicate detection and atomic file write, and can
+/// replace the global `duplicates` and `format` configuration.
+#[derive(Clone, Debug, Default, PartialEq, serde::Deserialize, serde::Serialize)]
+pub struct OutputRoute {
+    /// The name of the output in the logs (the path is used when not given).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// The compilation database file of the route (relative to the working directory).
+    pub path: PathBuf,
+    #[serde(default)]
+    pub sources: SourceFilter,
+    /// Replaces the `duplicates` configuration for this route.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub duplicates: Option<DuplicateFilter>,
+    /// Replaces the `format` configuration for this route.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub format: Option<Format>,
+    /// Replaces the `format.paths` configuration for this route.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub paths: Option<PathFormat>,
+}
+
+impl OutputRoute {
+    /// The path format configuration which applies to the route, if it is overridden.
+    pub fn paths_mut(&mut self) -> Option<&mut PathFormat> {
+        match (&mut self.paths, &mut self.format) {
+            (Some(paths), _) => Some(paths),
+            (None, Some(format)) => Some(&mut format.paths),
+            (None, None) => None,
+        }
+    }
+}
+
 /// Format configuration matching the YAML format.
 #[derive(Clone, Debug, Default, PartialEq, serde::Deserialize, serde::Serialize)]
 pub struct Format {
+    #[serde(default)]
+    pub database: DatabaseFormat,
     #[serde(default)]
     pub paths: PathFormat,
     #[serde(default)]
     pub entries: EntryFormat,
+    #[serde(default)]
+    pub query_driver: DriverQuery,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub arguments: Vec<ArgumentRule>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub executables: Vec<ExecutableRule>,
+}
+
+/// The file format of the compilation database.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, serde::Deserialize, serde::Serialize)]
+pub enum DatabaseFormat {
+    /// The standard JSON array of entries. (Default)
+    #[default]
+    #[serde(rename = "json")]
+    Json,
+    /// One JSON object per line (newline-delimited JSON).
+    #[serde(rename = "json-lines")]
+    JsonLines,
 }
 
 /// Format configuration of paths in the JSON compilation database.
@@ -216,6 +377,34 @@ pub struct PathFormat {
     pub directory: PathResolver,
     #[serde(default)]
     pub file: PathResolver,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub remap: Vec<PathRemap>,
+    /// The project root for the `root-relative` resolver and the placeholder.
+    /// When not given, the directory of the configuration file is used.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub root: Option<PathBuf>,
+    /// Write this placeholder (like `${root}`) instead of the project root
+    /// at the beginning of the absolute paths.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub root_placeholder: Option<String>,
+}
+
+impl PathFormat {
+    /// Whether the formatting depends on the project root.
+    pub fn needs_root(&self) -> bool {
+        self.directory == PathResolver::RootRelative || self.root_placeholder.is_some()
+    }
+}
+
+/// A path prefix remapping rule.
+///
+/// Translates the paths of the build environment (like a container, where the
+/// sources are at `/work`) to the view of the consumer (like the host, where
+/// the same sources are at `/home/dev/project`).
+#[derive(Clone, Debug, PartialEq, serde::Deserialize, serde::Serialize)]
+pub struct PathRemap {
+    pub from: PathBuf,
+    pub to: PathBuf,
 }
 
 /// Path resolver options matching the YAML format.
@@ -234,6 +423,10 @@ pub enum PathResolver {
     /// The path will be resolved to an absolute path.
     #[serde(rename = "absolute")]
     Absolute,
+    /// The path will be resolved to the relative path to the project root.
+    /// (Only for the directory attribute.)
+    #[serde(rename = "root-relative")]
+    RootRelative,
 }
 
 /// Configuration for formatting output entries matching the YAML format.
@@ -243,14 +436,130 @@ pub struct EntryFormat {
     pub use_array_format: bool,
     #[serde(default = "default_enabled")]
     pub include_output_field: bool,
+    /// Append `--target=<triple>` derived from a cross-compiler prefix
+    /// (like `aarch64-linux-gnu-gcc`) when the command has no explicit target.
+    #[serde(default)]
+    pub include_target_from_prefix: bool,
+    /// Remove or rename flags which Clang based tools do not understand.
+    #[serde(default)]
+    pub clang_compatible: bool,
+    /// Sort the entries, instead of writing them in the order of the build.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sort: Option<EntrySort>,
 }
 
 impl Default for EntryFormat {
     fn default() -> Self {
-        Self { use_array_format: true, include_output_field: true }
+        Self {
+            use_array_format: true,
+            include_output_field: true,
+            include_target_from_prefix: false,
+            clang_compatible: false,
+            sort: None,
+        }
     }
 }
 
+/// The sort key of the output entries.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, serde::Deserialize, serde::Serialize)]
+pub enum EntrySort {
+    /// Sort by the `file` field.
+    #[serde(rename = "file")]
+    File,
+    /// Sort by the `directory` field, then by the `file` field.
+    #[serde(rename = "directory-file")]
+    DirectoryFile,
+    /// Sort by the `output` field (entries without output come first).
+    #[serde(rename = "output")]
+    Output,
+}
+
+/// Configuration for querying compiler drivers for their implicit settings.
+///
+/// Cross toolchains have built-in include directories and macro definitions
+/// that tools consuming the compilation database cannot discover on their own.
+/// When enabled, each distinct compiler is executed once per language to
+/// collect these settings, which are then appended to the output entries.
+#[derive(Clone, Debug, Default, PartialEq, serde::Deserialize, serde::Serialize)]
+pub struct DriverQuery {
+    /// Query the compilers for their system include directories and target.
+    #[serde(default)]
+    pub enabled: bool,
+    /// Also append the built-in macro definitions of the compilers.
+    #[serde(default)]
+    pub defines: bool,
+}
+
+/// A rule to rewrite the arguments of the output entries.
+///
+/// Rules are applied in the order of the configuration to each flag of the
+/// entry (the compiler executable, the source and the output are not subject
+/// to rewriting). Each rule sees the result of the previous ones.
+///
+/// - `remove` drops the matching flags,
+/// - `replace` substitutes the matching flags with `arguments` (for `regex`
+///   matches the replacement can refer to capture groups, like `$1`),
+/// - `prepend` inserts `arguments` right after the compiler executable,
+/// - `append` adds `arguments` to the end of the flags.
+///
+/// When `compiler` is given, the rule applies only to commands of that compiler type.
+#[derive(Clone, Debug, PartialEq, serde::Deserialize, serde::Serialize)]
+pub struct ArgumentRule {
+    pub action: ArgumentAction,
+    #[serde(default, rename = "match", skip_serializing_if = "Option::is_none")]
+    pub match_: Option<ArgumentMatch>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub arguments: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub compiler: Option<CompilerType>,
+}
+
+/// Action of an argument rewrite rule.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ArgumentAction {
+    Remove,
+    Replace,
+    Append,
+    Prepend,
+}
+
+/// How an argument rewrite rule selects the flags.
+#[derive(Clone, Debug, Eq, PartialEq, serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ArgumentMatch {
+    /// The flag is equal to the given string.
+    Exact(String),
+    /// The flag starts with the given string.
+    Prefix(String),
+    /// The whole flag matches the given regular expression.
+    Regex(String),
+}
+
+/// A rule to substitute the compiler executable (`arguments[0]`) of the output entries.
+///
+/// A rule selects commands by the compiler `path` and/or by the recognized
+/// `compiler` type (when both are given, both have to match). The first
+/// matching rule wins. The rest of the arguments are left untouched.
+#[derive(Clone, Debug, PartialEq, serde::Deserialize, serde::Serialize)]
+pub struct ExecutableRule {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path: Option<PathBuf>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub compiler: Option<CompilerType>,
+    pub replacement: ExecutableReplacement,
+}
+
+/// What replaces the compiler executable in the output entries.
+#[derive(Clone, Debug, Eq, PartialEq, serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExecutableReplacement {
+    /// Only the file name of the executable (`/usr/lib/ccache/gcc` becomes `gcc`).
+    Basename,
+    /// The given executable.
+    Path(PathBuf),
+}
+
 pub(crate) const SUPPORTED_SCHEMA_VERSION: &str = "4.1";
 
 fn default_enabled() -> bool {
@@ -21,6 +21,10 @@ pub enum ValidationError {
     DuplicateEntry { field: &'static str, idx: usize },
     #[error("Path format error: {message}")]
     PathFormatError { message: &'static str },
+    #[error("Argument rule error at: {idx}: {message}")]
+    ArgumentRuleError { idx: usize, message: String },
+    #[error("Executable rule error at: {idx}: {message}")]
+    ExecutableRuleError { idx: usize, message: &'static str },
     #[error("Multiple validation errors: {errors:?}")]
     Multiple { errors: Vec<ValidationError> },
 }
@@ -90,6 +94,20 @@ impl Validator<Main> for Main {
         // Validate path format configuration
         collector.add_result(PathFormat::validate(&config.format.paths));
 
+        // Validate argument rewrite rules
+        collector.add_result(ArgumentRule::validate(&config.format.arguments));
+
+        // Validate executable substitution rules
+        collector.add_result(ExecutableRule::validate(&config.format.executables));
+
+        // Validate output routes
+        collector.add_result(OutputRoute::validate(&config.outputs));
+
+        // Validate the link command database path
+        if config.link_commands.enabled && config.link_commands.path.as_os_str().is_empty() {
+            collector.add(ValidationError::EmptyString { field: "link_commands.path".to_string() });
+        }
+
         collector.finish()
     }
 }
@@ -128,6 +146,55 @@ impl Validator<SourceFilter> for SourceFilter {
     }
 }
 
+impl Validator<Vec<OutputRoute>> for OutputRoute {
+    type Error = ValidationError;
+
+    /// Validates that each route has a distinct file path and name, non-empty
+    /// source rule paths and valid duplicate filter and format overrides.
+    fn validate(config: &Vec<OutputRoute>) -> Result<(), Self::Error> {
+        let mut collector = ValidationCollector::new();
+
+        let mut seen_paths = std::collections::HashSet::new();
+        let mut seen_names = std::collections::HashSet::new();
+        for (idx, route) in config.iter().enumerate() {
+            if route.path.as_os_str().is_empty() {
+                collector.add(ValidationError::EmptyString { field: format!("outputs[{}].path", idx) });
+            } else if !seen_paths.insert(&route.path) {
+                collector.add(ValidationError::DuplicateEntry { field: "outputs", idx });
+            }
+            match &route.name {
+                Some(name) if name.is_empty() => {
+                    collector.add(ValidationError::EmptyString { field: format!("outputs[{}].name", idx) });
+                }
+                Some(name) if !seen_names.insert(name) => {
+                    collector.add(ValidationError::DuplicateEntry { field: "outputs.name", idx });
+                }
+                _ => {}
+            }
+            for (rule_idx, rule) in route.sources.directories.iter().enumerate() {
+                if rule.path.as_os_str().is_empty() {
+                    collector.add(ValidationError::EmptyString {
+                        field: format!("outputs[{}].sources.directories[{}].path", idx, rule_idx),
+                    });
+                }
+            }
+            if let Some(duplicates) = &route.duplicates {
+                collector.add_result(DuplicateFilter::validate(duplicates));
+            }
+            if let Some(format) = &route.format {
+                collector.add_result(PathFormat::validate(&format.paths));
+                collector.add_result(ArgumentRule::validate(&format.arguments));
+                collector.add_result(ExecutableRule::validate(&format.executables));
+            }
+            if let Some(paths) = &route.paths {
+                collector.add_result(PathFormat::validate(paths));
+            }
+        }
+
+        collector.finish()
+    }
+}
+
 impl Validator<DuplicateFilter> for DuplicateFilter {
     type Error = ValidationError;
 
@@ -153,10 +220,42 @@ impl Validator<PathFormat> for PathFormat {
     /// - When directory is relative, file must be relative too
     /// - When directory is canonical, file can't be absolute
     /// - When directory is absolute, file can't be canonical
+    /// - When directory is root-relative, file must be relative
+    /// - Root-relative is only valid for the directory
+    /// - Remap rules need both prefixes
+    /// - Root-relative directory and the placeholder need the project root
     fn validate(config: &PathFormat) -> Result<(), Self::Error> {
         use PathResolver::*;
 
-        match (&config.directory, &config.file) {
+        let mut collector = ValidationCollector::new();
+        for (idx, rule) in config.remap.iter().enumerate() {
+            if rule.from.as_os_str().is_empty() {
+                collector
+                    .add(ValidationError::EmptyString { field: format!("format.paths.remap[{}].from", idx) });
+            }
+            if rule.to.as_os_str().is_empty() {
+                collector
+                    .add(ValidationError::EmptyString { field: format!("format.paths.remap[{}].to", idx) });
+            }
+        }
+
+        if config.root_placeholder.as_ref().is_some_and(|placeholder| placeholder.is_empty()) {
+            collector
+                .add(ValidationError::EmptyString { field: "format.paths.root_placeholder".to_string() });
+        }
+        if config.needs_root() && config.root.is_none() {
+            collector.add(ValidationError::PathFormatError {
+                message: "Root-relative directory and root placeholder need the project root",
+            });
+        }
+
+        let resolvers = match (&config.directory, &config.file) {
+            (_, RootRelative) => Err(ValidationError::PathFormatError {
+                message: "Root-relative is only valid for the directory",
+            }),
+            (RootRelative, AsIs | Absolute | Canonical) => Err(ValidationError::PathFormatError {
+                message: "When directory is root-relative, file must be relative",
+            }),
             (Relative, Absolute | Canonical) => Err(ValidationError::PathFormatError {
                 message: "When directory is relative, file must be relative too",
             }),
@@ -170,10 +269,93 @@ impl Validator<PathFormat> for PathFormat {
                 message: "When directory as-is, file should be the same",
             }),
             _ => Ok(()),
+        };
+        collector.add_result(resolvers);
+
+        collector.finish()
+    }
+}
+
+impl Validator<Vec<ArgumentRule>> for ArgumentRule {
+    type Error = ValidationError;
+
+    /// Validates that each rule has the fields its action requires, and
+    /// that the regular expressions compile.
+    fn validate(config: &Vec<ArgumentRule>) -> Result<(), Self::Error> {
+        let mut collector = ValidationCollector::new();
+
+        for (idx, rule) in config.iter().enumerate() {
+            if let Err(message) = Self::check(rule) {
+                collector.add(ValidationError::ArgumentRuleError { idx, message });
+            }
+        }
+
+        collector.finish()
+    }
+}
+
+impl ArgumentRule {
+    fn check(rule: &ArgumentRule) -> Result<(), String> {
+        use ArgumentAction::*;
+
+        match (rule.action, &rule.match_) {
+            (Remove | Replace, None) => return Err("'remove' and 'replace' rules need a 'match'".into()),
+            (Append | Prepend, Some(_)) => {
+                return Err("'append' and 'prepend' rules can't have a 'match'".into());
+            }
+            _ => {}
+        }
+        match rule.action {
+            Remove if !rule.arguments.is_empty() => {
+                return Err("'remove' rules can't have 'arguments'".into());
+            }
+            Replace | Append | Prepend if rule.arguments.is_empty() => {
+                return Err("'replace', 'append' and 'prepend' rules need 'arguments'".into());
+            }
+            _ => {}
+        }
+        match &rule.match_ {
+            Some(ArgumentMatch::Exact(value) | ArgumentMatch::Prefix(value)) if value.is_empty() => {
+                Err("empty 'match' value".into())
+            }
+            Some(ArgumentMatch::Regex(pattern)) => {
+                regex::Regex::new(pattern).map(|_| ()).map_err(|error| error.to_string())
+            }
+            _ => Ok(()),
         }
     }
 }
 
+impl Validator<Vec<ExecutableRule>> for ExecutableRule {
+    type Error = ValidationError;
+
+    /// Validates that each rule selects the commands by something, and that
+    /// the paths are not empty.
+    fn validate(config: &Vec<ExecutableRule>) -> Result<(), Self::Error> {
+        let mut collector = ValidationCollector::new();
+
+        for (idx, rule) in config.iter().enumerate() {
+            if rule.path.is_none() && rule.compiler.is_none() {
+                collector.add(ValidationError::ExecutableRuleError {
+                    idx,
+                    message: "rules need a 'path' or a 'compiler' to match",
+                });
+            }
+            if rule.path.as_ref().is_some_and(|path| path.as_os_str().is_empty()) {
+                collector.add(ValidationError::ExecutableRuleError { idx, message: "empty 'path' value" });
+            }
+            if let ExecutableReplacement::Path(path) = &rule.replacement
+                && path.as_os_str().is_empty()
+            {
+                collector
+                    .add(ValidationError::ExecutableRuleError { idx, message: "empty 'replacement' path" });
+            }
+        }
+
+        collector.finish()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -264,6 +446,7 @@ mod tests {
     fn test_validate_duplicate_filter_no_duplicates() {
         let config = DuplicateFilter {
             match_on: vec![OutputFields::File, OutputFields::Arguments, OutputFields::Directory],
+            ..DuplicateFilter::default()
         };
 
         let result = DuplicateFilter::validate(&config);
@@ -274,6 +457,7 @@ mod tests {
     fn test_validate_duplicate_filter_with_duplicates() {
         let config = DuplicateFilter {
             match_on: vec![OutputFields::File, OutputFields::Arguments, OutputFields::File],
+            ..DuplicateFilter::default()
         };
 
         let result = DuplicateFilter::validate(&config);
@@ -298,6 +482,7 @@ mod tests {
                 OutputFields::Directory,
                 OutputFields::Arguments,
             ],
+            ..DuplicateFilter::default()
         };
 
         let result = DuplicateFilter::validate(&config);
@@ -313,7 +498,7 @@ mod tests {
 
     #[test]
     fn test_validate_duplicate_filter_empty_match_on() {
-        let config = DuplicateFilter { match_on: vec![] };
+        let config = DuplicateFilter { match_on: vec![], ..DuplicateFilter::default() };
 
         let result = DuplicateFilter::validate(&config);
         assert!(result.is_ok());
@@ -322,11 +507,40 @@ mod tests {
     #[test]
     fn test_validate_path_format_success() {
         let valid_configs = vec![
-            PathFormat { directory: PathResolver::AsIs, file: PathResolver::AsIs },
-            PathFormat { directory: PathResolver::Relative, file: PathResolver::Relative },
-            PathFormat { directory: PathResolver::Canonical, file: PathResolver::Relative },
-            PathFormat { directory: PathResolver::Absolute, file: PathResolver::Relative },
-            PathFormat { directory: PathResolver::Absolute, file: PathResolver::Absolute },
+            PathFormat { directory: PathResolver::AsIs, file: PathResolver::AsIs, ..PathFormat::default() },
+            PathFormat {
+                directory: PathResolver::Relative,
+                file: PathResolver::Relative,
+                ..PathFormat::default()
+            },
+            PathFormat {
+                directory: PathResolver::Canonical,
+                file: PathResolver::Relative,
+                ..PathFormat::default()
+            },
+            PathFormat {
+                directory: PathResolver::Absolute,
+                file: PathResolver::Relative,
+                ..PathFormat::default()
+            },
+            PathFormat {
+                directory: PathResolver::Absolute,
+                file: PathResolver::Absolute,
+                ..PathFormat::default()
+            },
+            PathFormat {
+                directory: PathResolver::RootRelative,
+                file: PathResolver::Relative,
+                root: Some(PathBuf::from("/project")),
+                ..PathFormat::default()
+            },
+            PathFormat {
+                directory: PathResolver::Absolute,
+                file: PathResolver::Absolute,
+                root: Some(PathBuf::from("/project")),
+                root_placeholder: Some("${root}".to_string()),
+                ..PathFormat::default()
+            },
         ];
 
         for config in valid_configs {
@@ -338,25 +552,71 @@ mod tests {
     fn test_validate_path_format_failures() {
         let invalid_configs = vec![
             (
-                PathFormat { directory: PathResolver::Relative, file: PathResolver::Absolute },
+                PathFormat {
+                    directory: PathResolver::Relative,
+                    file: PathResolver::Absolute,
+                    ..PathFormat::default()
+                },
                 "When directory is relative, file must be relative too",
             ),
             (
-                PathFormat { directory: PathResolver::Relative, file: PathResolver::Canonical },
+                PathFormat {
+                    directory: PathResolver::Relative,
+                    file: PathResolver::Canonical,
+                    ..PathFormat::default()
+                },
                 "When directory is relative, file must be relative too",
             ),
             (
-                PathFormat { directory: PathResolver::Canonical, file: PathResolver::Absolute },
+                PathFormat {
+                    directory: PathResolver::Canonical,
+                    file: PathResolver::Absolute,
+                    ..PathFormat::default()
+                },
                 "When directory is canonical, file can't be absolute",
             ),
             (
-                PathFormat { directory: PathResolver::Absolute, file: PathResolver::Canonical },
+                PathFormat {
+                    directory: PathResolver::Absolute,
+                    file: PathResolver::Canonical,
+                    ..PathFormat::default()
+                },
                 "When directory is absolute, file can't be canonical",
             ),
             (
-                PathFormat { directory: PathResolver::AsIs, file: PathResolver::Canonical },
+                PathFormat {
+                    directory: PathResolver::AsIs,
+                    file: PathResolver::Canonical,
+                    ..PathFormat::default()
+                },
                 "When directory as-is, file should be the same",
             ),
+            (
+                PathFormat {
+                    directory: PathResolver::Absolute,
+                    file: PathResolver::RootRelative,
+                    root: Some(PathBuf::from("/project")),
+                    ..PathFormat::default()
+                },
+                "Root-relative is only valid for the directory",
+            ),
+            (
+                PathFormat {
+                    directory: PathResolver::RootRelative,
+                    file: PathResolver::Absolute,
+                    root: Some(PathBuf::from("/project")),
+                    ..PathFormat::default()
+                },
+                "When directory is root-relative, file must be relative",
+            ),
+            (
+                PathFormat {
+                    directory: PathResolver::RootRelative,
+                    file: PathResolver::Relative,
+                    ..PathFormat::default()
+                },
+                "Root-relative directory and root placeholder need the project root",
+            ),
         ];
 
         for (config, expected_error) in invalid_configs {
@@ -369,4 +629,204 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn test_validate_path_format_remap() {
+        let config = PathFormat {
+            remap: vec![
+                PathRemap { from: PathBuf::from("/work"), to: PathBuf::from("/home/dev/project") },
+                PathRemap { from: PathBuf::new(), to: PathBuf::from("/home/dev/project") },
+            ],
+            ..PathFormat::default()
+        };
+
+        match PathFormat::validate(&config) {
+            Err(ValidationError::EmptyString { field }) => assert_eq!(field, "format.paths.remap[1].from"),
+            other => panic!("Expected EmptyString error, got: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_validate_argument_rules_success() {
+        let rules = vec![
+            ArgumentRule {
+                action: ArgumentAction::Remove,
+                match_: Some(ArgumentMatch::Exact("-Werror".into())),
+                arguments: vec![],
+                compiler: None,
+            },
+            ArgumentRule {
+                action: ArgumentAction::Replace,
+                match_: Some(ArgumentMatch::Regex("^-std=gnu\\+\\+(.*)$".into())),
+                arguments: vec!["-std=c++$1".into()],
+                compiler: Some(CompilerType::Gcc),
+            },
+            ArgumentRule {
+                action: ArgumentAction::Append,
+                match_: None,
+                arguments: vec!["-Wno-unknown-warning-option".into()],
+                compiler: None,
+            },
+        ];
+
+        assert!(ArgumentRule::validate(&rules).is_ok());
+    }
+
+    #[test]
+    fn test_validate_argument_rules_failures() {
+        let rules = vec![
+            // remove without match
+            ArgumentRule { action: ArgumentAction::Remove, match_: None, arguments: vec![], compiler: None },
+            // append with match
+            ArgumentRule {
+                action: ArgumentAction::Append,
+                match_: Some(ArgumentMatch::Prefix("-W".into())),
+                arguments: vec!["-w".into()],
+                compiler: None,
+            },
+            // replace without arguments
+            ArgumentRule {
+                action: ArgumentAction::Replace,
+                match_: Some(ArgumentMatch::Exact("-w".into())),
+                arguments: vec![],
+                compiler: None,
+            },
+            // invalid regex
+            ArgumentRule {
+                action: ArgumentAction::Remove,
+                match_: Some(ArgumentMatch::Regex("(".into())),
+                arguments: vec![],
+                compiler: None,
+            },
+        ];
+
+        match ArgumentRule::validate(&rules) {
+            Err(ValidationError::Multiple { errors }) => {
+                let indexes: Vec<usize> = errors
+                    .iter()
+                    .map(|error| match error {
+                        ValidationError::ArgumentRuleError { idx, .. } => *idx,
+                        other => panic!("Expected ArgumentRuleError, got: {:?}", other),
+                    })
+                    .collect();
+                assert_eq!(indexes, vec![0, 1, 2, 3]);
+            }
+            other => panic!("Expected multiple errors, got: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_validate_executable_rules() {
+        let valid = vec![
+            ExecutableRule {
+                path: Some(PathBuf::from("/usr/lib/ccache/gcc")),
+                compiler: None,
+                replacement: ExecutableReplacement::Path(PathBuf::from("/usr/bin/gcc")),
+            },
+            ExecutableRule {
+                path: None,
+                compiler: Some(CompilerType::Clang),
+                replacement: ExecutableReplacement::Basename,
+            },
+        ];
+        assert!(ExecutableRule::validate(&valid).is_ok());
+
+        let invalid = vec![
+            // matches nothing
+            ExecutableRule { path: None, compiler: None, replacement: ExecutableReplacement::Basename },
+            // empty replacement
+            ExecutableRule {
+                path: Some(PathBuf::from("/usr/bin/cc")),
+                compiler: None,
+                replacement: ExecutableReplacement::Path(PathBuf::new()),
+            },
+        ];
+        match ExecutableRule::validate(&invalid) {
+            Err(ValidationError::Multiple { errors }) => assert_eq!(errors.len(), 2),
+            other => panic!("Expected multiple errors, got: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_validate_output_routes() {
+        let route = |path: &str| OutputRoute {
+            path: PathBuf::from(path),
+            sources: SourceFilter {
+                directories: vec![DirectoryRule {
+                    path: PathBuf::from("frontend"),
+                    action: DirectoryAction::Include,
+                }],
+            },
+            ..OutputRoute::default()
+        };
+        assert!(OutputRoute::validate(&vec![route("a.json"), route("b.json")]).is_ok());
+
+        let invalid = vec![
+            // duplicate path
+            route("a.json"),
+            route("a.json"),
+            // empty path and empty rule path
+            OutputRoute {
+                sources: SourceFilter {
+                    directories: vec![DirectoryRule {
+                        path: PathBuf::new(),
+                        action: DirectoryAction::Exclude,
+                    }],
+                },
+                ..OutputRoute::default()
+            },
+            // invalid path format override
+            OutputRoute {
+                paths: Some(PathFormat {
+                    directory: PathResolver::Relative,
+                    file: PathResolver::Absolute,
+                    ..PathFormat::default()
+                }),
+                ..route("c.json")
+            },
+        ];
+        match OutputRoute::validate(&invalid) {
+            Err(ValidationError::Multiple { errors }) => assert_eq!(errors.len(), 4),
+            other => panic!("Expected multiple errors, got: {:?}", other),
+        }
+
+        let invalid = vec![
+            // duplicate name
+            OutputRoute { name: Some("clangd".into()), ..route("a.json") },
+            OutputRoute { name: Some("clangd".into()), ..route("b.json") },
+            // empty name
+            OutputRoute { name: Some(String::new()), ..route("c.json") },
+            // invalid duplicate filter and format overrides
+            OutputRoute {
+                duplicates: Some(DuplicateFilter {
+                    match_on: vec![OutputFields::File, OutputFields::File],
+                    ..DuplicateFilter::default()
+                }),
+                format: Some(Format {
+                    paths: PathFormat {
+                        directory: PathResolver::Canonical,
+                        file: PathResolver::Absolute,
+                        ..PathFormat::default()
+                    },
+                    ..Format::default()
+                }),
+                ..route("d.json")
+            },
+        ];
+        match OutputRoute::validate(&invalid) {
+            Err(ValidationError::Multiple { errors }) => assert_eq!(errors.len(), 4),
+            other => panic!("Expected multiple errors, got: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_validate_link_commands_path() {
+        let config =
+            Main { link_commands: LinkCommands { enabled: true, path: PathBuf::new() }, ..Main::default() };
+        assert!(matches!(Main::validate(&config), Err(ValidationError::EmptyString { .. })));
+
+        let config =
+            Main { link_commands: LinkCommands { enabled: false, path: PathBuf::new() }, ..Main::default() };
+        assert!(Main::validate(&config).is_ok());
+    }
 }
@@ -0,0 +1,555 @@
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! Import of Bazel action graphs as execution events.
+//!
+//! Bazel runs the compilers in sandboxes, remote executors or persistent
+//! workers, where the interception can not follow them reliably. But Bazel
+//! can print the actions of the build without running it:
+//!
+//! ```sh
+//! bazel aquery --output=jsonproto 'mnemonic("CppCompile", //...)' > actions.json
+//! bazel aquery --output=textproto 'mnemonic("CppCompile", //...)' > actions.txtpb
+//! ```
+//!
+//! The output is an `ActionGraphContainer` message, in the JSON mapping or
+//! in the text format of protocol buffers. Only the `actions` are read from
+//! it; the C and C++ compilations (`CppCompile` mnemonic) become events,
+//! other actions are ignored.
+//!
+//! The arguments of the actions are relative to the execution root of the
+//! workspace (`bazel info execution_root`), which is the working directory
+//! of the events. The environment of the events is the environment of the
+//! action.
+
+use super::{Event, Execution};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::io;
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+
+/// The mnemonics of the actions which are turned into events.
+const COMPILE_MNEMONICS: [&str; 1] = ["CppCompile"];
+
+/// The process id of the events. The actions are not processes of the build.
+const ACTION_PID: u32 = 0;
+
+/// Errors that can occur while reading an action graph.
+#[derive(Error, Debug)]
+pub enum AqueryError {
+    #[error("Failed to read action graph: {0}")]
+    Io(#[from] io::Error),
+    #[error("Invalid JSON action graph: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("Invalid text action graph at line {line}: {message}")]
+    Text { line: usize, message: String },
+}
+
+/// Reads the events from the `--output=jsonproto` output of `bazel aquery`.
+///
+/// The `execroot` is the execution root of the workspace.
+pub fn read_json_events(reader: impl io::Read, execroot: &Path) -> Result<Vec<Event>, AqueryError> {
+    let container: ActionGraphContainer = serde_json::from_reader(reader)?;
+    Ok(events(container.actions, execroot))
+}
+
+/// Reads the events from the `--output=textproto` output of `bazel aquery`.
+///
+/// The `execroot` is the execution root of the workspace.
+pub fn read_text_events(mut reader: impl io::Read, execroot: &Path) -> Result<Vec<Event>, AqueryError> {
+    let mut content = String::new();
+    reader.read_to_string(&mut content)?;
+
+    let fields = TextParser::new(&content).message(None)?;
+    let actions = fields
+        .into_iter()
+        .filter(|(name, _)| name == "actions")
+        .filter_map(|(_, value)| match value {
+            TextValue::Message(fields) => Some(Action::from(fields)),
+            TextValue::Scalar(_) => None,
+        })
+        .collect();
+    Ok(events(actions, execroot))
+}
+
+/// Turns the compile actions into events.
+fn events(actions: Vec<Action>, execroot: &Path) -> Vec<Event> {
+    let total = actions.len();
+    let events: Vec<Event> = actions.into_iter().filter_map(|action| action.into_event(execroot)).collect();
+    log::debug!("Imported {} compile actions out of {} actions", events.len(), total);
+    events
+}
+
+/// The `ActionGraphContainer` message, without the fields which are not used.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct ActionGraphContainer {
+    actions: Vec<Action>,
+}
+
+/// The `Action` message, without the fields which are not used.
+#[derive(Debug, Default, Deserialize, PartialEq)]
+#[serde(default, rename_all = "camelCase")]
+struct Action {
+    mnemonic: String,
+    arguments: Vec<String>,
+    #[serde(alias = "environment_variables")]
+    environment_variables: Vec<KeyValuePair>,
+}
+
+/// The `KeyValuePair` message.
+#[derive(Debug, Default, Deserialize, PartialEq)]
+#[serde(default)]
+struct KeyValuePair {
+    key: String,
+    value: String,
+}
+
+impl Action {
+    /// Creates the event of a compile action.
+    fn into_event(self, execroot: &Path) -> Option<Event> {
+        if !COMPILE_MNEMONICS.contains(&self.mnemonic.as_str()) {
+            return None;
+        }
+        let Some(program) = self.arguments.first() else {
+            log::warn!("Skipping {} action: missing command line", self.mnemonic);
+            return None;
+        };
+        // Bare program names are resolved from `PATH` by the semantic analysis.
+        let executable = if Path::new(program).components().count() > 1 {
+            execroot.join(program)
+        } else {
+            PathBuf::from(program)
+        };
+        let environment: HashMap<String, String> =
+            self.environment_variables.into_iter().map(|pair| (pair.key, pair.value)).collect();
+
+        let execution = Execution {
+            executable,
+            arguments: self.arguments,
+            working_dir: execroot.to_path_buf(),
+            environment,
+        };
+        Some(Event { pid: ACTION_PID, execution: execution.trim() })
+    }
+}
+
+impl From<Vec<(String, TextValue)>> for Action {
+    fn from(fields: Vec<(String, TextValue)>) -> Self {
+        let mut action = Action::default();
+        for (name, value) in fields {
+            match (name.as_str(), value) {
+                ("mnemonic", TextValue::Scalar(value)) => action.mnemonic = value,
+                ("arguments", TextValue::Scalar(value)) => action.arguments.push(value),
+                ("environment_variables", TextValue::Message(fields)) => {
+                    let mut pair = KeyValuePair::default();
+                    for (name, value) in fields {
+                        match (name.as_str(), value) {
+                            ("key", TextValue::Scalar(value)) => pair.key = value,
+                            ("value", TextValue::Scalar(value)) => pair.value = value,
+                            _ => {}
+                        }
+                    }
+                    action.environment_variables.push(pair);
+                }
+                _ => {}
+            }
+        }
+        action
+    }
+}
+
+/// A field value of the protocol buffers text format.
+#[derive(Debug, PartialEq)]
+enum TextValue {
+    /// A string (unescaped), number or enum value.
+    Scalar(String),
+    /// A nested message with its fields in order.
+    Message(Vec<(String, TextValue)>),
+}
+
+/// Parser of the protocol buffers text format.
+///
+/// Supports the subset which is needed to read the messages: scalar and
+/// message fields, repeated values in brackets, comments and the optional
+/// field separators. The schema is not known, the values are kept as text.
+struct TextParser {
+    chars: Vec<char>,
+    position: usize,
+    line: usize,
+}
+
+impl TextParser {
+    fn new(content: &str) -> Self {
+        Self { chars: content.chars().collect(), position: 0, line: 1 }
+    }
+
+    fn error(&self, message: impl Into<String>) -> AqueryError {
+        AqueryError::Text { line: self.line, message: message.into() }
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.position).copied()
+    }
+
+    fn next(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.position += 1;
+        if c == '\n' {
+            self.line += 1;
+        }
+        Some(c)
+    }
+
+    /// Skips the whitespaces and the comments.
+    fn skip_whitespace(&mut self) {
+        while let Some(c) = self.peek() {
+            match c {
+                '#' => {
+                    while self.peek().is_some_and(|c| c != '\n') {
+                        self.next();
+                    }
+                }
+                c if c.is_whitespace() => {
+                    self.next();
+                }
+                _ => return,
+            }
+        }
+    }
+
+    /// Consumes the character if it follows, returns whether it was there.
+    fn consume(&mut self, expected: char) -> bool {
+        self.skip_whitespace();
+        let found = self.peek() == Some(expected);
+        if found {
+            self.next();
+        }
+        found
+    }
+
+    /// Parses the fields of a message until the closing character, or until
+    /// the end of the input for the top level message.
+    fn message(&mut self, close: Option<char>) -> Result<Vec<(String, TextValue)>, AqueryError> {
+        let mut fields = vec![];
+        loop {
+            self.skip_whitespace();
+            match (self.peek(), close) {
+                (None, None) => return Ok(fields),
+                (None, Some(close)) => return Err(self.error(format!("missing '{close}'"))),
+                (Some(c), Some(close)) if c == close => {
+                    self.next();
+                    return Ok(fields);
+                }
+                _ => {}
+            }
+
+            let name = self.token();
+            if name.is_empty() {
+                return Err(self.error("expected field name"));
+            }
+            let colon = self.consume(':');
+            if self.consume('[') {
+                // Repeated values: `name: [value, value]`.
+                while !self.consume(']') {
+                    let value = self.value(colon)?;
+                    fields.push((name.clone(), value));
+                    self.consume(',');
+                }
+            } else {
+                let value = self.value(colon)?;
+                fields.push((name, value));
+            }
+            if !self.consume(',') {
+                self.consume(';');
+            }
+        }
+    }
+
+    /// Parses a scalar or message value. The scalar values must follow a colon.
+    fn value(&mut self, colon: bool) -> Result<TextValue, AqueryError> {
+        if self.consume('{') {
+            return self.message(Some('}')).map(TextValue::Message);
+        }
+        if self.consume('<') {
+            return self.message(Some('>')).map(TextValue::Message);
+        }
+        if !colon {
+            return Err(self.error("expected ':' before scalar value"));
+        }
+        self.skip_whitespace();
+        match self.peek() {
+            Some('"' | '\'') => {
+                // Adjacent string literals are concatenated.
+                let mut bytes = vec![];
+                while let Some(quote @ ('"' | '\'')) = self.peek() {
+                    self.next();
+                    self.string(quote, &mut bytes)?;
+                    self.skip_whitespace();
+                }
+                Ok(TextValue::Scalar(String::from_utf8_lossy(&bytes).into_owned()))
+            }
+            _ => {
+                let token = self.token();
+                if token.is_empty() {
+                    return Err(self.error("expected value"));
+                }
+                Ok(TextValue::Scalar(token))
+            }
+        }
+    }
+
+    /// Parses an identifier or a number.
+    fn token(&mut self) -> String {
+        self.skip_whitespace();
+        let mut token = String::new();
+        while let Some(c) =
+            self.peek().filter(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '.' | '-' | '+'))
+        {
+            token.push(c);
+            self.next();
+        }
+        token
+    }
+
+    /// Parses the content of a string literal after the opening quote.
+    fn string(&mut self, quote: char, bytes: &mut Vec<u8>) -> Result<(), AqueryError> {
+        loop {
+            let c = self.next().ok_or_else(|| self.error("unterminated string"))?;
+            match c {
+                c if c == quote => return Ok(()),
+                '\n' => return Err(self.error("unterminated string")),
+                '\\' => {
+                    let c = self.next().ok_or_else(|| self.error("unterminated string"))?;
+                    match c {
+                        'a' => bytes.push(0x07),
+                        'b' => bytes.push(0x08),
+                        'f' => bytes.push(0x0c),
+                        'n' => bytes.push(b'\n'),
+                        'r' => bytes.push(b'\r'),
+                        't' => bytes.push(b'\t'),
+                        'v' => bytes.push(0x0b),
+                        'x' => {
+                            let value = self.digits(16, 2).ok_or_else(|| self.error("invalid escape"))?;
+                            bytes.push(value as u8);
+                        }
+                        'u' | 'U' => {
+                            let length = if c == 'u' { 4 } else { 8 };
+                            let c = self
+                                .digits(16, length)
+                                .and_then(char::from_u32)
+                                .ok_or_else(|| self.error("invalid escape"))?;
+                            push_char(bytes, c);
+                        }
+                        '0'..='7' => {
+                            self.position -= 1;
+                            let value = self.digits(8, 3).ok_or_else(|| self.error("invalid escape"))?;
+                            bytes.push(value as u8);
+                        }
+                        c => push_char(bytes, c),
+                    }
+                }
+                c => push_char(bytes, c),
+            }
+        }
+    }
+
+    /// Parses at most the given number of digits in the radix.
+    fn digits(&mut self, radix: u32, length: usize) -> Option<u32> {
+        let mut value = None;
+        for _ in 0..length {
+            match self.peek().and_then(|c| c.to_digit(radix)) {
+                Some(digit) => {
+                    value = Some(value.unwrap_or(0) * radix + digit);
+                    self.position += 1;
+                }
+                None => break,
+            }
+        }
+        value
+    }
+}
+
+fn push_char(bytes: &mut Vec<u8>, c: char) {
+    let mut buffer = [0; 4];
+    bytes.extend_from_slice(c.encode_utf8(&mut buffer).as_bytes());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXECROOT: &str = "/home/user/.cache/bazel/_bazel_user/1234/execroot/_main";
+
+    fn compile_event() -> Event {
+        Event {
+            pid: ACTION_PID,
+            execution: Execution::from_strings(
+                &format!("{EXECROOT}/external/toolchain/bin/clang"),
+                vec![
+                    "external/toolchain/bin/clang",
+                    "-DNAME=\"my lib\"",
+                    "-iquote",
+                    ".",
+                    "-c",
+                    "src/main.cc",
+                    "-o",
+                    "bazel-out/k8-fastbuild/bin/_objs/main/main.o",
+                ],
+                EXECROOT,
+                HashMap::from([("CPATH", "/opt/include")]),
+            ),
+        }
+    }
+
+    // Requirements: interception-bazel-aquery-import
+    #[test]
+    fn test_read_json_events() {
+        let content = r#"{
+          "artifacts": [{ "id": 1, "pathFragmentId": 1 }],
+          "actions": [{
+            "targetId": 1,
+            "actionKey": "7f6b",
+            "mnemonic": "CppCompile",
+            "configurationId": 1,
+            "arguments": [
+              "external/toolchain/bin/clang",
+              "-DNAME=\"my lib\"",
+              "-iquote",
+              ".",
+              "-c",
+              "src/main.cc",
+              "-o",
+              "bazel-out/k8-fastbuild/bin/_objs/main/main.o"
+            ],
+            "environmentVariables": [
+              { "key": "PWD", "value": "/proc/self/cwd" },
+              { "key": "CPATH", "value": "/opt/include" }
+            ],
+            "outputIds": [2],
+            "primaryOutputId": 2
+          }, {
+            "targetId": 1,
+            "mnemonic": "CppLink",
+            "arguments": ["external/toolchain/bin/clang", "-o", "bazel-out/k8-fastbuild/bin/main"]
+          }, {
+            "targetId": 2,
+            "mnemonic": "CppCompile"
+          }],
+          "targets": [{ "id": 1, "label": "//:main" }]
+        }"#;
+
+        let events = read_json_events(content.as_bytes(), Path::new(EXECROOT)).unwrap();
+
+        assert_eq!(events, vec![compile_event()]);
+    }
+
+    // Requirements: interception-bazel-aquery-import
+    #[test]
+    fn test_read_text_events() {
+        let content = r#"
+artifacts {
+  id: 1
+  path_fragment_id: 1
+}
+actions {
+  target_id: 1
+  action_key: "7f6b"
+  mnemonic: "CppCompile"
+  configuration_id: 1
+  arguments: "external/toolchain/bin/clang"
+  arguments: "-DNAME=\"my lib\""
+  arguments: "-iquote"
+  arguments: "."
+  arguments: "-c"
+  arguments: "src/main.cc"
+  arguments: "-o"
+  arguments: "bazel-out/k8-fastbuild/bin/_objs/main/main.o"
+  environment_variables {
+    key: "PWD"
+    value: "/proc/self/cwd"
+  }
+  environment_variables {
+    key: "CPATH"
+    value: "/opt/include"
+  }
+  output_ids: 2
+  primary_output_id: 2
+  execution_platform: "@local_config_platform//:host"
+}
+actions {
+  target_id: 1
+  mnemonic: "CppLink"
+  arguments: "external/toolchain/bin/clang"
+}
+# A comment at the end.
+targets {
+  id: 1
+  label: "//:main"
+}
+"#;
+
+        let events = read_text_events(content.as_bytes(), Path::new(EXECROOT)).unwrap();
+
+        assert_eq!(events, vec![compile_event()]);
+    }
+
+    #[test]
+    fn test_bare_program_name_is_kept() {
+        let action = Action {
+            mnemonic: "CppCompile".into(),
+            arguments: vec!["gcc".into(), "-c".into(), "main.c".into()],
+            environment_variables: vec![],
+        };
+
+        let event = action.into_event(Path::new(EXECROOT)).unwrap();
+
+        assert_eq!(event.execution.executable, PathBuf::from("gcc"));
+        assert_eq!(event.execution.working_dir, PathBuf::from(EXECROOT));
+    }
+
+    #[test]
+    fn test_text_format_syntax() {
+        let content = r#"
+            name: "a" 'b' "\x41\101\né", number: -1.5; enum: VALUE
+            list: ["x", "y"]
+            nested < inner { key: "\"" } >
+        "#;
+
+        let fields = TextParser::new(content).message(None).unwrap();
+
+        let scalar = |value: &str| TextValue::Scalar(value.to_string());
+        assert_eq!(
+            fields,
+            vec![
+                ("name".to_string(), scalar("abAA\né")),
+                ("number".to_string(), scalar("-1.5")),
+                ("enum".to_string(), scalar("VALUE")),
+                ("list".to_string(), scalar("x")),
+                ("list".to_string(), scalar("y")),
+                (
+                    "nested".to_string(),
+                    TextValue::Message(vec![(
+                        "inner".to_string(),
+                        TextValue::Message(vec![("key".to_string(), scalar("\""))])
+                    )])
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_invalid_inputs() {
+        let execroot = Path::new(EXECROOT);
+
+        assert!(matches!(read_json_events("{".as_bytes(), execroot), Err(AqueryError::Json(_))));
+        assert!(matches!(
+            read_text_events("actions {\n  mnemonic: \"CppCompile\"\n".as_bytes(), execroot),
+            Err(AqueryError::Text { line: 3, .. })
+        ));
+        assert!(matches!(
+            read_text_events("actions { mnemonic \"CppCompile\" }".as_bytes(), execroot),
+            Err(AqueryError::Text { line: 1, .. })
+        ));
+    }
+}
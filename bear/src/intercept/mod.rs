@@ -46,8 +46,10 @@
 //!
 //! [`ResolveExecutable`]: crate::semantic::interpreters::resolve::ResolveExecutable
 
+pub mod aquery;
 pub mod environment;
 pub mod reporter;
+pub mod strace;
 pub mod supervise;
 pub mod tcp;
 pub mod wrapper;
@@ -0,0 +1,574 @@
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! Import of `strace` logs as execution events.
+//!
+//! On hosts where neither the preload library nor the compiler wrappers can
+//! be used (setuid helpers, static build tools, locked-down machines), the
+//! build can be traced with `strace`:
+//!
+//! ```sh
+//! strace -f -s 4096 -e trace=process,chdir -o build.log make
+//! ```
+//!
+//! The log is parsed line by line. The successful `execve` calls become
+//! events, the working directory of the processes is reconstructed from the
+//! `chdir` calls. The supported features of the log format:
+//!
+//! - The process id prefix of the `-f` flag (`1234 execve(...)` when written
+//!   to a file, `[pid  1234] execve(...)` when written to the terminal), and
+//!   the timestamps of the `-t`, `-tt` and `-ttt` flags.
+//! - Calls which are interrupted by other processes (`<unfinished ...>`) and
+//!   continued later (`<... execve resumed>`).
+//! - The C-style escapes of the strings (`\"`, `\n`, `\x1b`, `\033`).
+//! - The environment of the `execve` calls, when it is printed (`-v` flag).
+//!
+//! A new process inherits the working directory of its parent. The parent is
+//! known from the `fork`, `vfork` and `clone` calls (traced with the
+//! `trace=process` filter); processes with unknown parent start from the
+//! given initial directory.
+//!
+//! Strings longer than the string size limit (`-s` flag, 32 by default) and
+//! long argument lists are truncated by `strace`. The calls with truncated
+//! arguments are reported and skipped, since the command can not be
+//! reconstructed from them.
+
+use super::{Event, Execution};
+use std::collections::HashMap;
+use std::io;
+use std::path::{Component, Path, PathBuf};
+
+/// The process id of the log lines without process id prefix.
+const UNKNOWN_PID: u32 = 0;
+
+/// Reads the events from an `strace` log.
+///
+/// The `directory` is the working directory of the traced command. Lines
+/// which can not be read or parsed are reported and ignored.
+pub fn read_events(reader: impl io::BufRead, directory: PathBuf) -> impl Iterator<Item = Event> {
+    let mut parser = StraceLogParser::new(directory);
+    reader.lines().filter_map(move |line| match line {
+        Ok(line) => parser.parse_line(&line),
+        Err(error) => {
+            log::warn!("Failed to read strace log: {error}");
+            None
+        }
+    })
+}
+
+/// Parses the lines of an `strace` log, and keeps track of the processes.
+pub struct StraceLogParser {
+    /// The working directory of the processes without known parent.
+    directory: PathBuf,
+    /// The working directory of the processes.
+    working_dirs: HashMap<u32, PathBuf>,
+    /// The beginning of the interrupted calls.
+    unfinished: HashMap<u32, String>,
+    /// The processes which are in an interrupted process creation call.
+    forking: Vec<u32>,
+}
+
+impl StraceLogParser {
+    pub fn new(directory: PathBuf) -> Self {
+        Self { directory, working_dirs: HashMap::new(), unfinished: HashMap::new(), forking: vec![] }
+    }
+
+    /// Parses a line of the log, returns the event of a successful `execve` call.
+    pub fn parse_line(&mut self, line: &str) -> Option<Event> {
+        let (pid, call) = split_prefix(line);
+
+        // Signals, exits and the messages of strace itself.
+        if call.starts_with("---")
+            || call.starts_with("+++")
+            || call.starts_with("strace:")
+            || call.is_empty()
+        {
+            return None;
+        }
+
+        let call = if let Some(rest) = call.strip_prefix("<... ") {
+            // The continuation of an interrupted call: `<... execve resumed>, ...) = 0`
+            let (_, rest) = rest.split_once(" resumed>")?;
+            let beginning = self.unfinished.remove(&pid)?;
+            self.forking.retain(|forking| *forking != pid);
+            beginning + rest
+        } else {
+            call.to_string()
+        };
+        if let Some(beginning) = call.strip_suffix("<unfinished ...>") {
+            let beginning = beginning.trim_end().to_string();
+            if is_process_creation(&beginning) {
+                self.forking.push(pid);
+            }
+            self.unfinished.insert(pid, beginning);
+            return None;
+        }
+
+        let syscall = match Syscall::parse(&call) {
+            Some(syscall) => syscall,
+            None => {
+                log::debug!("Failed to parse strace log line: {line}");
+                return None;
+            }
+        };
+        match syscall.name.as_str() {
+            "execve" if syscall.result == Some(0) => self.execve(pid, syscall.arguments),
+            "chdir" if syscall.result == Some(0) => {
+                if let Some(Value::Str { value, .. }) = syscall.arguments.first() {
+                    let working_dir = resolve(&self.working_dir(pid), Path::new(value));
+                    self.working_dirs.insert(pid, working_dir);
+                }
+                None
+            }
+            "fork" | "vfork" | "clone" | "clone3" => {
+                if let Some(child) = syscall.result.filter(|result| *result > 0) {
+                    let working_dir = self.working_dir(pid);
+                    self.working_dirs.insert(child as u32, working_dir);
+                }
+                None
+            }
+            _ => None,
+        }
+    }
+
+    /// Creates the event of a successful `execve` call.
+    fn execve(&mut self, pid: u32, arguments: Vec<Value>) -> Option<Event> {
+        let mut arguments = arguments.into_iter();
+        let (executable, arguments, environment) = (arguments.next()?, arguments.next()?, arguments.next());
+
+        let working_dir = self.working_dir(pid);
+        let executable = match executable {
+            Value::Str { value, truncated: false } => resolve(&working_dir, Path::new(&value)),
+            _ => {
+                log::warn!("Skipping execve call of process {pid}: truncated executable path");
+                return None;
+            }
+        };
+        let Some(arguments) = strings(arguments) else {
+            log::warn!(
+                "Skipping execve call of {}: truncated arguments (increase the string size with `strace -s`)",
+                executable.display()
+            );
+            return None;
+        };
+        // The environment is printed with the `-v` flag only.
+        let environment = environment
+            .and_then(strings)
+            .unwrap_or_default()
+            .into_iter()
+            .filter_map(|variable| variable.split_once('=').map(|(k, v)| (k.to_string(), v.to_string())))
+            .collect();
+
+        let execution = Execution { executable, arguments, working_dir, environment };
+        Some(Event { pid, execution: execution.trim() })
+    }
+
+    /// Returns the working directory of the process.
+    ///
+    /// A process seen for the first time inherits the working directory of
+    /// the process which is creating a new process (if there is one).
+    fn working_dir(&mut self, pid: u32) -> PathBuf {
+        if let Some(working_dir) = self.working_dirs.get(&pid) {
+            return working_dir.clone();
+        }
+        let working_dir = self
+            .forking
+            .last()
+            .and_then(|parent| self.working_dirs.get(parent))
+            .unwrap_or(&self.directory)
+            .clone();
+        self.working_dirs.insert(pid, working_dir.clone());
+        working_dir
+    }
+}
+
+/// Splits the process id and the optional timestamp from the line.
+fn split_prefix(line: &str) -> (u32, &str) {
+    let line = line.trim();
+    let (pid, rest) = if let Some(rest) = line.strip_prefix("[pid") {
+        match rest.split_once(']') {
+            Some((pid, rest)) => (pid.trim().parse().unwrap_or(UNKNOWN_PID), rest.trim_start()),
+            None => (UNKNOWN_PID, line),
+        }
+    } else {
+        match line.split_once(char::is_whitespace) {
+            Some((pid, rest)) if pid.chars().all(|c| c.is_ascii_digit()) => {
+                (pid.parse().unwrap_or(UNKNOWN_PID), rest.trim_start())
+            }
+            _ => (UNKNOWN_PID, line),
+        }
+    };
+    // The timestamps are digits, colons and dots (`10:20:30.123456`, `1700000000.123`).
+    let rest = match rest.split_once(char::is_whitespace) {
+        Some((timestamp, rest)) if timestamp.chars().all(|c| c.is_ascii_digit() || c == ':' || c == '.') => {
+            rest.trim_start()
+        }
+        _ => rest,
+    };
+    (pid, rest)
+}
+
+/// Tells whether the beginning of a call creates a new process.
+fn is_process_creation(call: &str) -> bool {
+    ["fork(", "vfork(", "clone(", "clone3("].iter().any(|name| call.starts_with(name))
+}
+
+/// Makes the path absolute to the working directory, and removes the `.` and
+/// `..` components lexically.
+fn resolve(working_dir: &Path, path: &Path) -> PathBuf {
+    let mut result = PathBuf::new();
+    for component in working_dir.join(path).components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                result.pop();
+            }
+            other => result.push(other),
+        }
+    }
+    result
+}
+
+/// Returns the strings of an array, or `None` if the array or any of the
+/// strings is truncated.
+fn strings(value: Value) -> Option<Vec<String>> {
+    let Value::Array { values, truncated: false } = value else {
+        return None;
+    };
+    values
+        .into_iter()
+        .map(|value| match value {
+            Value::Str { value, truncated: false } => Some(value),
+            _ => None,
+        })
+        .collect()
+}
+
+/// A parsed system call line: `name(arguments) = result`.
+#[derive(Debug, PartialEq)]
+struct Syscall {
+    name: String,
+    arguments: Vec<Value>,
+    /// The return value, missing when it is not a number (`= ?`).
+    result: Option<i64>,
+}
+
+/// An argument of a system call.
+#[derive(Debug, PartialEq)]
+enum Value {
+    /// A string literal, which is truncated when followed by `...`.
+    Str { value: String, truncated: bool },
+    /// An array, which is truncated when it ends with `...`.
+    Array { values: Vec<Value>, truncated: bool },
+    /// Any other argument (numbers, pointers, flags, structures).
+    Other,
+}
+
+impl Syscall {
+    fn parse(line: &str) -> Option<Self> {
+        let (name, rest) = line.split_once('(')?;
+        if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+            return None;
+        }
+        let mut cursor = Cursor { chars: rest.chars().collect(), position: 0 };
+        let arguments = cursor.values(')')?;
+        let rest = cursor.rest();
+        let result = rest.trim_start().strip_prefix('=')?;
+        let result = result.split_whitespace().next().and_then(|value| value.parse().ok());
+
+        Some(Self { name: name.to_string(), arguments, result })
+    }
+}
+
+/// A position in the arguments of a system call line.
+struct Cursor {
+    chars: Vec<char>,
+    position: usize,
+}
+
+impl Cursor {
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.position).copied()
+    }
+
+    fn next(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.position += 1;
+        Some(c)
+    }
+
+    fn rest(&self) -> String {
+        self.chars[self.position..].iter().collect()
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.peek().is_some_and(char::is_whitespace) {
+            self.position += 1;
+        }
+    }
+
+    /// Consumes `...` when it follows, returns whether it was there.
+    fn ellipsis(&mut self) -> bool {
+        let found = self.chars[self.position..].starts_with(&['.', '.', '.']);
+        if found {
+            self.position += 3;
+        }
+        found
+    }
+
+    /// Parses comma separated values until the closing character.
+    fn values(&mut self, close: char) -> Option<Vec<Value>> {
+        let mut values = vec![];
+        loop {
+            self.skip_whitespace();
+            match self.peek()? {
+                c if c == close => {
+                    self.position += 1;
+                    return Some(values);
+                }
+                ',' => self.position += 1,
+                _ => values.push(self.value(close)?),
+            }
+        }
+    }
+
+    fn value(&mut self, close: char) -> Option<Value> {
+        match self.peek()? {
+            '"' => {
+                self.position += 1;
+                let value = self.string()?;
+                let truncated = self.ellipsis();
+                self.other(close);
+                Some(Value::Str { value, truncated })
+            }
+            '[' => {
+                self.position += 1;
+                let mut values = self.values(']')?;
+                // A truncated array ends with `...`, which is parsed as other value.
+                let truncated = matches!(values.last(), Some(Value::Other));
+                if truncated {
+                    values.pop();
+                }
+                self.other(close);
+                Some(Value::Array { values, truncated })
+            }
+            _ => {
+                self.other(close);
+                Some(Value::Other)
+            }
+        }
+    }
+
+    /// Parses the content of a string literal, after the opening quote.
+    fn string(&mut self) -> Option<String> {
+        let mut bytes = vec![];
+        loop {
+            match self.next()? {
+                '"' => return Some(String::from_utf8_lossy(&bytes).into_owned()),
+                '\\' => match self.next()? {
+                    'n' => bytes.push(b'\n'),
+                    't' => bytes.push(b'\t'),
+                    'r' => bytes.push(b'\r'),
+                    'v' => bytes.push(0x0b),
+                    'f' => bytes.push(0x0c),
+                    'x' => {
+                        let digits: String = (0..2).filter_map(|_| self.next()).collect();
+                        bytes.push(u8::from_str_radix(&digits, 16).ok()?);
+                    }
+                    c @ '0'..='7' => {
+                        let mut value = c.to_digit(8)?;
+                        for _ in 0..2 {
+                            match self.peek().and_then(|c| c.to_digit(8)) {
+                                Some(digit) => {
+                                    value = value * 8 + digit;
+                                    self.position += 1;
+                                }
+                                None => break,
+                            }
+                        }
+                        bytes.push(value as u8);
+                    }
+                    c => {
+                        let mut buffer = [0; 4];
+                        bytes.extend_from_slice(c.encode_utf8(&mut buffer).as_bytes());
+                    }
+                },
+                c => {
+                    let mut buffer = [0; 4];
+                    bytes.extend_from_slice(c.encode_utf8(&mut buffer).as_bytes());
+                }
+            }
+        }
+    }
+
+    /// Skips the rest of a value until the next separator at the same level.
+    ///
+    /// Nested brackets, braces, comments and strings are skipped as a whole.
+    fn other(&mut self, close: char) {
+        let mut depth = 0;
+        while let Some(c) = self.peek() {
+            match c {
+                ',' if depth == 0 => return,
+                c if c == close && depth == 0 => return,
+                '(' | '[' | '{' => depth += 1,
+                ')' | ']' | '}' => depth -= 1,
+                '"' => {
+                    self.position += 1;
+                    let _ = self.string();
+                    continue;
+                }
+                '/' if self.chars.get(self.position + 1) == Some(&'*') => {
+                    let rest = self.rest();
+                    self.position += rest.find("*/").map(|end| end + 2).unwrap_or(rest.len());
+                    continue;
+                }
+                _ => {}
+            }
+            self.position += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(lines: &[&str]) -> Vec<Event> {
+        let log = lines.join("\n");
+        read_events(io::Cursor::new(log), PathBuf::from("/home/user/project")).collect()
+    }
+
+    fn event(pid: u32, executable: &str, arguments: Vec<&str>, working_dir: &str) -> Event {
+        Event::from_strings(pid, executable, arguments, working_dir, HashMap::new())
+    }
+
+    // Requirements: interception-strace-import
+    #[test]
+    fn test_single_process_log() {
+        let events = parse(&[
+            r#"execve("/usr/bin/make", ["make", "all"], 0x7ffc4d5e8a28 /* 30 vars */) = 0"#,
+            r#"chdir("src")                            = 0"#,
+            r#"execve("/usr/bin/cc", ["cc", "-c", "main.c"], 0x55d0c8a0 /* 30 vars */) = 0"#,
+            r#"+++ exited with 0 +++"#,
+        ]);
+
+        assert_eq!(
+            events,
+            vec![
+                event(0, "/usr/bin/make", vec!["make", "all"], "/home/user/project"),
+                event(0, "/usr/bin/cc", vec!["cc", "-c", "main.c"], "/home/user/project/src"),
+            ]
+        );
+    }
+
+    // Requirements: interception-strace-import
+    #[test]
+    fn test_multi_process_log() {
+        let events = parse(&[
+            r#"4200  execve("/usr/bin/make", ["make"], 0x7ffc /* 30 vars */) = 0"#,
+            r#"4200  chdir("/home/user/project/lib") = 0"#,
+            r#"4200  clone(child_stack=NULL, flags=CLONE_CHILD_CLEARTID|SIGCHLD, child_tidptr=0x7f) = 4201"#,
+            r#"4200  vfork( <unfinished ...>"#,
+            r#"4202  execve("./configure", ["./configure"], 0x7ffc /* 30 vars */ <unfinished ...>"#,
+            r#"4201  execve("/usr/bin/gcc", ["gcc", "-c", "lib.c"], 0x55 /* 30 vars */ <unfinished ...>"#,
+            r#"4202  <... execve resumed>)             = 0"#,
+            r#"4200  <... vfork resumed>)              = 4202"#,
+            r#"4201  <... execve resumed>)             = 0"#,
+            r#"4203  execve("/usr/bin/cc", ["cc"], 0x55 /* 30 vars */) = -1 ENOENT (No such file or directory)"#,
+            r#"4201  --- SIGCHLD {si_signo=SIGCHLD, si_code=CLD_EXITED, si_pid=4202} ---"#,
+            r#"4201  +++ exited with 0 +++"#,
+        ]);
+
+        assert_eq!(
+            events,
+            vec![
+                event(4200, "/usr/bin/make", vec!["make"], "/home/user/project"),
+                event(
+                    4202,
+                    "/home/user/project/lib/configure",
+                    vec!["./configure"],
+                    "/home/user/project/lib"
+                ),
+                event(4201, "/usr/bin/gcc", vec!["gcc", "-c", "lib.c"], "/home/user/project/lib"),
+            ]
+        );
+    }
+
+    // Requirements: interception-strace-import
+    #[test]
+    fn test_terminal_prefix_and_timestamps() {
+        let events = parse(&[
+            r#"10:20:30.123456 execve("/usr/bin/make", ["make"], 0x7ffc /* 30 vars */) = 0"#,
+            r#"[pid  4201] 10:20:31.000001 execve("/usr/bin/cc", ["cc", "-c", "a.c"], 0x55 /* 3 vars */) = 0"#,
+            r#"strace: Process 4202 attached"#,
+        ]);
+
+        assert_eq!(
+            events,
+            vec![
+                event(0, "/usr/bin/make", vec!["make"], "/home/user/project"),
+                event(4201, "/usr/bin/cc", vec!["cc", "-c", "a.c"], "/home/user/project"),
+            ]
+        );
+    }
+
+    // Requirements: interception-strace-import
+    #[test]
+    fn test_escaped_strings_and_environment() {
+        let events = parse(&[
+            r#"execve("/usr/bin/cc", ["cc", "-DNAME=\"my lib\"", "-DTAB=\t\x41\101", "a b.c"], ["PATH=/usr/bin", "HOME=/home/user", "CPATH=/opt/include"]) = 0"#,
+        ]);
+
+        let environment = HashMap::from([("PATH", "/usr/bin"), ("CPATH", "/opt/include")]);
+        let expected = Event::from_strings(
+            0,
+            "/usr/bin/cc",
+            vec!["cc", "-DNAME=\"my lib\"", "-DTAB=\tAA", "a b.c"],
+            "/home/user/project",
+            environment,
+        );
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].execution.arguments, expected.execution.arguments);
+        assert_eq!(events[0].execution.environment.get("CPATH"), Some(&"/opt/include".to_string()));
+        assert_eq!(events[0].execution.environment.get("HOME"), None);
+    }
+
+    // Requirements: interception-strace-import
+    #[test]
+    fn test_truncated_arguments_are_skipped() {
+        let events = parse(&[
+            r#"execve("/usr/bin/cc", ["cc", "-I/very/long/include/path/th"..., "main.c"], 0x55 /* 30 vars */) = 0"#,
+            r#"execve("/usr/bin/cc", ["cc", "-c", ...], 0x55 /* 30 vars */) = 0"#,
+        ]);
+
+        assert_eq!(events, vec![]);
+    }
+
+    #[test]
+    fn test_syscall_parse() {
+        let syscall =
+            Syscall::parse(r#"execve("/bin/sh", ["sh", "-c", "echo \"(a, b)\""], 0x1 /* 2 vars */) = 0"#)
+                .unwrap();
+
+        assert_eq!(
+            syscall,
+            Syscall {
+                name: "execve".into(),
+                arguments: vec![
+                    Value::Str { value: "/bin/sh".into(), truncated: false },
+                    Value::Array {
+                        values: vec![
+                            Value::Str { value: "sh".into(), truncated: false },
+                            Value::Str { value: "-c".into(), truncated: false },
+                            Value::Str { value: "echo \"(a, b)\"".into(), truncated: false },
+                        ],
+                        truncated: false,
+                    },
+                    Value::Other,
+                ],
+                result: Some(0),
+            }
+        );
+        assert_eq!(Syscall::parse("exit_group(0) = ?").map(|syscall| syscall.result), Some(None));
+        assert_eq!(Syscall::parse("not a syscall"), None);
+    }
+}
@@ -11,7 +11,7 @@ mod execution;
 use crate::intercept::environment;
 use crate::intercept::tcp::CollectorOnTcp;
 use crate::semantic::interpreters::compilers::compiler_recognition::CompilerRecognizer;
-use crate::{args, config, context, output};
+use crate::{args, config, context, output, semantic};
 use std::process::ExitCode;
 use std::sync::Arc;
 
@@ -21,16 +21,42 @@ use std::sync::Arc;
 /// - **Intercept only**: Capture build commands and write them to a file for later analysis.
 /// - **Semantic only**: Read previously captured build commands from a file and analyze them.
 /// - **Combined**: Capture build commands and analyze them in real-time.
+/// - **Prune**: Drop the entries of deleted files from an existing compilation database.
+/// - **Convert**: Rewrite an existing compilation database in another file format.
+/// - **Graph**: Read previously captured build commands and export the build artifact graph.
+/// - **Export**: Turn a compilation database or captured build commands into a build script.
+/// - **Merge**: Combine existing compilation databases into one.
+/// - **Diff**: Compare two compilation databases.
+/// - **Check**: Validate a compilation database in depth.
+/// - **Normalize**: Analyze the commands of an existing compilation database, and
+///   write them through the output pipeline.
+/// - **Import**: Turn the log of another build tracing tool (or a Bazel action graph)
+///   into an event file.
 ///
 /// Internally, this enum distinguishes between:
 /// - `Intercept`: Modes that execute build commands while capturing events (intercept-only and combined)
-/// - `Replay`: Modes that process previously captured events (semantic-only)
+/// - `Replay`: Modes that process previously captured events (semantic-only, graph, export
+///   from events and prune, which replays no events, but keeps the existing entries).
+///   Normalize replays the commands of a compilation database as events, and
+///   import replays the executions of a build log into an event file.
+/// - `Convert`: Modes that process existing compilation databases without events
+///   (format conversion and merge)
+/// - `Export`: Export of an existing compilation database without events
+/// - `Diff`: Comparison of existing compilation databases, which exits with
+///   failure when these are different
+/// - `Check`: Validation of an existing compilation database, which exits
+///   with failure when it has errors
 ///
 /// The distinction between writing raw events vs. performing semantic analysis
 /// is handled by the consumer configuration, not the mode itself.
 pub enum Mode {
     Intercept(execution::Interceptor, args::BuildCommand),
     Replay(execution::Replayer),
+    Convert(output::OutputConverter),
+    Merge(output::OutputMerger),
+    Export(output::DatabaseExporter),
+    Diff(output::DatabaseDiffer),
+    Check(output::DatabaseChecker),
 }
 
 impl Mode {
@@ -42,7 +68,7 @@ impl Mode {
     pub fn configure(
         context: context::Context,
         args: args::Arguments,
-        config: config::Main,
+        mut config: config::Main,
     ) -> Result<Self, ConfigurationError> {
         match args.mode {
             args::Mode::Intercept { input, output } => {
@@ -112,6 +138,145 @@ impl Mode {
 
                 Ok(Self::Intercept(intercept, input))
             }
+            args::Mode::Prune { output } => {
+                log::debug!("Mode: prune entries of missing files");
+
+                if !output.path.is_file() {
+                    return Err(ConfigurationError::InvalidConfiguration(format!(
+                        "Compilation database not found: {:?}",
+                        output.path
+                    )));
+                }
+                config.append.prune_missing = true;
+
+                let consumer =
+                    impls::SemanticEventWriter::create(output, &config, context.confstr_path.clone())
+                        .map_err(ConfigurationError::ConsumerCreation)?;
+
+                let replayer = execution::Replayer::new(Box::new(impls::NoEventSource), Box::new(consumer));
+
+                Ok(Self::Replay(replayer))
+            }
+            args::Mode::Convert { input, output } => {
+                log::debug!("Mode: convert compilation database format");
+
+                if !input.path.is_file() {
+                    return Err(ConfigurationError::InvalidConfiguration(format!(
+                        "Compilation database not found: {:?}",
+                        input.path
+                    )));
+                }
+
+                let converter = output::OutputConverter::try_from((&input, &output))
+                    .map_err(ConfigurationError::ConsumerCreation)?;
+
+                Ok(Self::Convert(converter))
+            }
+            args::Mode::Graph { input, output } => {
+                log::debug!("Mode: replay events and export artifact graph");
+
+                // The archiver calls are part of the graph.
+                config.link_commands.enabled = true;
+
+                let source = impls::RawEventReader::create(&input.path)?;
+                let consumer = impls::GraphEventWriter::create(output, &config, context.confstr_path.clone());
+
+                let replayer = execution::Replayer::new(Box::new(source), Box::new(consumer));
+
+                Ok(Self::Replay(replayer))
+            }
+            args::Mode::Merge { inputs, output } => {
+                log::debug!("Mode: merge compilation databases");
+
+                if let Some(missing) = inputs.iter().find(|input| !input.is_file()) {
+                    return Err(ConfigurationError::InvalidConfiguration(format!(
+                        "Compilation database not found: {missing:?}"
+                    )));
+                }
+
+                let merger = output::OutputMerger::try_from((inputs.as_slice(), &output, &config))
+                    .map_err(ConfigurationError::ConsumerCreation)?;
+
+                Ok(Self::Merge(merger))
+            }
+            args::Mode::Export { input: args::ExportInput::Database { path }, output } => {
+                log::debug!("Mode: export compilation database as build script");
+
+                if !path.is_file() {
+                    return Err(ConfigurationError::InvalidConfiguration(format!(
+                        "Compilation database not found: {path:?}"
+                    )));
+                }
+
+                Ok(Self::Export(output::DatabaseExporter::new(&path, &output)))
+            }
+            args::Mode::Diff { old, new, output } => {
+                log::debug!("Mode: compare compilation databases");
+
+                if let Some(missing) = [&old, &new].into_iter().find(|input| !input.is_file()) {
+                    return Err(ConfigurationError::InvalidConfiguration(format!(
+                        "Compilation database not found: {missing:?}"
+                    )));
+                }
+
+                let interpreter = semantic::interpreters::create(&config, context.confstr_path.clone());
+                let differ = output::DatabaseDiffer::new(&old, &new, output, Box::new(interpreter));
+
+                Ok(Self::Diff(differ))
+            }
+            args::Mode::Normalize { input, output } => {
+                log::debug!("Mode: replay compilation database and semantic analysis");
+
+                let source = impls::DatabaseEventReader::create(&input)?;
+                let consumer =
+                    impls::SemanticEventWriter::create(output, &config, context.confstr_path.clone())
+                        .map_err(ConfigurationError::ConsumerCreation)?;
+
+                let replayer = execution::Replayer::new(Box::new(source), Box::new(consumer));
+
+                Ok(Self::Replay(replayer))
+            }
+            args::Mode::Import { input, output } => {
+                log::debug!("Mode: import build log as events");
+
+                let directory = input
+                    .directory
+                    .as_ref()
+                    .map(|directory| context.current_directory.join(directory))
+                    .unwrap_or_else(|| context.current_directory.clone());
+                let source = impls::ImportEventReader::create(&input.path, input.format, directory)?;
+                let consumer = impls::RawEventWriter::create(&output.path)
+                    .map_err(ConfigurationError::ConsumerCreation)?;
+
+                let replayer = execution::Replayer::new(Box::new(source), Box::new(consumer));
+
+                Ok(Self::Replay(replayer))
+            }
+            args::Mode::Check { input, output } => {
+                log::debug!("Mode: check compilation database");
+
+                if !input.is_file() {
+                    return Err(ConfigurationError::InvalidConfiguration(format!(
+                        "Compilation database not found: {input:?}"
+                    )));
+                }
+
+                let interpreter = semantic::interpreters::create(&config, context.confstr_path.clone());
+                let checker = output::DatabaseChecker::new(&input, output, Box::new(interpreter));
+
+                Ok(Self::Check(checker))
+            }
+            args::Mode::Export { input: args::ExportInput::Events { path }, output } => {
+                log::debug!("Mode: replay events and export build script");
+
+                let source = impls::RawEventReader::create(&path)?;
+                let consumer =
+                    impls::ScriptEventWriter::create(output, &config, context.confstr_path.clone());
+
+                let replayer = execution::Replayer::new(Box::new(source), Box::new(consumer));
+
+                Ok(Self::Replay(replayer))
+            }
         }
     }
 
@@ -124,6 +289,23 @@ impl Mode {
         let status = match self {
             Self::Intercept(interceptor, command) => interceptor.run(command),
             Self::Replay(semantic) => semantic.run(),
+            Self::Convert(converter) => {
+                converter.convert().map(|_| ExitCode::SUCCESS).map_err(execution::RuntimeError::from)
+            }
+            Self::Merge(merger) => {
+                merger.merge().map(|_| ExitCode::SUCCESS).map_err(execution::RuntimeError::from)
+            }
+            Self::Export(exporter) => {
+                exporter.export().map(|_| ExitCode::SUCCESS).map_err(execution::RuntimeError::from)
+            }
+            Self::Diff(differ) => differ
+                .diff()
+                .map(|different| if different { ExitCode::FAILURE } else { ExitCode::SUCCESS })
+                .map_err(execution::RuntimeError::from),
+            Self::Check(checker) => checker
+                .check()
+                .map(|errors| if errors { ExitCode::FAILURE } else { ExitCode::SUCCESS })
+                .map_err(execution::RuntimeError::from),
         };
         status.unwrap_or_else(|error| {
             log::error!("{error}");
@@ -152,7 +334,11 @@ mod impls {
     use crate::intercept::reporter::ReporterError;
     use crate::intercept::supervise::SuperviseError;
     use crate::intercept::tcp::CollectorOnTcp;
-    use crate::output::{ExecutionEventDatabase, SerializationFormat, WriterCreationError, WriterError};
+    use crate::output::graph::ArtifactGraph;
+    use crate::output::script::BuildScriptWriter;
+    use crate::output::{
+        ExecutionEventDatabase, SerializationError, SerializationFormat, WriterCreationError, WriterError,
+    };
     use crate::{args, config, intercept, output, semantic};
     use crossbeam_channel::{Receiver, Sender};
     use std::process::ExitStatus;
@@ -246,6 +432,137 @@ mod impls {
         }
     }
 
+    /// Represents a compilation database reader to be event source.
+    ///
+    /// The commands of the entries are turned back into executions, so the
+    /// databases of other generators can be analyzed the same way as the
+    /// intercepted events.
+    pub(super) struct DatabaseEventReader {
+        path: std::path::PathBuf,
+    }
+
+    impl DatabaseEventReader {
+        /// Create a new compilation database reader.
+        pub(super) fn create(path: &std::path::Path) -> Result<Self, ConfigurationError> {
+            if !path.is_file() {
+                return Err(ConfigurationError::InvalidConfiguration(format!(
+                    "Compilation database not found: {path:?}"
+                )));
+            }
+
+            Ok(Self { path: path.to_path_buf() })
+        }
+    }
+
+    impl execution::Producer for DatabaseEventReader {
+        /// Reads the entries of the compilation database, and dispatches their
+        /// commands as events to the destination channel. Invalid entries are
+        /// logged and ignored.
+        fn produce(&self, destination: Sender<intercept::Event>) -> Result<(), ReporterError> {
+            let entries = output::read_compilation_db(&self.path).map_err(|error| {
+                ReporterError::Network(io::Error::other(format!("{}: {error}", self.path.display())))
+            })?;
+
+            for entry in entries {
+                let execution = match intercept::Execution::try_from(&entry) {
+                    Ok(execution) => execution,
+                    Err(error) => {
+                        log::warn!("Compilation database reading issue: {error}");
+                        continue;
+                    }
+                };
+                // The events of the database are not from a process, like the
+                // initial build command.
+                if destination.send(intercept::Event { pid: 0, execution }).is_err() {
+                    log::debug!("Consumer channel closed; stopping event forwarding");
+                    break;
+                }
+            }
+
+            Ok(())
+        }
+    }
+
+    /// Represents a build log reader to be event source.
+    ///
+    /// The logs of other tracing tools (and the action graphs of build
+    /// systems) are turned into events, so these can be written into an
+    /// event file, like the intercepted ones.
+    pub(super) struct ImportEventReader {
+        path: std::path::PathBuf,
+        format: args::ImportFormat,
+        directory: std::path::PathBuf,
+    }
+
+    impl ImportEventReader {
+        /// Create a new build log reader.
+        ///
+        /// The directory is the working directory of the traced build, the
+        /// relative paths of the log are resolved against it.
+        pub(super) fn create(
+            path: &std::path::Path,
+            format: args::ImportFormat,
+            directory: std::path::PathBuf,
+        ) -> Result<Self, ConfigurationError> {
+            if !path.is_file() {
+                return Err(ConfigurationError::InvalidConfiguration(format!(
+                    "Build log not found: {path:?}"
+                )));
+            }
+
+            Ok(Self { path: path.to_path_buf(), format, directory })
+        }
+    }
+
+    impl execution::Producer for ImportEventReader {
+        /// Reads the build log, and dispatches the executions as events to the
+        /// destination channel. Unparsable lines of the logs are logged and
+        /// ignored, while the action graphs have to be valid as a whole.
+        fn produce(&self, destination: Sender<intercept::Event>) -> Result<(), ReporterError> {
+            let file = fs::File::open(&self.path).map(io::BufReader::new).map_err(ReporterError::Network)?;
+
+            let aquery_error = |error: intercept::aquery::AqueryError| {
+                ReporterError::Network(io::Error::other(format!("{}: {error}", self.path.display())))
+            };
+            let events: Box<dyn Iterator<Item = intercept::Event>> = match self.format {
+                args::ImportFormat::Strace => {
+                    Box::new(intercept::strace::read_events(file, self.directory.clone()))
+                }
+                args::ImportFormat::AqueryJson => Box::new(
+                    intercept::aquery::read_json_events(file, &self.directory)
+                        .map_err(aquery_error)?
+                        .into_iter(),
+                ),
+                args::ImportFormat::AqueryText => Box::new(
+                    intercept::aquery::read_text_events(file, &self.directory)
+                        .map_err(aquery_error)?
+                        .into_iter(),
+                ),
+            };
+
+            for event in events {
+                if destination.send(event).is_err() {
+                    log::debug!("Consumer channel closed; stopping event forwarding");
+                    break;
+                }
+            }
+
+            Ok(())
+        }
+    }
+
+    /// Represents an event source without events.
+    ///
+    /// Used by the prune mode, where only the entries of the existing compilation
+    /// database are passed through the output pipeline.
+    pub(super) struct NoEventSource;
+
+    impl execution::Producer for NoEventSource {
+        fn produce(&self, _: Sender<intercept::Event>) -> Result<(), ReporterError> {
+            Ok(())
+        }
+    }
+
     /// Represents a raw event writer to be used as a consumer.
     ///
     /// The raw event writer will write the intercepted events as they are observed
@@ -330,6 +647,86 @@ mod impls {
         }
     }
 
+    /// Represents a build artifact graph writer as a consumer.
+    ///
+    /// It uses the semantic interpreter to recognize the compiler, linker and
+    /// archiver calls, and writes the graph of the files they read and produce.
+    pub(super) struct GraphEventWriter {
+        interpreter: Box<dyn semantic::Interpreter>,
+        output: args::ExportGraph,
+    }
+
+    impl GraphEventWriter {
+        /// Create a new graph writer for the given output file and format.
+        pub(super) fn create(output: args::ExportGraph, config: &config::Main, confstr_path: String) -> Self {
+            let interpreter = semantic::interpreters::create(config, confstr_path);
+
+            Self { interpreter: Box::new(interpreter), output }
+        }
+    }
+
+    impl execution::Consumer for GraphEventWriter {
+        /// Consume the intercepted events, collect the recognized commands into
+        /// the graph, and write it into the target file.
+        fn consume(self: Box<Self>, events: Receiver<intercept::Event>) -> Result<(), WriterError> {
+            let mut graph = ArtifactGraph::default();
+            for event in events {
+                if let semantic::RecognizeResult::Recognized(cmd) =
+                    self.interpreter.recognize(event.execution)
+                {
+                    graph.add(&cmd);
+                }
+            }
+            log::debug!("Writing {} nodes and {} edges", graph.nodes.len(), graph.edges.len());
+
+            let path = &self.output.path;
+            let destination = fs::File::create(path)
+                .map(io::BufWriter::new)
+                .map_err(|err| WriterError::Io(path.clone(), SerializationError::Io(err)))?;
+            graph
+                .write(destination, self.output.format)
+                .map_err(|err| WriterError::Io(path.clone(), SerializationError::Io(err)))
+        }
+    }
+
+    /// Represents a build script writer as a consumer.
+    ///
+    /// It uses the semantic interpreter and the compilation database entry
+    /// conversion (as configured), and writes the entries as a build script.
+    pub(super) struct ScriptEventWriter {
+        interpreter: Box<dyn semantic::Interpreter>,
+        converter: output::clang::CommandConverter,
+        writer: BuildScriptWriter,
+    }
+
+    impl ScriptEventWriter {
+        /// Create a new build script writer for the given output file and format.
+        pub(super) fn create(
+            output: args::ExportScript,
+            config: &config::Main,
+            confstr_path: String,
+        ) -> Self {
+            let interpreter = semantic::interpreters::create(config, confstr_path);
+            let converter = output::clang::CommandConverter::new(config.format.clone());
+
+            Self { interpreter: Box::new(interpreter), converter, writer: BuildScriptWriter::from(&output) }
+        }
+    }
+
+    impl execution::Consumer for ScriptEventWriter {
+        /// Consume the intercepted events, convert the recognized commands into
+        /// entries, and write them as a build script.
+        fn consume(self: Box<Self>, events: Receiver<intercept::Event>) -> Result<(), WriterError> {
+            let entries =
+                events.into_iter().flat_map(|event| match self.interpreter.recognize(event.execution) {
+                    semantic::RecognizeResult::Recognized(cmd) => self.converter.to_entries(&cmd),
+                    _ => vec![],
+                });
+
+            self.writer.write(entries)
+        }
+    }
+
     pub(super) struct BuildExecutor {
         environment: environment::BuildEnvironment,
     }
@@ -0,0 +1,435 @@
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! In-depth validation of an existing compilation database.
+//!
+//! The readers of the output pipeline validate the structure of the entries
+//! only (see `Entry::validate`), and skip the invalid ones. The checker reads
+//! every entry as it is, and reports the problems which make the database
+//! unreliable for the tools:
+//!
+//! - syntax errors and entries with missing fields,
+//! - entries with both `arguments` and `command` fields,
+//! - working directories, source files and include directories which do not
+//!   exist,
+//! - commands which are not recognized as compiler calls of the source file,
+//! - duplicate and conflicting entries of the same source file and output.
+//!
+//! Each finding has a severity. Errors make the entry unusable, warnings are
+//! suspicious, but the entry can still be used.
+
+use super::clang::serialization::is_json_lines;
+use super::clang::{Entry, EntryError, json};
+use super::graph::resolve;
+use crate::intercept::Execution;
+use crate::semantic::{Argument, FlagValue, Interpreter, RecognizeResult};
+use serde_json::StreamDeserializer;
+use serde_json::de::IoRead;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::{fs, io};
+
+/// The flags which values are checked as include directories.
+const INCLUDE_FLAGS: [&str; 2] = ["-I", "-isystem"];
+
+/// The entries seen by source file and output, with their index and arguments.
+type SeenEntries = HashMap<(PathBuf, Option<PathBuf>), Vec<(usize, Vec<String>)>>;
+
+/// The severity of a finding.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, serde::Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    /// The entry is unusable.
+    Error,
+    /// The entry is suspicious, but usable.
+    Warning,
+}
+
+/// The checks which produce the findings.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CheckKind {
+    /// The file is not a valid JSON compilation database.
+    Syntax,
+    /// The entry has missing or empty fields.
+    InvalidEntry,
+    /// The `command` and `arguments` fields are both present and differ.
+    CommandMismatch,
+    /// The `command` and `arguments` fields are both present and agree.
+    RedundantCommand,
+    /// The working directory does not exist.
+    MissingDirectory,
+    /// The source file does not exist.
+    MissingFile,
+    /// An include directory does not exist.
+    MissingIncludeDirectory,
+    /// The command is not recognized as a compiler call.
+    UnrecognizedCommand,
+    /// The command is recognized, but does not compile the source file.
+    FileNotInCommand,
+    /// The entry is the same as a previous one.
+    DuplicateEntry,
+    /// The entry compiles the same file to the same output as a previous
+    /// one, but with a different command.
+    ConflictingEntry,
+}
+
+/// A problem found in the compilation database.
+#[derive(Debug, PartialEq, serde::Serialize)]
+pub struct Finding {
+    pub severity: Severity,
+    pub check: CheckKind,
+    /// The position of the entry in the database (starting from 0).
+    pub index: usize,
+    /// The source file of the entry, when it could be read.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub file: Option<PathBuf>,
+    pub message: String,
+}
+
+/// The result of the compilation database check.
+#[derive(Debug, Default, serde::Serialize)]
+#[serde(transparent)]
+pub struct DatabaseCheck {
+    pub findings: Vec<Finding>,
+}
+
+impl DatabaseCheck {
+    /// Checks the entries of the database.
+    ///
+    /// The interpreter is used to parse the commands of the entries, the same
+    /// way as the semantic analysis does.
+    pub fn new(
+        interpreter: &dyn Interpreter,
+        entries: impl Iterator<Item = Result<Entry, serde_json::Error>>,
+    ) -> Self {
+        let mut result = Self::default();
+        let mut previous: SeenEntries = HashMap::new();
+
+        for (index, entry) in entries.enumerate() {
+            let entry = match entry {
+                Ok(entry) => entry,
+                Err(error) => {
+                    result.report(Severity::Error, CheckKind::Syntax, index, None, error.to_string());
+                    continue;
+                }
+            };
+            let file = resolve(&entry.directory, &entry.file);
+            let Some(arguments) = result.check_structure(index, &file, &entry) else {
+                continue;
+            };
+            result.check_paths(index, &file, &entry);
+            result.check_command(interpreter, index, &file, &entry);
+
+            let output = entry.output.as_ref().map(|output| resolve(&entry.directory, output));
+            let same_unit = previous.entry((file.clone(), output)).or_default();
+            if let Some((first, _)) = same_unit.iter().find(|(_, candidate)| candidate == &arguments) {
+                let message = format!("Same as entry {first}");
+                result.report(Severity::Warning, CheckKind::DuplicateEntry, index, Some(&file), message);
+            } else if let Some((first, _)) = same_unit.first() {
+                let message = format!("Same file and output as entry {first}, but different command");
+                result.report(Severity::Warning, CheckKind::ConflictingEntry, index, Some(&file), message);
+            }
+            same_unit.push((index, arguments));
+        }
+        result
+    }
+
+    /// Returns true when any finding is an error.
+    pub fn has_errors(&self) -> bool {
+        self.findings.iter().any(|finding| finding.severity == Severity::Error)
+    }
+
+    /// Writes the findings as a JSON array.
+    pub fn write_json(&self, writer: impl io::Write) -> Result<(), serde_json::Error> {
+        serde_json::to_writer_pretty(writer, self)
+    }
+
+    /// Checks the fields of the entry, returns the command arguments when
+    /// the entry is usable.
+    fn check_structure(&mut self, index: usize, file: &Path, entry: &Entry) -> Option<Vec<String>> {
+        match entry.validate() {
+            Ok(_) => entry.to_arguments().ok(),
+            Err(EntryError::CommandOrArgumentsArePresent) => {
+                let (check, severity, message) = match shell_words::split(&entry.command) {
+                    Ok(command) if command == entry.arguments => (
+                        CheckKind::RedundantCommand,
+                        Severity::Warning,
+                        "Both command and arguments fields are present".to_string(),
+                    ),
+                    Ok(_) => (
+                        CheckKind::CommandMismatch,
+                        Severity::Error,
+                        "The command and arguments fields are different".to_string(),
+                    ),
+                    Err(error) => (
+                        CheckKind::CommandMismatch,
+                        Severity::Error,
+                        format!("Both command and arguments fields are present, invalid command: {error}"),
+                    ),
+                };
+                self.report(severity, check, index, Some(file), message);
+                // The arguments field is preferred by the tools.
+                Some(entry.arguments.clone())
+            }
+            Err(error) => {
+                self.report(Severity::Error, CheckKind::InvalidEntry, index, Some(file), error.to_string());
+                None
+            }
+        }
+    }
+
+    /// Checks that the working directory and the source file exist.
+    fn check_paths(&mut self, index: usize, file: &Path, entry: &Entry) {
+        if !entry.directory.is_dir() {
+            let message = format!("Directory does not exist: {}", entry.directory.display());
+            self.report(Severity::Error, CheckKind::MissingDirectory, index, Some(file), message);
+        }
+        if !file.is_file() {
+            let message = format!("File does not exist: {}", file.display());
+            self.report(Severity::Error, CheckKind::MissingFile, index, Some(file), message);
+        }
+    }
+
+    /// Parses the command with the interpreter, and checks that it compiles
+    /// the source file and that the include directories exist.
+    fn check_command(&mut self, interpreter: &dyn Interpreter, index: usize, file: &Path, entry: &Entry) {
+        // The arguments are preferred over the command, when both are present.
+        let Ok(execution) = Execution::try_from(entry) else {
+            return;
+        };
+        let executable = execution.executable.display().to_string();
+        let cmd = match interpreter.recognize(execution) {
+            RecognizeResult::Recognized(cmd) => cmd,
+            RecognizeResult::Ignored(reason) => {
+                let message = format!("The command is not a compiler call: {reason}");
+                self.report(Severity::Warning, CheckKind::UnrecognizedCommand, index, Some(file), message);
+                return;
+            }
+            RecognizeResult::NotRecognized(_) => {
+                let message = format!("The compiler is not recognized: {executable}");
+                self.report(Severity::Warning, CheckKind::UnrecognizedCommand, index, Some(file), message);
+                return;
+            }
+        };
+
+        let sources: HashSet<PathBuf> = cmd
+            .arguments
+            .iter()
+            .filter_map(|argument| match argument {
+                Argument::Source { path, .. } => Some(resolve(&entry.directory, Path::new(path))),
+                _ => None,
+            })
+            .collect();
+        if !sources.contains(file) {
+            let message = "The command does not compile the file".to_string();
+            self.report(Severity::Warning, CheckKind::FileNotInCommand, index, Some(file), message);
+        }
+
+        for directory in cmd.arguments.iter().filter_map(include_directory) {
+            let path = resolve(&entry.directory, Path::new(directory));
+            if !path.is_dir() {
+                let message = format!("Include directory does not exist: {}", path.display());
+                self.report(
+                    Severity::Warning,
+                    CheckKind::MissingIncludeDirectory,
+                    index,
+                    Some(file),
+                    message,
+                );
+            }
+        }
+    }
+
+    fn report(
+        &mut self,
+        severity: Severity,
+        check: CheckKind,
+        index: usize,
+        file: Option<&Path>,
+        message: String,
+    ) {
+        self.findings.push(Finding { severity, check, index, file: file.map(Path::to_path_buf), message });
+    }
+}
+
+/// Reads the entries of the compilation database without validation.
+///
+/// The file can be in the JSON array or in the JSON lines format, the format
+/// is detected from the content.
+pub(super) fn read_entries(
+    path: &Path,
+) -> io::Result<Box<dyn Iterator<Item = Result<Entry, serde_json::Error>>>> {
+    let mut file = fs::File::open(path).map(io::BufReader::new)?;
+    if is_json_lines(&mut file)? {
+        Ok(Box::new(StreamDeserializer::new(IoRead::new(file))))
+    } else {
+        Ok(Box::new(json::deserialize_seq(file)))
+    }
+}
+
+/// Returns the value of an include directory flag.
+///
+/// Directories relative to the sysroot (`=dir`, `$SYSROOT/dir`) are not
+/// returned, these can not be checked.
+fn include_directory(argument: &Argument) -> Option<&str> {
+    let Argument::Other { arguments, value: FlagValue::Path { flag, .. }, .. } = argument else {
+        return None;
+    };
+    if !INCLUDE_FLAGS.contains(flag) {
+        return None;
+    }
+    let value = match arguments.as_slice() {
+        [_, value] => value.as_str(),
+        [single] => single.strip_prefix(*flag)?,
+        _ => return None,
+    };
+    (!value.is_empty() && !value.starts_with('=') && !value.starts_with('$')).then_some(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config;
+    use crate::semantic::interpreters;
+
+    fn check(entries: Vec<Entry>) -> Vec<(Severity, CheckKind, usize)> {
+        let interpreter = interpreters::create(&config::Main::default(), "/usr/bin:/bin".to_string());
+        let result = DatabaseCheck::new(&interpreter, entries.into_iter().map(Ok));
+        result.findings.into_iter().map(|finding| (finding.severity, finding.check, finding.index)).collect()
+    }
+
+    fn project() -> tempfile::TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        fs::create_dir(dir.path().join("include")).unwrap();
+        fs::write(dir.path().join("main.c"), "int main() { return 0; }").unwrap();
+        dir
+    }
+
+    // Requirements: output-check
+    #[test]
+    fn test_valid_entry() {
+        let dir = project();
+        let directory = dir.path().to_str().unwrap();
+
+        let result = check(vec![Entry::from_arguments_str(
+            "main.c",
+            vec!["/usr/bin/cc", "-c", "-I", "include", "-isystem", directory, "main.c"],
+            directory,
+            None,
+        )]);
+
+        assert_eq!(result, vec![]);
+    }
+
+    // Requirements: output-check
+    #[test]
+    fn test_missing_paths() {
+        let dir = project();
+        let directory = dir.path().to_str().unwrap();
+
+        let result = check(vec![
+            Entry::from_arguments_str(
+                "missing.c",
+                vec!["cc", "-c", "-Imissing", "missing.c"],
+                directory,
+                None,
+            ),
+            Entry::from_arguments_str("main.c", vec!["cc", "-c", "main.c"], "/this/does/not/exist", None),
+        ]);
+
+        assert_eq!(
+            result,
+            vec![
+                (Severity::Error, CheckKind::MissingFile, 0),
+                (Severity::Warning, CheckKind::MissingIncludeDirectory, 0),
+                (Severity::Error, CheckKind::MissingDirectory, 1),
+                (Severity::Error, CheckKind::MissingFile, 1),
+            ]
+        );
+    }
+
+    // Requirements: output-check
+    #[test]
+    fn test_command_and_arguments() {
+        let dir = project();
+        let directory = dir.path().to_str().unwrap();
+        let entry = |command: &str, arguments: Vec<&str>| {
+            let mut entry = Entry::from_arguments_str("main.c", arguments, directory, None);
+            entry.command = command.to_string();
+            entry
+        };
+
+        let result = check(vec![
+            entry("cc -c main.c", vec!["cc", "-c", "main.c"]),
+            entry("cc -c -O2 main.c", vec!["cc", "-c", "-O3", "main.c"]),
+            Entry::from_arguments_str("main.c", vec![], directory, None),
+        ]);
+
+        assert_eq!(
+            result,
+            vec![
+                (Severity::Warning, CheckKind::RedundantCommand, 0),
+                (Severity::Error, CheckKind::CommandMismatch, 1),
+                (Severity::Warning, CheckKind::ConflictingEntry, 1),
+                (Severity::Error, CheckKind::InvalidEntry, 2),
+            ]
+        );
+    }
+
+    // Requirements: output-check
+    #[test]
+    fn test_command_recognition() {
+        let dir = project();
+        let directory = dir.path().to_str().unwrap();
+
+        let result = check(vec![
+            Entry::from_arguments_str("main.c", vec!["/usr/bin/ls", "main.c"], directory, None),
+            Entry::from_arguments_str("main.c", vec!["cc", "-c", "other.c"], directory, Some("other.o")),
+        ]);
+
+        assert_eq!(
+            result,
+            vec![
+                (Severity::Warning, CheckKind::UnrecognizedCommand, 0),
+                (Severity::Warning, CheckKind::FileNotInCommand, 1),
+            ]
+        );
+    }
+
+    // Requirements: output-check
+    #[test]
+    fn test_duplicate_and_conflicting_entries() {
+        let dir = project();
+        let directory = dir.path().to_str().unwrap();
+
+        let result = check(vec![
+            Entry::from_arguments_str("main.c", vec!["cc", "-c", "main.c"], directory, None),
+            Entry::from_command_str("main.c", "cc -c main.c", directory, None),
+            Entry::from_arguments_str("main.c", vec!["cc", "-c", "-O2", "main.c"], directory, None),
+            // Different output is a different unit.
+            Entry::from_arguments_str("main.c", vec!["cc", "-c", "-O2", "main.c"], directory, Some("main.o")),
+        ]);
+
+        assert_eq!(
+            result,
+            vec![
+                (Severity::Warning, CheckKind::DuplicateEntry, 1),
+                (Severity::Warning, CheckKind::ConflictingEntry, 2)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_read_entries_reports_syntax_errors() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("compile_commands.json");
+        fs::write(&path, r#"[{"directory": "/tmp", "file": "main.c", "arguments": ["cc"]}, {"file": 42}]"#)
+            .unwrap();
+
+        let interpreter = interpreters::create(&config::Main::default(), "/usr/bin:/bin".to_string());
+        let result = DatabaseCheck::new(&interpreter, read_entries(&path).unwrap());
+
+        assert!(result.has_errors());
+        assert_eq!(result.findings.last().map(|finding| finding.check), Some(CheckKind::Syntax));
+    }
+}
@@ -12,6 +12,10 @@
 //! - Building properly formatted command lines for each source file
 //! - Computing output files based on command arguments
 //! - Applying format configuration (array vs string commands, output field inclusion)
+//! - Formatting the paths embedded in flags (like `-I include`) as the source files
+//! - Removing or renaming flags which Clang does not understand (when configured)
+//! - Applying the configured argument rewrite rules
+//! - Substituting the compiler executable by the configured rules
 //! - Filtering out commands that should not generate compilation database entries
 //!
 //! # Compilation Database Entry Generation Rules
@@ -49,9 +53,12 @@
 
 use super::Entry;
 use super::path_format::{ConfigurablePathFormatter, PathFormatter};
+use super::rewrite::ArgumentRewriter;
 use crate::config;
-use crate::semantic::{Argument, ArgumentKind, Command, CompilerPass, PassEffect};
-use log::warn;
+use crate::semantic::{
+    Argument, ArgumentKind, ClangCompatibility, Command, CompilerPass, FlagValue, PassEffect,
+};
+use log::{debug, warn};
 use std::borrow::Cow;
 use std::path::{Path, PathBuf};
 
@@ -62,19 +69,22 @@ use std::path::{Path, PathBuf};
 pub struct CommandConverter {
     format: config::EntryFormat,
     path_formatter: Box<dyn PathFormatter>,
+    rewriter: ArgumentRewriter,
+    executables: Vec<config::ExecutableRule>,
 }
 
 impl CommandConverter {
     /// Creates a new CommandConverter with the specified format configuration.
     pub fn new(format: config::Format) -> Self {
         let path_formatter = Box::new(ConfigurablePathFormatter::new(format.paths));
-        Self { format: format.entries, path_formatter }
+        let rewriter = ArgumentRewriter::new(&format.arguments);
+        Self { format: format.entries, path_formatter, rewriter, executables: format.executables }
     }
 
     /// Creates a new CommandConverter with a custom path formatter for testing.
     #[cfg(test)]
     fn with_formatter(format: config::EntryFormat, path_formatter: Box<dyn PathFormatter>) -> Self {
-        Self { format, path_formatter }
+        Self { format, path_formatter, rewriter: ArgumentRewriter::new(&[]), executables: vec![] }
     }
 
     /// Converts a compiler command into compilation database entries.
@@ -82,6 +92,20 @@ impl CommandConverter {
         self.convert_compiler_command(command)
     }
 
+    /// Formats the paths of an existing entry by the configured path rules.
+    ///
+    /// The `directory`, `file` and `output` fields are formatted, the command
+    /// line is kept as it is (the flags of an existing entry are not classified).
+    pub fn format_entry(&self, entry: Entry) -> Entry {
+        let Some(directory) = self.format_working_directory(&entry.directory) else {
+            return entry;
+        };
+        let file = self.format_source_file(&directory, &entry.file);
+        let output = entry.output.map(|output| self.format_source_file(&directory, &output));
+
+        Entry { file, directory, output, ..entry }
+    }
+
     /// Converts a compiler command into compilation database entries.
     fn convert_compiler_command(&self, cmd: &Command) -> Vec<Entry> {
         // Check if we should skip entry generation for this command
@@ -184,6 +208,7 @@ impl CommandConverter {
     /// This method constructs the command arguments list that includes the executable,
     /// all non-source arguments, and the specific source file.
     /// It ensures that the source file is placed in the correct position relative to output arguments.
+    /// The flags are rewritten by the configured rules (the executable, the source and output are kept).
     fn build_command_args_for_source(
         &self,
         cmd: &Command,
@@ -199,12 +224,7 @@ impl CommandConverter {
             }
 
             // Filter out linking-specific arguments for compilation database entries
-            if matches!(
-                arg.kind(),
-                ArgumentKind::Other(PassEffect::Configures(CompilerPass::Linking))
-                    | ArgumentKind::Other(PassEffect::StopsAt(CompilerPass::Linking))
-                    | ArgumentKind::Other(PassEffect::PassThrough)
-            ) {
+            if Self::is_linking_argument(arg) {
                 continue;
             }
 
@@ -234,22 +254,185 @@ impl CommandConverter {
                     command_args.extend(formatted_args);
                 }
                 ArgumentKind::Compiler => {
-                    if let Some(exe_str) = cmd.executable.to_str() {
+                    if let Some(exe_str) = self.executable(cmd).to_str() {
                         command_args.push(exe_str.to_string());
                     } else {
                         command_args.extend(original_args);
                     }
+                    command_args.extend(self.target_argument(cmd));
+                    command_args.extend(self.rewriter.prepended(cmd.compiler));
                 }
                 _ => {
-                    // Non-file arguments, use as-is
-                    command_args.extend(original_args);
+                    // Non-file arguments, use as-is (unless they carry a path, or Clang or the rewrite rules need them differently)
+                    let formatted_args = self.format_flag_path(arg, original_args, formatted_directory);
+                    let compatible_args = self.clang_compatible_arguments(arg, formatted_args);
+                    command_args.extend(self.rewriter.rewrite(cmd.compiler, compatible_args));
                 }
             }
         }
+        command_args.extend(self.rewriter.appended(cmd.compiler));
 
         command_args
     }
 
+    /// Converts a compiler command into the flags of its source files.
+    ///
+    /// Returns the formatted source file paths with the flags which are needed
+    /// to compile them. The compiler, the source and output arguments, the
+    /// flags which select the last compiler pass (like `-c`) and the linking
+    /// arguments are not part of the flags. Each flag is returned with its
+    /// value (e.g. `["-I", "include"]`), so these can be compared as a unit.
+    pub fn to_flags(&self, cmd: &Command) -> Vec<(PathBuf, Vec<Vec<String>>)> {
+        if self.should_skip_entry_generation(cmd) {
+            return vec![];
+        }
+        let Some(formatted_directory) = self.format_working_directory(&cmd.working_dir) else {
+            return vec![];
+        };
+
+        let mut flags: Vec<Vec<String>> = vec![];
+        flags.extend(self.target_argument(cmd).map(|target| vec![target]));
+        flags.extend(self.rewriter.prepended(cmd.compiler).into_iter().map(|flag| vec![flag]));
+        for arg in &cmd.arguments {
+            if Self::is_linking_argument(arg)
+                || !matches!(arg.kind(), ArgumentKind::Other(PassEffect::Configures(_) | PassEffect::None))
+            {
+                continue;
+            }
+            let path_updater: &dyn Fn(&Path) -> Cow<Path> = &|path: &Path| Cow::Borrowed(path);
+            let formatted_args =
+                self.format_flag_path(arg, arg.as_arguments(path_updater), &formatted_directory);
+            let compatible_args = self.clang_compatible_arguments(arg, formatted_args);
+            let rewritten_args = self.rewriter.rewrite(cmd.compiler, compatible_args);
+            if !rewritten_args.is_empty() {
+                flags.push(rewritten_args);
+            }
+        }
+        flags.extend(self.rewriter.appended(cmd.compiler).into_iter().map(|flag| vec![flag]));
+
+        let path_updater: &dyn Fn(&Path) -> Cow<Path> = &|path: &Path| Cow::Borrowed(path);
+        Self::find_arguments_by_kind(cmd, ArgumentKind::Source { binary: false })
+            .filter_map(|arg| arg.as_file(path_updater))
+            .map(|source| (self.format_source_file(&formatted_directory, &source), flags.clone()))
+            .collect()
+    }
+
+    /// Checks if the argument is only relevant for the linker (or passed through to it).
+    ///
+    /// These arguments are not part of the compilation database entries.
+    fn is_linking_argument(arg: &Argument) -> bool {
+        matches!(
+            arg.kind(),
+            ArgumentKind::Other(PassEffect::Configures(CompilerPass::Linking))
+                | ArgumentKind::Other(PassEffect::StopsAt(CompilerPass::Linking))
+                | ArgumentKind::Other(PassEffect::PassThrough)
+        )
+    }
+
+    /// Formats the path value of a flag (like `-I include` or `--sysroot=/opt`)
+    /// the same way as the source files.
+    fn format_flag_path(
+        &self,
+        arg: &Argument,
+        arguments: Vec<String>,
+        formatted_directory: &Path,
+    ) -> Vec<String> {
+        let Argument::Other { value: FlagValue::Path { flag, glue }, .. } = arg else {
+            return arguments;
+        };
+        let mut arguments = arguments;
+        match arguments.as_mut_slice() {
+            [_, .., value] => *value = self.format_flag_path_value(formatted_directory, value),
+            [single] => {
+                let Some(rest) = single.strip_prefix(*flag) else {
+                    return arguments;
+                };
+                let (prefix_len, value) = match glue.and_then(|glue| rest.strip_prefix(glue)) {
+                    Some(value) => (single.len() - value.len(), value),
+                    None => (flag.len(), rest),
+                };
+                if !value.is_empty() {
+                    *single = format!(
+                        "{}{}",
+                        &single[..prefix_len],
+                        self.format_flag_path_value(formatted_directory, value)
+                    );
+                }
+            }
+            [] => {}
+        }
+        arguments
+    }
+
+    /// Formats a single path value, falling back to the original on error.
+    ///
+    /// Values relative to the sysroot (`=dir`, `$SYSROOT/dir`) are kept as they are.
+    fn format_flag_path_value(&self, formatted_directory: &Path, value: &str) -> String {
+        if value.starts_with('=') || value.starts_with('$') {
+            return value.to_string();
+        }
+        match self.path_formatter.format_file(formatted_directory, Path::new(value)) {
+            Ok(formatted_path) => formatted_path.to_string_lossy().to_string(),
+            Err(e) => {
+                debug!("Failed to format path {} of flag: {}", value, e);
+                value.to_string()
+            }
+        }
+    }
+
+    /// Drops or renames flags which Clang does not understand, when configured.
+    fn clang_compatible_arguments(&self, arg: &Argument, arguments: Vec<String>) -> Vec<String> {
+        let Argument::Other { clang, .. } = arg else {
+            return arguments;
+        };
+        if !self.format.clang_compatible {
+            return arguments;
+        }
+        match *clang {
+            ClangCompatibility::Supported => arguments,
+            ClangCompatibility::Unsupported => vec![],
+            ClangCompatibility::Equivalent { flag, equivalent } => {
+                let mut arguments = arguments;
+                if let Some(first) = arguments.first_mut()
+                    && let Some(rest) = first.strip_prefix(flag)
+                {
+                    *first = format!("{equivalent}{rest}");
+                }
+                arguments
+            }
+        }
+    }
+
+    /// Returns the compiler executable to write into the entry.
+    ///
+    /// The first configured substitution rule which matches the command
+    /// decides, otherwise the executable of the command is used.
+    fn executable<'a>(&'a self, cmd: &'a Command) -> Cow<'a, Path> {
+        let matching = self.executables.iter().find(|rule| {
+            rule.path.as_ref().is_none_or(|path| path == &cmd.executable)
+                && rule.compiler.is_none_or(|compiler| Some(compiler) == cmd.compiler)
+        });
+        match matching.map(|rule| &rule.replacement) {
+            Some(config::ExecutableReplacement::Path(path)) => Cow::Borrowed(path),
+            Some(config::ExecutableReplacement::Basename) => match cmd.executable.file_name() {
+                Some(name) => Cow::Owned(PathBuf::from(name)),
+                None => Cow::Borrowed(&cmd.executable),
+            },
+            None => Cow::Borrowed(&cmd.executable),
+        }
+    }
+
+    /// Returns the `--target` flag implied by the cross-compiler prefix.
+    ///
+    /// The flag is only produced when configured, and when the command does
+    /// not select the target explicitly.
+    fn target_argument(&self, cmd: &Command) -> Option<String> {
+        if !self.format.include_target_from_prefix || cmd.has_explicit_target() {
+            return None;
+        }
+        cmd.target.as_ref().map(|target| format!("--target={target}"))
+    }
+
     /// Returns arguments of a specific kind from the command.
     ///
     /// This method filters arguments by their kind and returns their values as strings.
@@ -358,7 +541,10 @@ impl CommandConverter {
 mod tests {
     use super::super::path_format::{FormatError, MockPathFormatter};
     use super::*;
-    use crate::config::{EntryFormat, Format, PathFormat, PathResolver};
+    use crate::config::{
+        ArgumentAction, ArgumentMatch, ArgumentRule, CompilerType, EntryFormat, ExecutableReplacement,
+        ExecutableRule, Format, PathFormat, PathRemap, PathResolver,
+    };
     use crate::semantic::{ArgumentKind, Command, CompilerPass, PassEffect};
     use std::io;
 
@@ -376,7 +562,7 @@ mod tests {
             ],
         );
 
-        let format = Format { paths: PathFormat::default(), entries: EntryFormat::default() };
+        let format = Format::default();
         let converter = CommandConverter::new(format);
         let entries = converter.to_entries(&command);
 
@@ -402,7 +588,7 @@ mod tests {
             ],
         );
 
-        let format = Format { paths: PathFormat::default(), entries: EntryFormat::default() };
+        let format = Format::default();
         let converter = CommandConverter::new(format);
         let result = converter.to_entries(&command);
 
@@ -431,7 +617,7 @@ mod tests {
             vec![(ArgumentKind::Other(PassEffect::InfoAndExit), vec!["--version"])],
         );
 
-        let format = Format { paths: PathFormat::default(), entries: EntryFormat::default() };
+        let format = Format::default();
         let converter = CommandConverter::new(format);
         let result = converter.to_entries(&command);
 
@@ -453,7 +639,12 @@ mod tests {
         );
         let format = Format {
             paths: PathFormat::default(),
-            entries: EntryFormat { include_output_field: true, use_array_format: false },
+            entries: EntryFormat {
+                include_output_field: true,
+                use_array_format: false,
+                ..EntryFormat::default()
+            },
+            ..Format::default()
         };
         let converter = CommandConverter::new(format);
         let entries = converter.to_entries(&command);
@@ -481,7 +672,12 @@ mod tests {
         );
         let format = Format {
             paths: PathFormat::default(),
-            entries: EntryFormat { use_array_format: true, include_output_field: false },
+            entries: EntryFormat {
+                use_array_format: true,
+                include_output_field: false,
+                ..EntryFormat::default()
+            },
+            ..Format::default()
         };
         let sut = CommandConverter::new(format);
         let result = sut.to_entries(&command);
@@ -495,12 +691,373 @@ mod tests {
         assert_eq!(result, expected);
     }
 
+    #[test]
+    fn test_to_entries_with_target_from_prefix() {
+        let command = Command::from_strings(
+            "/home/user",
+            "/usr/bin/aarch64-linux-gnu-gcc",
+            vec![
+                (ArgumentKind::Compiler, vec!["aarch64-linux-gnu-gcc"]),
+                (ArgumentKind::Other(PassEffect::StopsAt(CompilerPass::Compiling)), vec!["-c"]),
+                (ArgumentKind::Source { binary: false }, vec!["main.c"]),
+            ],
+        )
+        .with_target(Some("aarch64-linux-gnu".to_string()));
+
+        let disabled = CommandConverter::new(Format::default());
+        let expected = vec![Entry::from_arguments_str(
+            "main.c",
+            vec!["/usr/bin/aarch64-linux-gnu-gcc", "-c", "main.c"],
+            "/home/user",
+            None,
+        )];
+        assert_eq!(disabled.to_entries(&command), expected);
+
+        let format = Format {
+            entries: EntryFormat { include_target_from_prefix: true, ..EntryFormat::default() },
+            ..Format::default()
+        };
+        let enabled = CommandConverter::new(format);
+        let expected = vec![Entry::from_arguments_str(
+            "main.c",
+            vec!["/usr/bin/aarch64-linux-gnu-gcc", "--target=aarch64-linux-gnu", "-c", "main.c"],
+            "/home/user",
+            None,
+        )];
+        assert_eq!(enabled.to_entries(&command), expected);
+    }
+
+    #[test]
+    fn test_to_entries_with_target_from_prefix_keeps_explicit_target() {
+        let command = Command::from_strings(
+            "/home/user",
+            "/usr/bin/aarch64-linux-gnu-clang",
+            vec![
+                (ArgumentKind::Compiler, vec!["aarch64-linux-gnu-clang"]),
+                (
+                    ArgumentKind::Other(PassEffect::Configures(CompilerPass::Compiling)),
+                    vec!["-target", "armv7a-none-eabi"],
+                ),
+                (ArgumentKind::Other(PassEffect::StopsAt(CompilerPass::Compiling)), vec!["-c"]),
+                (ArgumentKind::Source { binary: false }, vec!["main.c"]),
+            ],
+        )
+        .with_target(Some("aarch64-linux-gnu".to_string()));
+
+        let format = Format {
+            entries: EntryFormat { include_target_from_prefix: true, ..EntryFormat::default() },
+            ..Format::default()
+        };
+        let sut = CommandConverter::new(format);
+        let expected = vec![Entry::from_arguments_str(
+            "main.c",
+            vec!["/usr/bin/aarch64-linux-gnu-clang", "-target", "armv7a-none-eabi", "-c", "main.c"],
+            "/home/user",
+            None,
+        )];
+        assert_eq!(sut.to_entries(&command), expected);
+    }
+
+    #[test]
+    fn test_to_entries_formats_paths_in_flags() {
+        let preprocessing = ArgumentKind::Other(PassEffect::Configures(CompilerPass::Preprocessing));
+        let path = |arguments: &[&str], flag: &'static str, glue: Option<char>| Argument::Other {
+            arguments: arguments.iter().map(|arg| arg.to_string()).collect(),
+            kind: preprocessing,
+            clang: ClangCompatibility::Supported,
+            value: FlagValue::Path { flag, glue },
+        };
+        let command = Command::new(
+            PathBuf::from("/home/user"),
+            PathBuf::from("/usr/bin/gcc"),
+            vec![
+                Argument::Other {
+                    arguments: vec!["gcc".to_string()],
+                    kind: ArgumentKind::Compiler,
+                    clang: ClangCompatibility::Supported,
+                    value: FlagValue::Plain,
+                },
+                path(&["-I", "include"], "-I", None),
+                path(&["-Igenerated"], "-I", None),
+                path(&["--sysroot=sysroot"], "--sysroot", Some('=')),
+                path(&["-isystem", "=/usr/include"], "-isystem", None),
+                path(&["-I/opt/include"], "-I", None),
+                Argument::new_source("main.c".to_string()),
+            ],
+        );
+        let format = Format {
+            paths: PathFormat {
+                directory: PathResolver::Absolute,
+                file: PathResolver::Absolute,
+                ..PathFormat::default()
+            },
+            entries: EntryFormat { include_output_field: false, ..EntryFormat::default() },
+            ..Format::default()
+        };
+        let sut = CommandConverter::new(format);
+
+        let expected = vec![Entry::from_arguments_str(
+            "/home/user/main.c",
+            vec![
+                "/usr/bin/gcc",
+                "-I",
+                "/home/user/include",
+                "-I/home/user/generated",
+                "--sysroot=/home/user/sysroot",
+                "-isystem",
+                "=/usr/include",
+                "-I/opt/include",
+                "/home/user/main.c",
+            ],
+            "/home/user",
+            None,
+        )];
+        assert_eq!(sut.to_entries(&command), expected);
+    }
+
+    #[test]
+    fn test_to_flags_strips_compiler_source_and_output() {
+        let preprocessing = ArgumentKind::Other(PassEffect::Configures(CompilerPass::Preprocessing));
+        let command = Command::new(
+            PathBuf::from("/home/user"),
+            PathBuf::from("/usr/bin/gcc"),
+            vec![
+                Argument::Other {
+                    arguments: vec!["gcc".to_string()],
+                    kind: ArgumentKind::Compiler,
+                    clang: ClangCompatibility::Supported,
+                    value: FlagValue::Plain,
+                },
+                Argument::Other {
+                    arguments: vec!["-c".to_string()],
+                    kind: ArgumentKind::Other(PassEffect::StopsAt(CompilerPass::Compiling)),
+                    clang: ClangCompatibility::Supported,
+                    value: FlagValue::Plain,
+                },
+                Argument::Other {
+                    arguments: vec!["-I".to_string(), "include".to_string()],
+                    kind: preprocessing,
+                    clang: ClangCompatibility::Supported,
+                    value: FlagValue::Path { flag: "-I", glue: None },
+                },
+                Argument::Other {
+                    arguments: vec!["-DFOO".to_string()],
+                    kind: preprocessing,
+                    clang: ClangCompatibility::Supported,
+                    value: FlagValue::Plain,
+                },
+                Argument::Other {
+                    arguments: vec!["-lm".to_string()],
+                    kind: ArgumentKind::Other(PassEffect::Configures(CompilerPass::Linking)),
+                    clang: ClangCompatibility::Supported,
+                    value: FlagValue::Plain,
+                },
+                Argument::new_source("main.c".to_string()),
+                Argument::new_source("util.c".to_string()),
+                Argument::Output { flag: "-o".to_string(), path: "main".to_string() },
+            ],
+        );
+        let format = Format {
+            paths: PathFormat {
+                directory: PathResolver::Absolute,
+                file: PathResolver::Absolute,
+                ..PathFormat::default()
+            },
+            ..Format::default()
+        };
+        let sut = CommandConverter::new(format);
+
+        let flags = vec![vec!["-I".to_string(), "/home/user/include".to_string()], vec!["-DFOO".to_string()]];
+        let expected = vec![
+            (PathBuf::from("/home/user/main.c"), flags.clone()),
+            (PathBuf::from("/home/user/util.c"), flags),
+        ];
+        assert_eq!(sut.to_flags(&command), expected);
+    }
+
+    #[test]
+    fn test_to_entries_clang_compatible_drops_and_renames_flags() {
+        let compiling = ArgumentKind::Other(PassEffect::Configures(CompilerPass::Compiling));
+        let other = |arguments: &[&str], kind: ArgumentKind, clang: ClangCompatibility| Argument::Other {
+            arguments: arguments.iter().map(|arg| arg.to_string()).collect(),
+            kind,
+            clang,
+            value: FlagValue::Plain,
+        };
+        let command = Command::new(
+            PathBuf::from("/home/user"),
+            PathBuf::from("/usr/bin/gcc"),
+            vec![
+                other(&["/usr/bin/gcc"], ArgumentKind::Compiler, ClangCompatibility::Supported),
+                other(
+                    &["-c"],
+                    ArgumentKind::Other(PassEffect::StopsAt(CompilerPass::Compiling)),
+                    ClangCompatibility::Supported,
+                ),
+                other(&["-fconserve-stack"], compiling, ClangCompatibility::Unsupported),
+                other(
+                    &["-Wno-maybe-uninitialized"],
+                    ArgumentKind::Other(PassEffect::None),
+                    ClangCompatibility::Equivalent {
+                        flag: "-Wno-maybe-uninitialized",
+                        equivalent: "-Wno-uninitialized",
+                    },
+                ),
+                other(&["-O2"], compiling, ClangCompatibility::Supported),
+                Argument::Source { path: "main.c".to_string(), binary: false },
+            ],
+        );
+
+        let disabled = CommandConverter::new(Format::default());
+        let expected = vec![Entry::from_arguments_str(
+            "main.c",
+            vec!["/usr/bin/gcc", "-c", "-fconserve-stack", "-Wno-maybe-uninitialized", "-O2", "main.c"],
+            "/home/user",
+            None,
+        )];
+        assert_eq!(disabled.to_entries(&command), expected);
+
+        let format = Format {
+            entries: EntryFormat { clang_compatible: true, ..EntryFormat::default() },
+            ..Format::default()
+        };
+        let enabled = CommandConverter::new(format);
+        let expected = vec![Entry::from_arguments_str(
+            "main.c",
+            vec!["/usr/bin/gcc", "-c", "-Wno-uninitialized", "-O2", "main.c"],
+            "/home/user",
+            None,
+        )];
+        assert_eq!(enabled.to_entries(&command), expected);
+    }
+
+    #[test]
+    fn test_to_entries_with_argument_rules() {
+        let command = Command::from_strings(
+            "/home/user",
+            "/usr/bin/g++",
+            vec![
+                (ArgumentKind::Compiler, vec!["g++"]),
+                (ArgumentKind::Other(PassEffect::StopsAt(CompilerPass::Compiling)), vec!["-c"]),
+                (ArgumentKind::Other(PassEffect::None), vec!["-Werror"]),
+                (ArgumentKind::Other(PassEffect::Configures(CompilerPass::Compiling)), vec!["-std=gnu++2b"]),
+                (ArgumentKind::Source { binary: false }, vec!["main.cpp"]),
+            ],
+        )
+        .with_compiler(CompilerType::Gcc);
+        let format = Format {
+            entries: EntryFormat { include_output_field: false, ..EntryFormat::default() },
+            arguments: vec![
+                ArgumentRule {
+                    action: ArgumentAction::Remove,
+                    match_: Some(ArgumentMatch::Exact("-Werror".into())),
+                    arguments: vec![],
+                    compiler: None,
+                },
+                ArgumentRule {
+                    action: ArgumentAction::Replace,
+                    match_: Some(ArgumentMatch::Prefix("-std=gnu++2b".into())),
+                    arguments: vec!["-std=c++23".into()],
+                    compiler: Some(CompilerType::Gcc),
+                },
+                ArgumentRule {
+                    action: ArgumentAction::Append,
+                    match_: None,
+                    arguments: vec!["-Wno-unknown-warning-option".into()],
+                    compiler: None,
+                },
+                ArgumentRule {
+                    action: ArgumentAction::Prepend,
+                    match_: None,
+                    arguments: vec!["--driver-mode=g++".into()],
+                    compiler: Some(CompilerType::Clang),
+                },
+            ],
+            ..Format::default()
+        };
+        let sut = CommandConverter::new(format);
+
+        let expected = vec![Entry::from_arguments_str(
+            "main.cpp",
+            vec!["/usr/bin/g++", "-c", "-std=c++23", "main.cpp", "-Wno-unknown-warning-option"],
+            "/home/user",
+            None,
+        )];
+        assert_eq!(sut.to_entries(&command), expected);
+    }
+
+    #[test]
+    fn test_to_entries_with_executable_rules() {
+        let command = |executable: &str, compiler: CompilerType| {
+            Command::from_strings(
+                "/home/user",
+                executable,
+                vec![
+                    (ArgumentKind::Compiler, vec![executable]),
+                    (ArgumentKind::Other(PassEffect::StopsAt(CompilerPass::Compiling)), vec!["-c"]),
+                    (ArgumentKind::Source { binary: false }, vec!["main.c"]),
+                ],
+            )
+            .with_compiler(compiler)
+        };
+        let format = Format {
+            entries: EntryFormat { include_output_field: false, ..EntryFormat::default() },
+            executables: vec![
+                ExecutableRule {
+                    path: Some(PathBuf::from("/usr/lib/ccache/gcc")),
+                    compiler: None,
+                    replacement: ExecutableReplacement::Path(PathBuf::from("/usr/bin/gcc")),
+                },
+                ExecutableRule {
+                    path: None,
+                    compiler: Some(CompilerType::Clang),
+                    replacement: ExecutableReplacement::Basename,
+                },
+            ],
+            ..Format::default()
+        };
+        let sut = CommandConverter::new(format);
+
+        let entries = sut.to_entries(&command("/usr/lib/ccache/gcc", CompilerType::Gcc));
+        assert_eq!(
+            entries,
+            vec![Entry::from_arguments_str(
+                "main.c",
+                vec!["/usr/bin/gcc", "-c", "main.c"],
+                "/home/user",
+                None
+            )]
+        );
+
+        let entries = sut.to_entries(&command("/opt/toolchain/bin/clang", CompilerType::Clang));
+        assert_eq!(
+            entries,
+            vec![Entry::from_arguments_str("main.c", vec!["clang", "-c", "main.c"], "/home/user", None)]
+        );
+
+        let entries = sut.to_entries(&command("/usr/bin/gcc-13", CompilerType::Gcc));
+        assert_eq!(
+            entries,
+            vec![Entry::from_arguments_str(
+                "main.c",
+                vec!["/usr/bin/gcc-13", "-c", "main.c"],
+                "/home/user",
+                None
+            )]
+        );
+    }
+
     #[test]
     fn test_command_converter_public_api() {
         // Test that CommandConverter can be used as a public API
         let format = Format {
             paths: PathFormat::default(),
-            entries: EntryFormat { use_array_format: true, include_output_field: false },
+            entries: EntryFormat {
+                use_array_format: true,
+                include_output_field: false,
+                ..EntryFormat::default()
+            },
+            ..Format::default()
         };
         let converter = CommandConverter::new(format);
 
@@ -629,7 +1186,7 @@ mod tests {
             .returning(|_, file| Ok(file.to_path_buf()));
 
         let converter = CommandConverter::with_formatter(
-            EntryFormat { include_output_field: true, use_array_format: true },
+            EntryFormat { include_output_field: true, use_array_format: true, ..EntryFormat::default() },
             Box::new(mock_formatter),
         );
 
@@ -717,7 +1274,12 @@ mod tests {
     fn test_compile_and_link_filters_linking_flags() {
         let format = Format {
             paths: PathFormat::default(),
-            entries: EntryFormat { use_array_format: true, include_output_field: false },
+            entries: EntryFormat {
+                use_array_format: true,
+                include_output_field: false,
+                ..EntryFormat::default()
+            },
+            ..Format::default()
         };
         let converter = CommandConverter::new(format);
 
@@ -882,7 +1444,12 @@ mod tests {
     fn test_consistent_formatting_methods() {
         let format = Format {
             paths: PathFormat::default(),
-            entries: EntryFormat { use_array_format: true, include_output_field: true },
+            entries: EntryFormat {
+                use_array_format: true,
+                include_output_field: true,
+                ..EntryFormat::default()
+            },
+            ..Format::default()
         };
         let converter = CommandConverter::new(format);
 
@@ -998,8 +1565,13 @@ mod tests {
         std::fs::write(temp_path.join("main.c"), "").unwrap();
 
         let format = Format {
-            paths: PathFormat { directory: PathResolver::Absolute, file: PathResolver::Absolute },
+            paths: PathFormat {
+                directory: PathResolver::Absolute,
+                file: PathResolver::Absolute,
+                ..PathFormat::default()
+            },
             entries: EntryFormat::default(),
+            ..Format::default()
         };
         let converter = CommandConverter::new(format);
 
@@ -1025,6 +1597,42 @@ mod tests {
         assert!(entry.file.is_absolute(), "file should be absolute: {:?}", entry.file);
     }
 
+    // Requirements: output-merge
+    #[test]
+    fn test_format_entry_applies_path_rules() {
+        let format = Format {
+            paths: PathFormat {
+                directory: PathResolver::Absolute,
+                file: PathResolver::Absolute,
+                remap: vec![PathRemap {
+                    from: PathBuf::from("/work"),
+                    to: PathBuf::from("/home/user/project"),
+                }],
+                ..PathFormat::default()
+            },
+            ..Format::default()
+        };
+        let converter = CommandConverter::new(format);
+
+        let entry = Entry::from_arguments_str(
+            "src/main.c",
+            vec!["gcc", "-c", "src/main.c"],
+            "/work/build",
+            Some("main.o"),
+        );
+        let result = converter.format_entry(entry);
+
+        assert_eq!(
+            result,
+            Entry::from_arguments_str(
+                "/home/user/project/build/src/main.c",
+                vec!["gcc", "-c", "src/main.c"],
+                "/home/user/project/build",
+                Some("/home/user/project/build/main.o"),
+            )
+        );
+    }
+
     #[test]
     fn test_relative_path_format_makes_paths_relative() {
         let temp_dir = tempfile::tempdir().unwrap();
@@ -1035,8 +1643,13 @@ mod tests {
         std::fs::write(&source_file, "").unwrap();
 
         let format = Format {
-            paths: PathFormat { directory: PathResolver::Relative, file: PathResolver::Relative },
+            paths: PathFormat {
+                directory: PathResolver::Relative,
+                file: PathResolver::Relative,
+                ..PathFormat::default()
+            },
             entries: EntryFormat::default(),
+            ..Format::default()
         };
         let converter = CommandConverter::new(format);
 
@@ -1078,8 +1691,13 @@ mod tests {
         let working_dir_str = working_dir_with_dotdot.to_str().unwrap();
 
         let format = Format {
-            paths: PathFormat { directory: PathResolver::Canonical, file: PathResolver::Canonical },
+            paths: PathFormat {
+                directory: PathResolver::Canonical,
+                file: PathResolver::Canonical,
+                ..PathFormat::default()
+            },
             entries: EntryFormat::default(),
+            ..Format::default()
         };
         let converter = CommandConverter::new(format);
 
@@ -1114,8 +1732,13 @@ mod tests {
         std::fs::write(temp_path.join("main.c"), "").unwrap();
 
         let format = Format {
-            paths: PathFormat { directory: PathResolver::Absolute, file: PathResolver::Relative },
+            paths: PathFormat {
+                directory: PathResolver::Absolute,
+                file: PathResolver::Relative,
+                ..PathFormat::default()
+            },
             entries: EntryFormat::default(),
+            ..Format::default()
         };
         let converter = CommandConverter::new(format);
 
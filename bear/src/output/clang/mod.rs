@@ -7,11 +7,14 @@
 //! along with the [`CommandConverter`] for creating entries from semantic commands.
 
 pub mod converter;
-mod json;
+pub(crate) mod json;
 mod path_format;
+mod rewrite;
 pub(crate) mod serialization;
 
+use crate::intercept::Execution;
 use shell_words;
+use std::collections::HashMap;
 use std::path;
 use thiserror::Error;
 
@@ -105,6 +108,18 @@ impl Entry {
         Ok(())
     }
 
+    /// Returns the command of the entry as a list of arguments.
+    ///
+    /// The `command` field is split by the shell quoting rules, when the
+    /// `arguments` field is empty.
+    pub fn to_arguments(&self) -> Result<Vec<String>, EntryError> {
+        if self.arguments.is_empty() {
+            Ok(shell_words::split(&self.command)?)
+        } else {
+            Ok(self.arguments.clone())
+        }
+    }
+
     /// Constructor method for testing purposes.
     #[cfg(test)]
     pub fn from_arguments_str(
@@ -135,6 +150,27 @@ impl Entry {
     }
 }
 
+impl TryFrom<&Entry> for Execution {
+    type Error = EntryError;
+
+    /// Converts the entry back into the execution of its command.
+    ///
+    /// The executable is the first argument of the command, the working
+    /// directory is the `directory` of the entry. The compilation database
+    /// does not record the environment, so it is empty.
+    fn try_from(entry: &Entry) -> Result<Self, Self::Error> {
+        let arguments = entry.to_arguments()?;
+        let executable = arguments.first().ok_or(EntryError::CommandOrArgumentsAreMissing)?;
+
+        Ok(Execution {
+            executable: path::PathBuf::from(executable),
+            arguments,
+            working_dir: entry.directory.clone(),
+            environment: HashMap::new(),
+        })
+    }
+}
+
 /// Represents the possible errors that can occur when validating an entry.
 #[derive(Debug, Eq, PartialEq, Error)]
 pub enum EntryError {
@@ -214,6 +250,27 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_entry_to_execution() {
+        let entry = Entry::from_command_str("main.c", "cc -c -DNAME='\"my lib\"' main.c", "/home/user", None);
+
+        let execution = Execution::try_from(&entry).unwrap();
+
+        assert_eq!(
+            execution,
+            Execution::from_strings(
+                "cc",
+                vec!["cc", "-c", "-DNAME=\"my lib\"", "main.c"],
+                "/home/user",
+                HashMap::new()
+            )
+        );
+        assert_eq!(
+            Execution::try_from(&Entry::from_command_str("main.c", " ", "/home/user", None)),
+            Err(EntryError::CommandOrArgumentsAreMissing)
+        );
+    }
+
     #[test]
     fn test_entry_with_arguments_constructor() {
         let entry = Entry::with_arguments(
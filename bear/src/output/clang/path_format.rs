@@ -11,10 +11,18 @@
 //! relative to the `directory` attribute.
 //!
 //! The `arguments` attribute contains the compiler flags, where some flags are using
-//! file paths. In the current implementation, the `arguments` attribute is not
-//! transformed.
+//! file paths. The values of the flags which are marked as paths in the compiler
+//! flag tables are formatted the same way as the `file` attribute.
+//!
+//! The configured prefix remapping rules translate the paths of the build
+//! environment to the view of the consumer (e.g. from a container to the host).
+//!
+//! To make the database portable between checkouts, the `directory` can be made
+//! relative to the project root, and the project root can be written as a
+//! placeholder (like `${root}`) at the beginning of the absolute paths.
 
 use crate::config::{PathFormat, PathResolver};
+use std::borrow::Cow;
 use std::io;
 use std::path::{Path, PathBuf, absolute};
 use thiserror::Error;
@@ -43,6 +51,8 @@ pub(super) trait PathFormatter: Send {
 /// how to format paths.
 pub(super) struct ConfigurablePathFormatter {
     config: PathFormat,
+    /// The project root, as it looks after the remapping.
+    root: Option<PathBuf>,
 }
 
 impl ConfigurablePathFormatter {
@@ -50,20 +60,86 @@ impl ConfigurablePathFormatter {
     ///
     /// The configuration is expected to be pre-validated by the config loader.
     pub fn new(config: PathFormat) -> Self {
-        Self { config }
+        let root = config.root.as_deref().map(|root| config.remap(root));
+        Self { config, root }
+    }
+
+    /// Replaces the project root at the beginning of the path with the
+    /// placeholder, when configured.
+    fn with_placeholder(&self, path: PathBuf) -> PathBuf {
+        match (&self.config.root_placeholder, &self.root) {
+            (Some(placeholder), Some(root)) => match path.strip_prefix(root) {
+                Ok(rest) => join_remapped(Path::new(placeholder), rest),
+                Err(_) => path,
+            },
+            _ => path,
+        }
+    }
+
+    /// Turns a formatted directory back to a path which files can be resolved against.
+    fn expand_directory<'a>(&self, directory: &'a Path) -> Cow<'a, Path> {
+        let Some(root) = &self.root else {
+            return Cow::Borrowed(directory);
+        };
+        if let Some(placeholder) = &self.config.root_placeholder
+            && let Ok(rest) = directory.strip_prefix(placeholder)
+        {
+            return Cow::Owned(join_remapped(root, rest));
+        }
+        if self.config.directory == PathResolver::RootRelative && directory.is_relative() {
+            return Cow::Owned(root.join(directory));
+        }
+        Cow::Borrowed(directory)
     }
 }
 
 impl PathFormatter for ConfigurablePathFormatter {
     fn format_directory(&self, working_dir: &Path, directory: &Path) -> Result<PathBuf, FormatError> {
-        self.config.directory.resolve(working_dir, directory)
+        let resolved = match (self.config.directory, &self.root) {
+            (PathResolver::RootRelative, Some(root)) => {
+                let remapped = self.config.remap(&absolute_to(working_dir, directory)?);
+                PathResolver::RootRelative.resolve(root, &remapped)?
+            }
+            (resolver, _) => self.config.remap(&resolver.resolve(working_dir, directory)?),
+        };
+        Ok(self.with_placeholder(resolved))
     }
 
+    /// The `directory` is already remapped, therefore the file is remapped
+    /// before it gets resolved against it. Except for canonical paths, which
+    /// can only be resolved in the build environment.
     fn format_file(&self, directory: &Path, file: &Path) -> Result<PathBuf, FormatError> {
-        self.config.file.resolve(directory, file)
+        let directory = self.expand_directory(directory);
+        let resolved = match self.config.file {
+            PathResolver::Canonical => {
+                let resolved = self.config.file.resolve(&directory, file)?;
+                self.config.remap(&resolved)
+            }
+            resolver => resolver.resolve(&directory, &self.config.remap(file))?,
+        };
+        Ok(self.with_placeholder(resolved))
+    }
+}
+
+impl PathFormat {
+    /// Replaces the prefix of the path by the first matching remap rule.
+    ///
+    /// Prefixes are matched by whole path components (`/work` does not
+    /// match `/workspace`). Paths without a matching rule are returned as is.
+    pub fn remap(&self, path: &Path) -> PathBuf {
+        self.remap
+            .iter()
+            .find_map(|rule| path.strip_prefix(&rule.from).ok().map(|rest| join_remapped(&rule.to, rest)))
+            .unwrap_or_else(|| path.to_path_buf())
     }
 }
 
+/// Joins the remainder to the new prefix, without a trailing separator for
+/// an empty remainder.
+fn join_remapped(prefix: &Path, rest: &Path) -> PathBuf {
+    if rest.as_os_str().is_empty() { prefix.to_path_buf() } else { prefix.join(rest) }
+}
+
 impl PathResolver {
     /// Resolves a path according to the resolver strategy.
     ///
@@ -82,7 +158,7 @@ impl PathResolver {
                 let result = path.canonicalize()?;
                 Ok(strip_windows_extended_length_prefix(result))
             }
-            PathResolver::Relative => {
+            PathResolver::Relative | PathResolver::RootRelative => {
                 let absolute = absolute_to(base, path)?;
                 relative_to(base, &absolute)
             }
@@ -180,7 +256,7 @@ fn relative_to(root: &Path, path: &Path) -> Result<PathBuf, FormatError> {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::config::PathResolver;
+    use crate::config::{PathRemap, PathResolver};
     use std::fs;
     use tempfile::tempdir;
 
@@ -254,7 +330,8 @@ mod tests {
 
     #[test]
     fn test_path_formatter_format_directory() {
-        let config = PathFormat { directory: PathResolver::AsIs, file: PathResolver::AsIs };
+        let config =
+            PathFormat { directory: PathResolver::AsIs, file: PathResolver::AsIs, ..PathFormat::default() };
         let formatter = ConfigurablePathFormatter::new(config);
 
         let working_dir = PathBuf::from("/working");
@@ -266,7 +343,8 @@ mod tests {
 
     #[test]
     fn test_path_formatter_format_file() {
-        let config = PathFormat { directory: PathResolver::AsIs, file: PathResolver::AsIs };
+        let config =
+            PathFormat { directory: PathResolver::AsIs, file: PathResolver::AsIs, ..PathFormat::default() };
         let formatter = ConfigurablePathFormatter::new(config);
 
         let directory = PathBuf::from("/some/dir");
@@ -276,6 +354,92 @@ mod tests {
         assert_eq!(result, file);
     }
 
+    #[test]
+    fn test_path_remap() {
+        let config = PathFormat {
+            remap: vec![
+                PathRemap { from: PathBuf::from("/work/vendor"), to: PathBuf::from("/opt/vendor") },
+                PathRemap { from: PathBuf::from("/work"), to: PathBuf::from("/home/dev/project") },
+            ],
+            ..PathFormat::default()
+        };
+
+        assert_eq!(
+            config.remap(Path::new("/work/src/main.c")),
+            PathBuf::from("/home/dev/project/src/main.c")
+        );
+        assert_eq!(config.remap(Path::new("/work/vendor/lib.c")), PathBuf::from("/opt/vendor/lib.c"));
+        assert_eq!(config.remap(Path::new("/work")), PathBuf::from("/home/dev/project"));
+        // Only whole components are matched.
+        assert_eq!(config.remap(Path::new("/workspace/main.c")), PathBuf::from("/workspace/main.c"));
+        assert_eq!(config.remap(Path::new("src/main.c")), PathBuf::from("src/main.c"));
+    }
+
+    #[test]
+    fn test_path_formatter_with_remap() {
+        let config = PathFormat {
+            directory: PathResolver::Absolute,
+            file: PathResolver::Absolute,
+            remap: vec![PathRemap { from: PathBuf::from("/work"), to: PathBuf::from("/home/dev/project") }],
+            ..PathFormat::default()
+        };
+        let formatter = ConfigurablePathFormatter::new(config);
+
+        let directory =
+            formatter.format_directory(Path::new("/work/build"), Path::new("/work/build")).unwrap();
+        assert_eq!(directory, PathBuf::from("/home/dev/project/build"));
+
+        let file = formatter.format_file(&directory, Path::new("main.c")).unwrap();
+        assert_eq!(file, PathBuf::from("/home/dev/project/build/main.c"));
+
+        let file = formatter.format_file(&directory, Path::new("/work/include/config.h")).unwrap();
+        assert_eq!(file, PathBuf::from("/home/dev/project/include/config.h"));
+    }
+
+    // Requirements: output-path-root
+    #[test]
+    fn test_path_formatter_root_relative() {
+        let config = PathFormat {
+            directory: PathResolver::RootRelative,
+            file: PathResolver::Relative,
+            root: Some(PathBuf::from("/project")),
+            ..PathFormat::default()
+        };
+        let formatter = ConfigurablePathFormatter::new(config);
+
+        let directory = formatter.format_directory(Path::new("/project/build"), Path::new("src")).unwrap();
+        assert_eq!(directory, PathBuf::from("build/src"));
+
+        let file = formatter.format_file(&directory, Path::new("/project/build/src/main.c")).unwrap();
+        assert_eq!(file, PathBuf::from("main.c"));
+
+        let directory = formatter.format_directory(Path::new("/project"), Path::new("/project")).unwrap();
+        assert_eq!(directory, PathBuf::from("."));
+    }
+
+    // Requirements: output-path-root
+    #[test]
+    fn test_path_formatter_root_placeholder() {
+        let config = PathFormat {
+            directory: PathResolver::Absolute,
+            file: PathResolver::Absolute,
+            remap: vec![PathRemap { from: PathBuf::from("/work"), to: PathBuf::from("/project") }],
+            root: Some(PathBuf::from("/work")),
+            root_placeholder: Some("${root}".to_string()),
+        };
+        let formatter = ConfigurablePathFormatter::new(config);
+
+        let directory =
+            formatter.format_directory(Path::new("/work/build"), Path::new("/work/build")).unwrap();
+        assert_eq!(directory, PathBuf::from("${root}/build"));
+
+        let file = formatter.format_file(&directory, Path::new("main.c")).unwrap();
+        assert_eq!(file, PathBuf::from("${root}/build/main.c"));
+
+        let file = formatter.format_file(&directory, Path::new("/usr/include/stdio.h")).unwrap();
+        assert_eq!(file, PathBuf::from("/usr/include/stdio.h"));
+    }
+
     #[test]
     fn test_path_resolver_absolute_with_temp_files() {
         let temp_dir = tempdir().unwrap();
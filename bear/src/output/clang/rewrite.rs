@@ -0,0 +1,218 @@
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! Argument rewrite rules for compilation database entries.
+//!
+//! The rules come from the `format.arguments` section of the configuration.
+//! They are applied in the configured order to the flags of each entry, so
+//! post-processing of the compilation database (like dropping `-Werror` or
+//! replacing a `-std` value) is deterministic and versioned with the project.
+//!
+//! A flag is matched by its first element, and the whole flag (including its
+//! separate values, like `-include foo.h`) is removed or replaced.
+
+use crate::config::{self, ArgumentAction, CompilerType};
+use log::warn;
+use regex::Regex;
+
+/// Applies the configured argument rewrite rules.
+pub(super) struct ArgumentRewriter {
+    rules: Vec<Rule>,
+}
+
+struct Rule {
+    action: ArgumentAction,
+    matcher: Option<Matcher>,
+    arguments: Vec<String>,
+    compiler: Option<CompilerType>,
+}
+
+enum Matcher {
+    Exact(String),
+    Prefix(String),
+    Regex(Regex),
+}
+
+impl ArgumentRewriter {
+    /// Creates the rewriter from the configured rules.
+    ///
+    /// Rules with invalid regular expressions are rejected by the configuration
+    /// validation already, here they are skipped with a warning.
+    pub(super) fn new(rules: &[config::ArgumentRule]) -> Self {
+        let rules = rules
+            .iter()
+            .filter_map(|rule| {
+                let matcher = match &rule.match_ {
+                    None => None,
+                    Some(config::ArgumentMatch::Exact(value)) => Some(Matcher::Exact(value.clone())),
+                    Some(config::ArgumentMatch::Prefix(value)) => Some(Matcher::Prefix(value.clone())),
+                    Some(config::ArgumentMatch::Regex(pattern)) => {
+                        match Regex::new(&format!("^(?:{pattern})$")) {
+                            Ok(regex) => Some(Matcher::Regex(regex)),
+                            Err(error) => {
+                                warn!("Skipping argument rule with invalid regex '{}': {}", pattern, error);
+                                return None;
+                            }
+                        }
+                    }
+                };
+                Some(Rule {
+                    action: rule.action,
+                    matcher,
+                    arguments: rule.arguments.clone(),
+                    compiler: rule.compiler,
+                })
+            })
+            .collect();
+        Self { rules }
+    }
+
+    /// Rewrites a single flag with the `remove` and `replace` rules.
+    pub(super) fn rewrite(&self, compiler: Option<CompilerType>, flag: Vec<String>) -> Vec<String> {
+        self.rules_for(compiler).fold(flag, |flag, rule| {
+            let Some(first) = flag.first() else {
+                return flag;
+            };
+            match (rule.action, &rule.matcher) {
+                (ArgumentAction::Remove, Some(matcher)) if matcher.is_match(first) => vec![],
+                (ArgumentAction::Replace, Some(matcher)) if matcher.is_match(first) => {
+                    matcher.replacement(first, &rule.arguments)
+                }
+                _ => flag,
+            }
+        })
+    }
+
+    /// Returns the arguments to insert right after the compiler executable.
+    pub(super) fn prepended(&self, compiler: Option<CompilerType>) -> Vec<String> {
+        self.inserted(compiler, ArgumentAction::Prepend)
+    }
+
+    /// Returns the arguments to add to the end of the flags.
+    pub(super) fn appended(&self, compiler: Option<CompilerType>) -> Vec<String> {
+        self.inserted(compiler, ArgumentAction::Append)
+    }
+
+    fn inserted(&self, compiler: Option<CompilerType>, action: ArgumentAction) -> Vec<String> {
+        self.rules_for(compiler)
+            .filter(|rule| rule.action == action)
+            .flat_map(|rule| rule.arguments.iter().cloned())
+            .collect()
+    }
+
+    fn rules_for(&self, compiler: Option<CompilerType>) -> impl Iterator<Item = &Rule> {
+        self.rules.iter().filter(move |rule| rule.compiler.is_none() || rule.compiler == compiler)
+    }
+}
+
+impl Matcher {
+    fn is_match(&self, argument: &str) -> bool {
+        match self {
+            Matcher::Exact(value) => argument == value,
+            Matcher::Prefix(value) => argument.starts_with(value.as_str()),
+            Matcher::Regex(regex) => regex.is_match(argument),
+        }
+    }
+
+    /// Returns the replacement arguments, expanding capture group
+    /// references (like `$1`) for regular expression matches.
+    fn replacement(&self, argument: &str, arguments: &[String]) -> Vec<String> {
+        match self {
+            Matcher::Regex(regex) => match regex.captures(argument) {
+                Some(captures) => arguments
+                    .iter()
+                    .map(|template| {
+                        let mut result = String::new();
+                        captures.expand(template, &mut result);
+                        result
+                    })
+                    .collect(),
+                None => arguments.to_vec(),
+            },
+            _ => arguments.to_vec(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{ArgumentMatch, ArgumentRule};
+
+    fn rule(action: ArgumentAction, match_: Option<ArgumentMatch>, arguments: &[&str]) -> ArgumentRule {
+        ArgumentRule {
+            action,
+            match_,
+            arguments: arguments.iter().map(|argument| argument.to_string()).collect(),
+            compiler: None,
+        }
+    }
+
+    fn flag(arguments: &[&str]) -> Vec<String> {
+        arguments.iter().map(|argument| argument.to_string()).collect()
+    }
+
+    #[test]
+    fn test_remove_matches_exact_prefix_and_regex() {
+        let sut = ArgumentRewriter::new(&[
+            rule(ArgumentAction::Remove, Some(ArgumentMatch::Exact("-Werror".into())), &[]),
+            rule(ArgumentAction::Remove, Some(ArgumentMatch::Prefix("-fmodules".into())), &[]),
+            rule(ArgumentAction::Remove, Some(ArgumentMatch::Regex("-include|-imacros".into())), &[]),
+        ]);
+
+        assert_eq!(sut.rewrite(None, flag(&["-Werror"])), flag(&[]));
+        assert_eq!(sut.rewrite(None, flag(&["-Werror=format"])), flag(&["-Werror=format"]));
+        assert_eq!(sut.rewrite(None, flag(&["-fmodules-ts"])), flag(&[]));
+        assert_eq!(sut.rewrite(None, flag(&["-include", "config.h"])), flag(&[]));
+        // The regex has to match the whole flag.
+        assert_eq!(sut.rewrite(None, flag(&["-includes"])), flag(&["-includes"]));
+        assert_eq!(sut.rewrite(None, flag(&["-O2"])), flag(&["-O2"]));
+    }
+
+    #[test]
+    fn test_replace_expands_regex_captures() {
+        let sut = ArgumentRewriter::new(&[
+            rule(ArgumentAction::Replace, Some(ArgumentMatch::Exact("-std=gnu++2b".into())), &["-std=c++23"]),
+            rule(ArgumentAction::Replace, Some(ArgumentMatch::Regex("-std=gnu(\\d+)".into())), &["-std=c$1"]),
+        ]);
+
+        assert_eq!(sut.rewrite(None, flag(&["-std=gnu++2b"])), flag(&["-std=c++23"]));
+        assert_eq!(sut.rewrite(None, flag(&["-std=gnu11"])), flag(&["-std=c11"]));
+    }
+
+    #[test]
+    fn test_rules_are_applied_in_order() {
+        let sut = ArgumentRewriter::new(&[
+            rule(ArgumentAction::Replace, Some(ArgumentMatch::Exact("-O3".into())), &["-O2"]),
+            rule(ArgumentAction::Remove, Some(ArgumentMatch::Exact("-O2".into())), &[]),
+        ]);
+
+        assert_eq!(sut.rewrite(None, flag(&["-O3"])), flag(&[]));
+    }
+
+    #[test]
+    fn test_prepend_and_append() {
+        let sut = ArgumentRewriter::new(&[
+            rule(ArgumentAction::Append, None, &["-Wno-unknown-warning-option"]),
+            rule(ArgumentAction::Prepend, None, &["-D__BEAR__"]),
+            rule(ArgumentAction::Append, None, &["-w"]),
+        ]);
+
+        assert_eq!(sut.prepended(None), flag(&["-D__BEAR__"]));
+        assert_eq!(sut.appended(None), flag(&["-Wno-unknown-warning-option", "-w"]));
+    }
+
+    #[test]
+    fn test_rules_for_specific_compiler() {
+        let sut = ArgumentRewriter::new(&[ArgumentRule {
+            compiler: Some(CompilerType::Gcc),
+            ..rule(ArgumentAction::Remove, Some(ArgumentMatch::Exact("-fconserve-stack".into())), &[])
+        }]);
+
+        assert_eq!(sut.rewrite(Some(CompilerType::Gcc), flag(&["-fconserve-stack"])), flag(&[]));
+        assert_eq!(
+            sut.rewrite(Some(CompilerType::Clang), flag(&["-fconserve-stack"])),
+            flag(&["-fconserve-stack"])
+        );
+        assert_eq!(sut.rewrite(None, flag(&["-fconserve-stack"])), flag(&["-fconserve-stack"]));
+    }
+}
@@ -1,16 +1,22 @@
 // SPDX-License-Identifier: GPL-3.0-or-later
 
-//! JSON compilation database serialization format.
+//! JSON compilation database serialization formats.
 //!
-//! The format is a JSON array format, which is a sequence of JSON objects
+//! The standard format is a JSON array format, which is a sequence of JSON objects
 //! enclosed in square brackets. Each object represents a compilation command.
 //!
 //! The format itself is defined in the LLVM project documentation.
 //! https://clang.llvm.org/docs/JSONCompilationDatabase.html
+//!
+//! The JSON lines format has the same objects, one per line, without the
+//! enclosing array. It can be appended to and consumed incrementally.
 
 use super::Entry;
 use super::json;
 use crate::output::{SerializationError, SerializationFormat};
+use serde_json::StreamDeserializer;
+use serde_json::de::IoRead;
+use std::io;
 
 /// The type represents a JSON compilation database format.
 pub struct JsonCompilationDatabase;
@@ -41,6 +47,59 @@ impl SerializationFormat<Entry> for JsonCompilationDatabase {
     }
 }
 
+/// The type represents a JSON lines compilation database format.
+pub struct JsonLinesCompilationDatabase;
+
+impl SerializationFormat<Entry> for JsonLinesCompilationDatabase {
+    /// Serialize entries as one JSON object per line.
+    ///
+    /// Entries are expected to already be validated, same as for the
+    /// `JsonCompilationDatabase` format.
+    fn write(writer: impl io::Write, entries: impl Iterator<Item = Entry>) -> Result<(), SerializationError> {
+        let mut writer = writer;
+        for entry in entries {
+            serde_json::to_writer(&mut writer, &entry).map_err(SerializationError::Syntax)?;
+            writer.write_all(b"\n").map_err(SerializationError::Io)?;
+        }
+        writer.flush().map_err(SerializationError::Io)
+    }
+
+    fn read(reader: impl io::Read) -> impl Iterator<Item = Result<Entry, SerializationError>> {
+        StreamDeserializer::new(IoRead::new(reader)).map(|res| {
+            res.map_err(SerializationError::Syntax)
+                // Ensure only valid entries are returned.
+                .and_then(|entry: Entry| match entry.validate() {
+                    Ok(_) => Ok(entry),
+                    Err(err) => Err(SerializationError::Semantic(err)),
+                })
+        })
+    }
+}
+
+/// Tells whether the content is in the JSON lines format.
+///
+/// The JSON array format starts with `[`, the JSON lines format with `{`.
+/// Empty content is treated as JSON array. Leading whitespace is consumed.
+pub fn is_json_lines(reader: &mut impl io::BufRead) -> io::Result<bool> {
+    loop {
+        let buffer = reader.fill_buf()?;
+        if buffer.is_empty() {
+            return Ok(false);
+        }
+        match buffer.iter().position(|byte| !byte.is_ascii_whitespace()) {
+            Some(idx) => {
+                let json_lines = buffer[idx] != b'[';
+                reader.consume(idx);
+                return Ok(json_lines);
+            }
+            None => {
+                let length = buffer.len();
+                reader.consume(length);
+            }
+        }
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::JsonCompilationDatabase as Sut;
@@ -330,4 +389,52 @@ mod test {
 
         Ok(())
     }
+
+    mod json_lines {
+        use super::super::{JsonLinesCompilationDatabase as Sut, is_json_lines};
+        use super::*;
+
+        #[test]
+        fn read_write() -> Result<(), SerializationError> {
+            let input = expected_values_with_arguments();
+
+            let mut buffer = Cursor::new(Vec::new());
+            Sut::write(&mut buffer, input.into_iter())?;
+
+            let content = String::from_utf8(buffer.get_ref().clone()).unwrap();
+            assert_eq!(content.lines().count(), 2);
+
+            buffer.seek(SeekFrom::Start(0)).unwrap();
+            let result: Vec<Entry> = Sut::read(&mut buffer).map(|e| e.unwrap()).collect();
+            assert_eq!(expected_values_with_arguments(), result);
+
+            Ok(())
+        }
+
+        #[test]
+        fn read_invalid_entry() {
+            let content = format!(
+                "{}\n{}\n",
+                json!({ "directory": "", "file": "./file_a.c", "command": "cc -c ./file_a.c" }),
+                json!({ "directory": "/home/user", "file": "./file_b.c", "command": "cc -c ./file_b.c" })
+            );
+            let mut result = Sut::read(content.as_bytes());
+
+            assert_format_error!(result.next());
+            assert!(matches!(result.next(), Some(Ok(_))));
+            assert!(result.next().is_none());
+        }
+
+        #[test]
+        fn detect_format() {
+            let mut reader = std::io::BufReader::new("  \n[ {} ]".as_bytes());
+            assert!(!is_json_lines(&mut reader).unwrap());
+
+            let mut reader = std::io::BufReader::new("{}\n{}\n".as_bytes());
+            assert!(is_json_lines(&mut reader).unwrap());
+
+            let mut reader = std::io::BufReader::new("".as_bytes());
+            assert!(!is_json_lines(&mut reader).unwrap());
+        }
+    }
 }
@@ -0,0 +1,433 @@
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! Semantic difference of two compilation databases.
+//!
+//! The entries of the databases are matched by the source file and the output
+//! file (made absolute to the working directory of the entry). The unmatched
+//! entries are reported as added or removed translation units, the matched
+//! ones are compared flag by flag.
+//!
+//! The commands are split into flags with the compiler flag tables, the same
+//! way as the semantic analysis does. This keeps the flag values with their
+//! flags (`-I include` is one flag, not two arguments), so reordering the
+//! flags is not a difference. A removed and an added flag with the same name
+//! before the `=` sign are reported as a changed value (e.g. `-std=c11` to
+//! `-std=c17`, or `-DDEBUG=1` to `-DDEBUG=2`).
+
+use super::clang::Entry;
+use super::graph::resolve;
+use crate::intercept::Execution;
+use crate::semantic::{Argument, ArgumentKind, Interpreter, RecognizeResult};
+use std::collections::BTreeMap;
+use std::io;
+use std::path::PathBuf;
+
+/// Identifies a translation unit in the compilation databases.
+#[derive(Clone, Debug, Eq, Ord, PartialEq, PartialOrd, serde::Serialize)]
+pub struct Unit {
+    /// The absolute path of the source file.
+    pub file: PathBuf,
+    /// The absolute path of the output file, when the entry has one.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub output: Option<PathBuf>,
+}
+
+/// Represents a value which is different in the two databases.
+#[derive(Debug, PartialEq, serde::Serialize)]
+pub struct ValueChange {
+    pub old: String,
+    pub new: String,
+}
+
+/// Represents a flag with a different value in the two databases.
+#[derive(Debug, PartialEq, serde::Serialize)]
+pub struct FlagChange {
+    /// The name of the flag (the part before the `=` sign).
+    pub flag: String,
+    pub old: String,
+    pub new: String,
+}
+
+/// The differences of a translation unit which is in both databases.
+#[derive(Debug, PartialEq, serde::Serialize)]
+pub struct UnitChange {
+    #[serde(flatten)]
+    pub unit: Unit,
+    /// The compiler executable, when it was changed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub compiler: Option<ValueChange>,
+    /// The flags which are only in the new database.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub added: Vec<String>,
+    /// The flags which are only in the old database.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub removed: Vec<String>,
+    /// The flags with a different value.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub changed: Vec<FlagChange>,
+}
+
+/// The differences of two compilation databases.
+#[derive(Debug, Default, PartialEq, serde::Serialize)]
+pub struct DatabaseDiff {
+    /// The translation units which are only in the new database.
+    pub added: Vec<Unit>,
+    /// The translation units which are only in the old database.
+    pub removed: Vec<Unit>,
+    /// The translation units which are compiled differently.
+    pub changed: Vec<UnitChange>,
+}
+
+/// The command of an entry, split into flags.
+struct Flags {
+    compiler: String,
+    flags: Vec<String>,
+}
+
+impl DatabaseDiff {
+    /// Compares the entries of the old and the new database.
+    ///
+    /// The interpreter is used to split the commands into flags. Entries with
+    /// an invalid command are reported and skipped.
+    pub fn new(
+        interpreter: &dyn Interpreter,
+        old: impl Iterator<Item = Entry>,
+        new: impl Iterator<Item = Entry>,
+    ) -> Self {
+        let mut old = Self::units(interpreter, old);
+        let new = Self::units(interpreter, new);
+
+        let mut result = Self::default();
+        for (unit, new_flags) in new {
+            let mut old_flags = old.remove(&unit).unwrap_or_default().into_iter();
+            for new_flags in new_flags {
+                match old_flags.next() {
+                    Some(old_flags) => {
+                        if let Some(change) = Self::compare(&unit, old_flags, new_flags) {
+                            result.changed.push(change);
+                        }
+                    }
+                    None => result.added.push(unit.clone()),
+                }
+            }
+            result.removed.extend(old_flags.map(|_| unit.clone()));
+        }
+        for (unit, old_flags) in old {
+            result.removed.extend(old_flags.iter().map(|_| unit.clone()));
+        }
+        result.removed.sort();
+        result
+    }
+
+    /// Returns true when the databases are the same.
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+
+    /// Writes the differences as a JSON object with the `added`, `removed`
+    /// and `changed` arrays.
+    pub fn write_json(&self, writer: impl io::Write) -> Result<(), serde_json::Error> {
+        serde_json::to_writer_pretty(writer, self)
+    }
+
+    /// Writes the differences in a human-readable form.
+    pub fn write_text(&self, mut writer: impl io::Write) -> io::Result<()> {
+        for unit in &self.added {
+            writeln!(writer, "added: {unit}")?;
+        }
+        for unit in &self.removed {
+            writeln!(writer, "removed: {unit}")?;
+        }
+        for change in &self.changed {
+            writeln!(writer, "changed: {}", change.unit)?;
+            if let Some(compiler) = &change.compiler {
+                writeln!(writer, "  compiler: {} -> {}", compiler.old, compiler.new)?;
+            }
+            for flag in &change.added {
+                writeln!(writer, "  + {flag}")?;
+            }
+            for flag in &change.removed {
+                writeln!(writer, "  - {flag}")?;
+            }
+            for flag in &change.changed {
+                writeln!(writer, "  ~ {} -> {}", flag.old, flag.new)?;
+            }
+        }
+        writeln!(
+            writer,
+            "{} added, {} removed, {} changed translation units",
+            self.added.len(),
+            self.removed.len(),
+            self.changed.len()
+        )
+    }
+
+    /// Groups the entries by translation unit, keeping the order of the
+    /// entries with the same unit.
+    fn units(
+        interpreter: &dyn Interpreter,
+        entries: impl Iterator<Item = Entry>,
+    ) -> BTreeMap<Unit, Vec<Flags>> {
+        let mut result: BTreeMap<Unit, Vec<Flags>> = BTreeMap::new();
+        for entry in entries {
+            let unit = Unit {
+                file: resolve(&entry.directory, &entry.file),
+                output: entry.output.as_ref().map(|output| resolve(&entry.directory, output)),
+            };
+            match Self::flags(interpreter, entry) {
+                Some(flags) => result.entry(unit).or_default().push(flags),
+                None => log::warn!("Skipping entry of {unit}: invalid command"),
+            }
+        }
+        result
+    }
+
+    /// Splits the command of the entry into flags.
+    ///
+    /// The source and output arguments are not flags, these identify the unit.
+    /// When the compiler is not recognized, every argument is a flag.
+    fn flags(interpreter: &dyn Interpreter, entry: Entry) -> Option<Flags> {
+        let execution = Execution::try_from(&entry).ok()?;
+        let compiler = execution.arguments[0].clone();
+        let arguments = execution.arguments.clone();
+
+        let flags = match interpreter.recognize(execution) {
+            RecognizeResult::Recognized(cmd) => cmd
+                .arguments
+                .into_iter()
+                .filter_map(|argument| match argument {
+                    Argument::Other { kind: ArgumentKind::Compiler, .. } => None,
+                    Argument::Other { arguments, .. } => Some(arguments.join(" ")),
+                    Argument::Source { .. } | Argument::Output { .. } => None,
+                })
+                .collect(),
+            _ => arguments.into_iter().skip(1).collect(),
+        };
+        Some(Flags { compiler, flags })
+    }
+
+    /// Compares the flags of the same unit, returns the differences if any.
+    fn compare(unit: &Unit, old: Flags, new: Flags) -> Option<UnitChange> {
+        let compiler =
+            (old.compiler != new.compiler).then_some(ValueChange { old: old.compiler, new: new.compiler });
+
+        let mut removed = old.flags;
+        let mut added = vec![];
+        for flag in new.flags {
+            match removed.iter().position(|candidate| candidate == &flag) {
+                Some(index) => {
+                    removed.remove(index);
+                }
+                None => added.push(flag),
+            }
+        }
+
+        let mut changed = vec![];
+        removed.retain(|old_flag| {
+            let Some(name) = flag_name(old_flag) else {
+                return true;
+            };
+            match added.iter().position(|new_flag| flag_name(new_flag) == Some(name)) {
+                Some(index) => {
+                    let new = added.remove(index);
+                    changed.push(FlagChange { flag: name.to_string(), old: old_flag.clone(), new });
+                    false
+                }
+                None => true,
+            }
+        });
+
+        if compiler.is_none() && added.is_empty() && removed.is_empty() && changed.is_empty() {
+            None
+        } else {
+            Some(UnitChange { unit: unit.clone(), compiler, added, removed, changed })
+        }
+    }
+}
+
+/// Returns the name of a flag with a value after the `=` sign.
+fn flag_name(flag: &str) -> Option<&str> {
+    flag.split_once('=').map(|(name, _)| name)
+}
+
+impl std::fmt::Display for Unit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.file.display())?;
+        if let Some(output) = &self.output {
+            write!(f, " (output: {})", output.display())?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config;
+    use crate::semantic::interpreters;
+
+    fn diff(old: Vec<Entry>, new: Vec<Entry>) -> DatabaseDiff {
+        let interpreter = interpreters::create(&config::Main::default(), "/usr/bin:/bin".to_string());
+        DatabaseDiff::new(&interpreter, old.into_iter(), new.into_iter())
+    }
+
+    fn unit(file: &str, output: Option<&str>) -> Unit {
+        Unit { file: file.into(), output: output.map(PathBuf::from) }
+    }
+
+    // Requirements: output-diff
+    #[test]
+    fn test_same_databases() {
+        let old = vec![Entry::from_arguments_str(
+            "main.c",
+            vec!["/usr/bin/gcc", "-c", "-I", "include", "-Wall", "main.c"],
+            "/home/user",
+            None,
+        )];
+        // The order of the flags, and the form of the command does not matter.
+        let new = vec![Entry::from_command_str(
+            "main.c",
+            "/usr/bin/gcc -Wall -c main.c -I include",
+            "/home/user",
+            None,
+        )];
+
+        let result = diff(old, new);
+
+        assert!(result.is_empty());
+    }
+
+    // Requirements: output-diff
+    #[test]
+    fn test_added_and_removed_units() {
+        let old = vec![
+            Entry::from_arguments_str("main.c", vec!["/usr/bin/gcc", "-c", "main.c"], "/home/user", None),
+            Entry::from_arguments_str(
+                "old.c",
+                vec!["/usr/bin/gcc", "-c", "old.c", "-o", "old.o"],
+                "/home/user",
+                Some("old.o"),
+            ),
+        ];
+        let new = vec![
+            Entry::from_arguments_str("new.c", vec!["/usr/bin/gcc", "-c", "new.c"], "/home/user", None),
+            Entry::from_arguments_str(
+                "/home/user/main.c",
+                vec!["/usr/bin/gcc", "-c", "main.c"],
+                "/home/user",
+                None,
+            ),
+        ];
+
+        let result = diff(old, new);
+
+        assert_eq!(
+            result,
+            DatabaseDiff {
+                added: vec![unit("/home/user/new.c", None)],
+                removed: vec![unit("/home/user/old.c", Some("/home/user/old.o"))],
+                changed: vec![],
+            }
+        );
+    }
+
+    // Requirements: output-diff
+    #[test]
+    fn test_changed_flags() {
+        let old = vec![Entry::from_arguments_str(
+            "main.c",
+            vec!["/usr/bin/gcc", "-c", "-std=c11", "-DDEBUG=1", "-I", "include", "-Wall", "main.c"],
+            "/home/user",
+            None,
+        )];
+        let new = vec![Entry::from_arguments_str(
+            "main.c",
+            vec!["/usr/bin/clang", "-c", "-std=c17", "-DDEBUG=2", "-I", "include", "-DNEW", "main.c"],
+            "/home/user",
+            None,
+        )];
+
+        let result = diff(old, new);
+
+        assert_eq!(
+            result,
+            DatabaseDiff {
+                added: vec![],
+                removed: vec![],
+                changed: vec![UnitChange {
+                    unit: unit("/home/user/main.c", None),
+                    compiler: Some(ValueChange { old: "/usr/bin/gcc".into(), new: "/usr/bin/clang".into() }),
+                    added: vec!["-DNEW".into()],
+                    removed: vec!["-Wall".into()],
+                    changed: vec![
+                        FlagChange { flag: "-std".into(), old: "-std=c11".into(), new: "-std=c17".into() },
+                        FlagChange {
+                            flag: "-DDEBUG".into(),
+                            old: "-DDEBUG=1".into(),
+                            new: "-DDEBUG=2".into()
+                        },
+                    ],
+                }],
+            }
+        );
+    }
+
+    #[test]
+    fn test_flag_values_are_grouped() {
+        let old = vec![Entry::from_arguments_str(
+            "main.c",
+            vec!["/usr/bin/gcc", "-c", "-I", "old", "main.c"],
+            "/home/user",
+            None,
+        )];
+        let new = vec![Entry::from_arguments_str(
+            "main.c",
+            vec!["/usr/bin/gcc", "-c", "-I", "new", "main.c"],
+            "/home/user",
+            None,
+        )];
+
+        let result = diff(old, new);
+
+        assert_eq!(result.changed.len(), 1);
+        assert_eq!(result.changed[0].added, vec!["-I new".to_string()]);
+        assert_eq!(result.changed[0].removed, vec!["-I old".to_string()]);
+    }
+
+    #[test]
+    fn test_write_text() {
+        let sut = DatabaseDiff {
+            added: vec![unit("/home/user/new.c", None)],
+            removed: vec![unit("/home/user/old.c", Some("/home/user/old.o"))],
+            changed: vec![UnitChange {
+                unit: unit("/home/user/main.c", None),
+                compiler: None,
+                added: vec!["-DNEW".into()],
+                removed: vec!["-Wall".into()],
+                changed: vec![FlagChange {
+                    flag: "-std".into(),
+                    old: "-std=c11".into(),
+                    new: "-std=c17".into(),
+                }],
+            }],
+        };
+
+        let mut content = vec![];
+        sut.write_text(&mut content).unwrap();
+
+        assert_eq!(
+            String::from_utf8(content).unwrap(),
+            [
+                "added: /home/user/new.c",
+                "removed: /home/user/old.c (output: /home/user/old.o)",
+                "changed: /home/user/main.c",
+                "  + -DNEW",
+                "  - -Wall",
+                "  ~ -std=c11 -> -std=c17",
+                "1 added, 1 removed, 1 changed translation units",
+                "",
+            ]
+            .join("\n")
+        );
+    }
+}
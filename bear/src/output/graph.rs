@@ -0,0 +1,399 @@
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! Build artifact graph data model.
+//!
+//! The graph connects the files of the build: sources to objects, objects to
+//! archives and executables. The nodes are files, the edges are compiler,
+//! linker or archiver invocations, annotated with the recognized compiler
+//! type. Following the edges from a source file tells which binaries it ends
+//! up in.
+//!
+//! The edges are derived from the source and output arguments of the semantic
+//! commands. When the output is not given, the default output of the compiler
+//! driver is used (`main.o` for `-c`, `main.s` for `-S` and `a.out` for
+//! linking). The paths are made absolute (lexically, without touching the
+//! file system), so the same file is the same node across the invocations.
+
+use crate::args::GraphFormat;
+use crate::config::CompilerType;
+use crate::semantic::{Argument, ArgumentKind, Command, CompilerPass, PassEffect};
+use std::collections::{HashMap, HashSet};
+use std::io;
+use std::path::{Component, Path, PathBuf};
+
+/// The kind of the invocation which produces the target of an edge.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EdgeKind {
+    /// A compiler call which stops before linking (`-c`, `-S` or `-E`).
+    Compile,
+    /// A compiler driver call which links an executable or shared library.
+    Link,
+    /// An archiver call which creates a static library.
+    Archive,
+}
+
+/// Represents a file of the build.
+#[derive(Clone, Debug, Eq, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct Node {
+    /// The index of the node, the edges refer to it.
+    pub id: usize,
+    /// The absolute path of the file.
+    pub path: PathBuf,
+}
+
+/// Represents an invocation which produces the `to` file from the `from` file.
+#[derive(Clone, Debug, Eq, Hash, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct Edge {
+    /// The index of the input file node.
+    pub from: usize,
+    /// The index of the produced file node.
+    pub to: usize,
+    /// The kind of the invocation.
+    pub kind: EdgeKind,
+    /// The recognized compiler type. Missing for archiver calls.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub compiler: Option<CompilerType>,
+}
+
+/// The build artifact graph.
+#[derive(Debug, Default, serde::Serialize)]
+pub struct ArtifactGraph {
+    pub nodes: Vec<Node>,
+    pub edges: Vec<Edge>,
+    #[serde(skip)]
+    node_ids: HashMap<PathBuf, usize>,
+    #[serde(skip)]
+    edge_set: HashSet<Edge>,
+}
+
+impl ArtifactGraph {
+    /// Adds the edges of a semantic command to the graph.
+    ///
+    /// Commands which only print information, or preprocess to the standard
+    /// output, do not produce files, these are not added.
+    pub fn add(&mut self, cmd: &Command) {
+        let Some((kind, stops_at)) = Self::classify(cmd) else {
+            return;
+        };
+
+        let sources: Vec<&str> = cmd
+            .arguments
+            .iter()
+            .filter_map(|arg| match arg {
+                Argument::Source { path, .. } => Some(path.as_str()),
+                _ => None,
+            })
+            .collect();
+        let output = cmd.arguments.iter().find_map(|arg| match arg {
+            Argument::Output { path, .. } => Some(path.as_str()),
+            _ => None,
+        });
+
+        for source in sources {
+            let target = match (output, kind, stops_at) {
+                (Some(output), _, _) => PathBuf::from(output),
+                (None, EdgeKind::Link, _) => PathBuf::from("a.out"),
+                (None, _, Some(CompilerPass::Compiling)) => default_output(source, "o"),
+                (None, _, Some(CompilerPass::Assembling)) => default_output(source, "s"),
+                // Preprocessing writes to the standard output.
+                (None, _, _) => continue,
+            };
+
+            let from = self.node(resolve(&cmd.working_dir, Path::new(source)));
+            let to = self.node(resolve(&cmd.working_dir, &target));
+            let edge = Edge { from, to, kind, compiler: cmd.compiler };
+            if self.edge_set.insert(edge.clone()) {
+                self.edges.push(edge);
+            }
+        }
+    }
+
+    /// Writes the graph as a JSON object with the `nodes` and `edges` arrays.
+    pub fn write_json(&self, writer: impl io::Write) -> Result<(), serde_json::Error> {
+        serde_json::to_writer_pretty(writer, self)
+    }
+
+    /// Writes the graph in the Graphviz DOT language.
+    ///
+    /// The nodes are labeled with the file paths, the edges with the kind of
+    /// the invocation and the compiler type.
+    pub fn write_dot(&self, mut writer: impl io::Write) -> io::Result<()> {
+        writeln!(writer, "digraph artifacts {{")?;
+        for node in &self.nodes {
+            writeln!(writer, "  n{} [label=\"{}\"];", node.id, escape(&node.path.to_string_lossy()))?;
+        }
+        for edge in &self.edges {
+            let label = match (edge.kind, edge.compiler) {
+                (EdgeKind::Compile, Some(compiler)) => format!("compile ({compiler})"),
+                (EdgeKind::Link, Some(compiler)) => format!("link ({compiler})"),
+                (EdgeKind::Compile, None) => "compile".to_string(),
+                (EdgeKind::Link, None) => "link".to_string(),
+                (EdgeKind::Archive, _) => "archive".to_string(),
+            };
+            writeln!(writer, "  n{} -> n{} [label=\"{}\"];", edge.from, edge.to, escape(&label))?;
+        }
+        writeln!(writer, "}}")
+    }
+
+    /// Writes the graph in the requested format.
+    pub fn write(&self, writer: impl io::Write, format: GraphFormat) -> io::Result<()> {
+        match format {
+            GraphFormat::Json => self.write_json(writer).map_err(io::Error::from),
+            GraphFormat::Dot => self.write_dot(writer),
+        }
+    }
+
+    /// Returns the index of the node, adds the node when it is not yet in the graph.
+    fn node(&mut self, path: PathBuf) -> usize {
+        if let Some(id) = self.node_ids.get(&path) {
+            return *id;
+        }
+        let id = self.nodes.len();
+        self.node_ids.insert(path.clone(), id);
+        self.nodes.push(Node { id, path });
+        id
+    }
+
+    /// Returns the kind of the invocation and the pass where the compiler stops.
+    ///
+    /// Returns `None` when the command does not produce a file.
+    fn classify(cmd: &Command) -> Option<(EdgeKind, Option<CompilerPass>)> {
+        if cmd.compiler.is_none() {
+            return Some((EdgeKind::Archive, None));
+        }
+        let mut stops_at: Option<CompilerPass> = None;
+        for arg in &cmd.arguments {
+            match arg.kind() {
+                ArgumentKind::Other(PassEffect::InfoAndExit) => return None,
+                // The earliest stop wins, like `-E` with `-c`.
+                ArgumentKind::Other(PassEffect::StopsAt(pass)) => {
+                    stops_at = stops_at.into_iter().chain([pass]).min_by_key(|pass| Self::stop_order(*pass));
+                }
+                _ => {}
+            }
+        }
+        match stops_at {
+            Some(CompilerPass::Linking) | None => Some((EdgeKind::Link, None)),
+            Some(pass) => Some((EdgeKind::Compile, Some(pass))),
+        }
+    }
+
+    /// Returns the order of the compiler stops: preprocessing (`-E`), then
+    /// assembly output (`-S`), then object output (`-c`), then linking.
+    fn stop_order(pass: CompilerPass) -> u8 {
+        match pass {
+            CompilerPass::Preprocessing => 0,
+            CompilerPass::Assembling => 1,
+            CompilerPass::Compiling => 2,
+            CompilerPass::Linking => 3,
+        }
+    }
+}
+
+/// Makes the path absolute to the working directory, and removes the `.` and
+/// `..` components lexically.
+pub(super) fn resolve(working_dir: &Path, path: &Path) -> PathBuf {
+    let mut result = PathBuf::new();
+    for component in working_dir.join(path).components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                result.pop();
+            }
+            other => result.push(other),
+        }
+    }
+    result
+}
+
+/// Returns the default output of the compiler: the file name of the source
+/// with the given extension, in the working directory.
+fn default_output(source: &str, extension: &str) -> PathBuf {
+    let name = Path::new(source).file_name().map(PathBuf::from).unwrap_or_default();
+    name.with_extension(extension)
+}
+
+/// Escapes a string for a quoted DOT identifier.
+fn escape(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn compile(source: &str, output: Option<&str>) -> Command {
+        let mut arguments = vec![
+            (ArgumentKind::Compiler, vec!["gcc"]),
+            (ArgumentKind::Other(PassEffect::StopsAt(CompilerPass::Compiling)), vec!["-c"]),
+            (ArgumentKind::Source { binary: false }, vec![source]),
+        ];
+        if let Some(output) = output {
+            arguments.push((ArgumentKind::Output, vec!["-o", output]));
+        }
+        Command::from_strings("/home/user/project", "/usr/bin/gcc", arguments)
+            .with_compiler(CompilerType::Gcc)
+    }
+
+    fn archive() -> Command {
+        Command::from_strings(
+            "/home/user/project",
+            "/usr/bin/ar",
+            vec![
+                (ArgumentKind::Compiler, vec!["ar"]),
+                (ArgumentKind::Other(PassEffect::None), vec!["rcs"]),
+                (ArgumentKind::Output, vec!["", "lib/libfoo.a"]),
+                (ArgumentKind::Source { binary: true }, vec!["./foo.o"]),
+            ],
+        )
+    }
+
+    fn link() -> Command {
+        Command::from_strings(
+            "/home/user/project/app",
+            "/usr/bin/clang",
+            vec![
+                (ArgumentKind::Compiler, vec!["clang"]),
+                (ArgumentKind::Source { binary: true }, vec!["../main.o"]),
+                (ArgumentKind::Source { binary: true }, vec!["../lib/libfoo.a"]),
+                (ArgumentKind::Output, vec!["-o", "app"]),
+            ],
+        )
+        .with_compiler(CompilerType::Clang)
+    }
+
+    fn paths(graph: &ArtifactGraph) -> Vec<&str> {
+        graph.nodes.iter().map(|node| node.path.to_str().unwrap()).collect()
+    }
+
+    // Requirements: output-artifact-graph
+    #[test]
+    fn test_graph_connects_sources_to_binaries() {
+        let mut graph = ArtifactGraph::default();
+        for cmd in [compile("main.c", Some("main.o")), compile("src/foo.c", None), archive(), link()] {
+            graph.add(&cmd);
+        }
+
+        assert_eq!(
+            paths(&graph),
+            vec![
+                "/home/user/project/main.c",
+                "/home/user/project/main.o",
+                "/home/user/project/src/foo.c",
+                "/home/user/project/foo.o",
+                "/home/user/project/lib/libfoo.a",
+                "/home/user/project/app/app",
+            ]
+        );
+        assert_eq!(
+            graph.edges,
+            vec![
+                Edge { from: 0, to: 1, kind: EdgeKind::Compile, compiler: Some(CompilerType::Gcc) },
+                Edge { from: 2, to: 3, kind: EdgeKind::Compile, compiler: Some(CompilerType::Gcc) },
+                Edge { from: 3, to: 4, kind: EdgeKind::Archive, compiler: None },
+                Edge { from: 1, to: 5, kind: EdgeKind::Link, compiler: Some(CompilerType::Clang) },
+                Edge { from: 4, to: 5, kind: EdgeKind::Link, compiler: Some(CompilerType::Clang) },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_graph_skips_commands_without_output_file() {
+        let preprocess = Command::from_strings(
+            "/home/user",
+            "/usr/bin/gcc",
+            vec![
+                (ArgumentKind::Compiler, vec!["gcc"]),
+                (ArgumentKind::Other(PassEffect::StopsAt(CompilerPass::Preprocessing)), vec!["-E"]),
+                (ArgumentKind::Source { binary: false }, vec!["main.c"]),
+            ],
+        )
+        .with_compiler(CompilerType::Gcc);
+        let info = Command::from_strings(
+            "/home/user",
+            "/usr/bin/gcc",
+            vec![
+                (ArgumentKind::Compiler, vec!["gcc"]),
+                (ArgumentKind::Other(PassEffect::InfoAndExit), vec!["--version"]),
+            ],
+        )
+        .with_compiler(CompilerType::Gcc);
+
+        let mut graph = ArtifactGraph::default();
+        graph.add(&preprocess);
+        graph.add(&info);
+
+        assert!(graph.nodes.is_empty());
+        assert!(graph.edges.is_empty());
+    }
+
+    #[test]
+    fn test_graph_default_outputs() {
+        let command = |working_dir: &str, stop: Option<(CompilerPass, &str)>| {
+            let mut arguments = vec![(ArgumentKind::Compiler, vec!["gcc"])];
+            if let Some((pass, flag)) = stop {
+                arguments.push((ArgumentKind::Other(PassEffect::StopsAt(pass)), vec![flag]));
+            }
+            arguments.push((ArgumentKind::Source { binary: false }, vec!["src/main.c"]));
+            Command::from_strings(working_dir, "/usr/bin/gcc", arguments).with_compiler(CompilerType::Gcc)
+        };
+
+        let mut graph = ArtifactGraph::default();
+        graph.add(&command("/a", Some((CompilerPass::Compiling, "-c"))));
+        graph.add(&command("/b", Some((CompilerPass::Assembling, "-S"))));
+        graph.add(&command("/c", None));
+
+        assert_eq!(
+            paths(&graph),
+            vec!["/a/src/main.c", "/a/main.o", "/b/src/main.c", "/b/main.s", "/c/src/main.c", "/c/a.out"]
+        );
+    }
+
+    #[test]
+    fn test_graph_ignores_repeated_commands() {
+        let mut graph = ArtifactGraph::default();
+        graph.add(&compile("main.c", None));
+        graph.add(&compile("main.c", None));
+
+        assert_eq!(paths(&graph), vec!["/home/user/project/main.c", "/home/user/project/main.o"]);
+        assert_eq!(graph.edges.len(), 1);
+    }
+
+    #[test]
+    fn test_graph_write_formats() {
+        let mut graph = ArtifactGraph::default();
+        graph.add(&compile("main.c", None));
+
+        let mut json = vec![];
+        graph.write(&mut json, GraphFormat::Json).unwrap();
+        let value: serde_json::Value = serde_json::from_slice(&json).unwrap();
+        assert_eq!(
+            value,
+            serde_json::json!({
+                "nodes": [
+                    { "id": 0, "path": "/home/user/project/main.c" },
+                    { "id": 1, "path": "/home/user/project/main.o" }
+                ],
+                "edges": [
+                    { "from": 0, "to": 1, "kind": "compile", "compiler": "gcc" }
+                ]
+            })
+        );
+
+        let mut dot = vec![];
+        graph.write(&mut dot, GraphFormat::Dot).unwrap();
+        assert_eq!(
+            String::from_utf8(dot).unwrap(),
+            [
+                "digraph artifacts {",
+                "  n0 [label=\"/home/user/project/main.c\"];",
+                "  n1 [label=\"/home/user/project/main.o\"];",
+                "  n0 -> n1 [label=\"compile (GCC)\"];",
+                "}",
+                "",
+            ]
+            .join("\n")
+        );
+    }
+}
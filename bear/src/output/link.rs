@@ -0,0 +1,161 @@
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! Link command database data model.
+//!
+//! The compilation database does not contain the linker and archiver calls.
+//! These calls tell which object files end up in which executable or library,
+//! which is needed for reachability analysis and for software bill of
+//! materials. The [`LinkEntry`] type represents one of these calls.
+//!
+//! The entries are derived from the binary source arguments (object files,
+//! archives, shared libraries) and the output argument of the semantic
+//! commands. The paths are written as they were given to the linker, these
+//! are relative to the `directory` of the entry.
+
+use super::clang::json;
+use super::{SerializationError, SerializationFormat};
+use crate::semantic::{Argument, ArgumentKind, Command, CompilerPass, PassEffect};
+use std::borrow::Cow;
+use std::io;
+use std::path::PathBuf;
+
+/// The kind of the link command.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LinkKind {
+    /// A compiler driver call which links an executable or shared library.
+    Link,
+    /// An archiver call which creates a static library.
+    Archive,
+}
+
+/// Represents an entry of the link command database.
+#[derive(Clone, Debug, Eq, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct LinkEntry {
+    /// The working directory of the call.
+    pub directory: PathBuf,
+    /// The linker or archiver executable.
+    pub executable: PathBuf,
+    /// The kind of the call.
+    pub kind: LinkKind,
+    /// The produced file. Missing when the linker writes its default output.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output: Option<PathBuf>,
+    /// The input files (object files, archives and shared libraries).
+    pub inputs: Vec<PathBuf>,
+    /// The libraries given by name (`-l` flags).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub libraries: Vec<String>,
+    /// The library search paths (`-L` flags).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub library_paths: Vec<PathBuf>,
+    /// The full command line of the call.
+    pub arguments: Vec<String>,
+}
+
+impl LinkEntry {
+    /// Creates a link entry from a semantic command.
+    ///
+    /// Returns `None` when the command does not link nor archive: it stops
+    /// before linking (`-c`, `-S`, `-E`), only prints information, or has
+    /// no input files.
+    pub fn from_command(cmd: &Command) -> Option<Self> {
+        let kind = match cmd.compiler {
+            Some(_) if Self::is_linking(cmd) => LinkKind::Link,
+            Some(_) => return None,
+            None => LinkKind::Archive,
+        };
+
+        let inputs: Vec<PathBuf> = cmd
+            .arguments
+            .iter()
+            .filter_map(|arg| match arg {
+                Argument::Source { path, .. } => Some(PathBuf::from(path)),
+                _ => None,
+            })
+            .collect();
+        if inputs.is_empty() {
+            return None;
+        }
+
+        let output = cmd.arguments.iter().find_map(|arg| match arg {
+            Argument::Output { path, .. } => Some(PathBuf::from(path)),
+            _ => None,
+        });
+
+        let mut libraries = vec![];
+        let mut library_paths = vec![];
+        for arg in &cmd.arguments {
+            if let Argument::Other {
+                arguments,
+                kind: ArgumentKind::Other(PassEffect::Configures(CompilerPass::Linking)),
+                ..
+            } = arg
+            {
+                if let Some(value) = Self::flag_value(arguments, "-l") {
+                    libraries.push(value);
+                } else if let Some(value) = Self::flag_value(arguments, "-L") {
+                    library_paths.push(PathBuf::from(value));
+                }
+            }
+        }
+
+        let arguments =
+            cmd.arguments.iter().flat_map(|arg| arg.as_arguments(&|path| Cow::Borrowed(path))).collect();
+
+        Some(Self {
+            directory: cmd.working_dir.clone(),
+            executable: cmd.executable.clone(),
+            kind,
+            output,
+            inputs,
+            libraries,
+            library_paths,
+            arguments,
+        })
+    }
+
+    /// Checks if a compiler command runs the linking pass.
+    ///
+    /// The command does not link when it stops before the linking pass
+    /// (`-E`, `-S` or `-c`), or only prints information.
+    fn is_linking(cmd: &Command) -> bool {
+        !cmd.arguments.iter().any(|arg| {
+            matches!(
+                arg.kind(),
+                ArgumentKind::Other(
+                    PassEffect::StopsAt(
+                        CompilerPass::Preprocessing | CompilerPass::Compiling | CompilerPass::Assembling
+                    ) | PassEffect::InfoAndExit
+                )
+            )
+        })
+    }
+
+    /// Returns the value of the flag, either glued (`-lm`) or separate (`-l m`).
+    fn flag_value(arguments: &[String], flag: &str) -> Option<String> {
+        match arguments {
+            [single] => single.strip_prefix(flag).filter(|value| !value.is_empty()).map(str::to_string),
+            [first, value] if first == flag => Some(value.clone()),
+            _ => None,
+        }
+    }
+}
+
+/// The type represents the link command database format.
+///
+/// The format is a JSON array of the [`LinkEntry`] objects.
+pub struct LinkCommandDatabase;
+
+impl SerializationFormat<LinkEntry> for LinkCommandDatabase {
+    fn write(
+        writer: impl io::Write,
+        entries: impl Iterator<Item = LinkEntry>,
+    ) -> Result<(), SerializationError> {
+        json::serialize_seq(writer, entries).map_err(SerializationError::Syntax)
+    }
+
+    fn read(reader: impl io::Read) -> impl Iterator<Item = Result<LinkEntry, SerializationError>> {
+        json::deserialize_seq(reader).map(|res| res.map_err(SerializationError::Syntax))
+    }
+}
@@ -9,13 +9,19 @@
 //! The `OutputWriter` struct represents the main entry point for writing output.
 //! The input to the `OutputWriter` is a stream of `semantic::Command` instances.
 
+pub mod check;
 pub mod clang;
+pub mod diff;
 mod formats;
+pub mod graph;
 mod intercept;
+pub mod link;
+pub mod script;
 mod statistics;
 mod writers;
 
 use crate::{args, config, semantic};
+use std::io::Write;
 use std::sync::Arc;
 use thiserror::Error;
 
@@ -23,6 +29,7 @@ use thiserror::Error;
 pub use formats::{SerializationError, SerializationFormat};
 pub use intercept::ExecutionEventDatabase;
 pub use statistics::OutputStatistics;
+pub(crate) use writers::read_compilation_db;
 
 /// Represents the output writer for JSON compilation databases.
 ///
@@ -80,6 +87,202 @@ impl OutputWriter {
     }
 }
 
+/// Represents the converter between compilation database file formats.
+///
+/// The converter reads an existing compilation database (either JSON array or
+/// JSON lines format) and writes its entries in the requested format.
+pub struct OutputConverter {
+    converter: writers::DatabaseConverter,
+    stats: Arc<OutputStatistics>,
+}
+
+impl TryFrom<(&args::BuildEvents, &args::ConvertDatabase)> for OutputConverter {
+    type Error = WriterCreationError;
+
+    fn try_from(value: (&args::BuildEvents, &args::ConvertDatabase)) -> Result<Self, Self::Error> {
+        let (input, output) = value;
+        let stats = OutputStatistics::new();
+        let converter = writers::create_converter(input, output, Arc::clone(&stats))?;
+
+        Ok(Self { converter, stats })
+    }
+}
+
+impl OutputConverter {
+    /// Converts the input compilation database into the output file.
+    pub fn convert(self) -> Result<(), WriterError> {
+        let result = self.converter.convert();
+
+        log::info!("{}", self.stats);
+
+        result
+    }
+}
+
+/// Represents the merger of existing compilation databases.
+///
+/// The entries of the input databases (either JSON array or JSON lines format)
+/// are passed through the same filters and formatting as the entries of a build.
+pub struct OutputMerger {
+    merger: Box<writers::DatabaseMerger>,
+    stats: Arc<OutputStatistics>,
+}
+
+impl TryFrom<(&[std::path::PathBuf], &args::BuildSemantic, &config::Main)> for OutputMerger {
+    type Error = WriterCreationError;
+
+    fn try_from(
+        value: (&[std::path::PathBuf], &args::BuildSemantic, &config::Main),
+    ) -> Result<Self, Self::Error> {
+        let (inputs, output, config) = value;
+        let stats = OutputStatistics::new();
+        let merger = writers::create_merger(inputs, output, config, Arc::clone(&stats))?;
+
+        Ok(Self { merger: Box::new(merger), stats })
+    }
+}
+
+impl OutputMerger {
+    /// Merges the input databases into the output file.
+    pub fn merge(self) -> Result<(), WriterError> {
+        let result = self.merger.merge();
+
+        log::info!("{}", self.stats);
+
+        result
+    }
+}
+
+/// Represents the exporter of an existing compilation database into a build script.
+pub struct DatabaseExporter {
+    input: std::path::PathBuf,
+    writer: script::BuildScriptWriter,
+}
+
+impl DatabaseExporter {
+    /// Creates an exporter of the input compilation database.
+    ///
+    /// The format of the input is detected from its content.
+    pub fn new(input: &std::path::Path, output: &args::ExportScript) -> Self {
+        Self { input: input.to_path_buf(), writer: script::BuildScriptWriter::from(output) }
+    }
+
+    /// Reads the entries of the input database and writes them as a build script.
+    pub fn export(self) -> Result<(), WriterError> {
+        let entries = writers::read_compilation_db(&self.input)
+            .map_err(|err| WriterError::Io(self.input.clone(), err))?;
+        self.writer.write(entries)
+    }
+}
+
+/// Represents the comparison of two compilation databases.
+pub struct DatabaseDiffer {
+    old: std::path::PathBuf,
+    new: std::path::PathBuf,
+    output: args::DiffReport,
+    interpreter: Box<dyn semantic::Interpreter>,
+}
+
+impl DatabaseDiffer {
+    /// Creates a comparison of the old and new compilation databases.
+    ///
+    /// The interpreter splits the commands into flags.
+    pub fn new(
+        old: &std::path::Path,
+        new: &std::path::Path,
+        output: args::DiffReport,
+        interpreter: Box<dyn semantic::Interpreter>,
+    ) -> Self {
+        Self { old: old.to_path_buf(), new: new.to_path_buf(), output, interpreter }
+    }
+
+    /// Compares the databases and writes the report.
+    ///
+    /// # Returns
+    /// `Ok(true)` when the databases are different, `Ok(false)` when they
+    /// are the same, or a `WriterError` if reading or writing fails.
+    pub fn diff(self) -> Result<bool, WriterError> {
+        let old =
+            writers::read_compilation_db(&self.old).map_err(|err| WriterError::Io(self.old.clone(), err))?;
+        let new =
+            writers::read_compilation_db(&self.new).map_err(|err| WriterError::Io(self.new.clone(), err))?;
+        let result = diff::DatabaseDiff::new(self.interpreter.as_ref(), old, new);
+
+        let path = report_path(self.output.path.as_deref());
+        let io_error = |err: std::io::Error| WriterError::Io(path.clone(), SerializationError::Io(err));
+        let mut writer = report_writer(self.output.path.as_deref()).map_err(io_error)?;
+        match self.output.format {
+            args::DiffFormat::Text => result.write_text(&mut writer).map_err(io_error)?,
+            args::DiffFormat::Json => {
+                result
+                    .write_json(&mut writer)
+                    .map_err(|err| WriterError::Io(path.clone(), SerializationError::Syntax(err)))?;
+                writeln!(writer).map_err(io_error)?;
+            }
+        }
+        writer.flush().map_err(io_error)?;
+
+        Ok(!result.is_empty())
+    }
+}
+
+/// Represents the in-depth check of a compilation database.
+pub struct DatabaseChecker {
+    input: std::path::PathBuf,
+    output: args::CheckReport,
+    interpreter: Box<dyn semantic::Interpreter>,
+}
+
+impl DatabaseChecker {
+    /// Creates a check of the input compilation database.
+    ///
+    /// The interpreter parses the commands of the entries.
+    pub fn new(
+        input: &std::path::Path,
+        output: args::CheckReport,
+        interpreter: Box<dyn semantic::Interpreter>,
+    ) -> Self {
+        Self { input: input.to_path_buf(), output, interpreter }
+    }
+
+    /// Checks the database and writes the findings.
+    ///
+    /// # Returns
+    /// `Ok(true)` when any of the findings is an error, `Ok(false)` otherwise,
+    /// or a `WriterError` if reading or writing fails.
+    pub fn check(self) -> Result<bool, WriterError> {
+        let entries = check::read_entries(&self.input)
+            .map_err(|err| WriterError::Io(self.input.clone(), SerializationError::Io(err)))?;
+        let result = check::DatabaseCheck::new(self.interpreter.as_ref(), entries);
+        log::info!("Found {} problems in {}", result.findings.len(), self.input.display());
+
+        let path = report_path(self.output.path.as_deref());
+        let io_error = |err: std::io::Error| WriterError::Io(path.clone(), SerializationError::Io(err));
+        let mut writer = report_writer(self.output.path.as_deref()).map_err(io_error)?;
+        result
+            .write_json(&mut writer)
+            .map_err(|err| WriterError::Io(path.clone(), SerializationError::Syntax(err)))?;
+        writeln!(writer).map_err(io_error)?;
+        writer.flush().map_err(io_error)?;
+
+        Ok(result.has_errors())
+    }
+}
+
+/// Returns the path of the report for the error messages.
+fn report_path(path: Option<&std::path::Path>) -> std::path::PathBuf {
+    path.map(std::path::Path::to_path_buf).unwrap_or_else(|| std::path::PathBuf::from("<stdout>"))
+}
+
+/// Creates the writer of a report, which is the standard output when the
+/// path is not given.
+fn report_writer(path: Option<&std::path::Path>) -> std::io::Result<Box<dyn Write>> {
+    match path {
+        Some(path) => Ok(Box::new(std::fs::File::create(path).map(std::io::BufWriter::new)?)),
+        None => Ok(Box::new(std::io::stdout().lock())),
+    }
+}
+
 /// Represents errors that can occur while creating an output writer.
 #[derive(Error, Debug)]
 pub enum WriterCreationError {
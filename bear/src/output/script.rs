@@ -0,0 +1,312 @@
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! Build script export.
+//!
+//! Turns the compilation database entries into a runnable artifact, which
+//! reproduces the compilations on another machine (e.g. to reproduce an
+//! analyzer or compiler crash). Two formats are supported:
+//!
+//! - A Ninja build file, with one build edge per entry. The edge changes into
+//!   the working directory of the entry, and runs the command. The output of
+//!   the edge is the `output` of the entry (or a generated name when it is
+//!   missing or not unique), the input is the source file.
+//! - A POSIX shell script, which runs the commands one after the other in
+//!   their working directories, and exits with failure if any of them failed.
+//!
+//! The compiler of the commands can be replaced, and flags can be appended
+//! to them (e.g. to run `clang -fsyntax-only` over everything).
+
+use super::clang::Entry;
+use super::{SerializationError, WriterError};
+use crate::args;
+use std::collections::HashSet;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::{fs, io};
+
+/// A command to run in the build script.
+#[derive(Debug, PartialEq)]
+struct ScriptCommand {
+    directory: PathBuf,
+    file: PathBuf,
+    output: Option<PathBuf>,
+    arguments: Vec<String>,
+}
+
+/// Writes the compilation database entries as a build script.
+pub struct BuildScriptWriter {
+    path: PathBuf,
+    format: args::ScriptFormat,
+    compiler: Option<String>,
+    flags: Vec<String>,
+}
+
+impl From<&args::ExportScript> for BuildScriptWriter {
+    fn from(output: &args::ExportScript) -> Self {
+        Self {
+            path: output.path.clone(),
+            format: output.format,
+            compiler: output.compiler.clone(),
+            flags: output.flags.clone(),
+        }
+    }
+}
+
+impl BuildScriptWriter {
+    /// Writes the entries into the output file in the configured format.
+    ///
+    /// Entries with an invalid `command` field are reported and skipped.
+    pub fn write(self, entries: impl Iterator<Item = Entry>) -> Result<(), WriterError> {
+        let commands: Vec<ScriptCommand> = entries.filter_map(|entry| self.command(entry)).collect();
+        log::debug!("Writing {} commands into {}", commands.len(), self.path.display());
+
+        let io_error = |err: io::Error| WriterError::Io(self.path.clone(), SerializationError::Io(err));
+        let mut output = fs::File::create(&self.path).map(io::BufWriter::new).map_err(io_error)?;
+        match self.format {
+            args::ScriptFormat::Ninja => write_ninja(&mut output, &commands),
+            args::ScriptFormat::Shell => write_shell(&mut output, &commands),
+        }
+        .and_then(|_| output.flush())
+        .map_err(io_error)?;
+
+        if self.format == args::ScriptFormat::Shell {
+            make_executable(&self.path).map_err(io_error)?;
+        }
+        Ok(())
+    }
+
+    /// Returns the command of the entry, with the compiler replaced and the
+    /// flags appended.
+    fn command(&self, entry: Entry) -> Option<ScriptCommand> {
+        let mut arguments = if entry.arguments.is_empty() {
+            match shell_words::split(&entry.command) {
+                Ok(arguments) => arguments,
+                Err(error) => {
+                    log::warn!("Skipping entry of {}: {error}", entry.file.display());
+                    return None;
+                }
+            }
+        } else {
+            entry.arguments
+        };
+        if arguments.is_empty() {
+            log::warn!("Skipping entry of {}: empty command", entry.file.display());
+            return None;
+        }
+        if let Some(compiler) = &self.compiler {
+            arguments[0] = compiler.clone();
+        }
+        arguments.extend(self.flags.iter().cloned());
+
+        Some(ScriptCommand { directory: entry.directory, file: entry.file, output: entry.output, arguments })
+    }
+}
+
+/// Writes the commands as a Ninja build file.
+///
+/// Ninja requires unique outputs, the entries without output (or with an
+/// output which is already taken) get the `bear-entry-<index>` output name.
+fn write_ninja(writer: &mut impl Write, commands: &[ScriptCommand]) -> io::Result<()> {
+    writeln!(writer, "# Generated by Bear, runs the recorded compilations.")?;
+    writeln!(writer, "ninja_required_version = 1.5")?;
+    writeln!(writer)?;
+    writeln!(writer, "rule compile")?;
+    writeln!(writer, "  command = cd $directory && $arguments")?;
+    writeln!(writer, "  description = $description")?;
+
+    let mut outputs = HashSet::new();
+    for (index, command) in commands.iter().enumerate() {
+        let output = command
+            .output
+            .as_ref()
+            .map(|output| resolve(&command.directory, output))
+            .filter(|output| outputs.insert(output.clone()))
+            .unwrap_or_else(|| PathBuf::from(format!("bear-entry-{index}")));
+        let file = resolve(&command.directory, &command.file);
+
+        writeln!(writer)?;
+        writeln!(
+            writer,
+            "build {}: compile {}",
+            ninja_path(&output.to_string_lossy()),
+            ninja_path(&file.to_string_lossy())
+        )?;
+        writeln!(
+            writer,
+            "  directory = {}",
+            ninja_value(&shell_quote(&command.directory.to_string_lossy()))
+        )?;
+        writeln!(writer, "  arguments = {}", ninja_value(&shell_words::join(&command.arguments)))?;
+        writeln!(writer, "  description = {}", ninja_value(&command.file.to_string_lossy()))?;
+    }
+    Ok(())
+}
+
+/// Writes the commands as a POSIX shell script.
+fn write_shell(writer: &mut impl Write, commands: &[ScriptCommand]) -> io::Result<()> {
+    writeln!(writer, "#!/bin/sh")?;
+    writeln!(writer, "# Generated by Bear, runs the recorded compilations.")?;
+    writeln!(writer)?;
+    writeln!(writer, "status=0")?;
+    for command in commands {
+        writeln!(
+            writer,
+            "(cd {} && {}) || status=1",
+            shell_quote(&command.directory.to_string_lossy()),
+            shell_words::join(&command.arguments)
+        )?;
+    }
+    writeln!(writer, "exit $status")
+}
+
+/// Makes the path absolute to the directory of the entry.
+fn resolve(directory: &Path, path: &Path) -> PathBuf {
+    if path.is_absolute() { path.to_path_buf() } else { directory.join(path) }
+}
+
+fn shell_quote(value: &str) -> String {
+    shell_words::quote(value).into_owned()
+}
+
+/// Escapes a path for the build line of a Ninja file.
+fn ninja_path(value: &str) -> String {
+    ninja_value(value).replace(' ', "$ ").replace(':', "$:")
+}
+
+/// Escapes a variable value of a Ninja file.
+fn ninja_value(value: &str) -> String {
+    value.replace('$', "$$").replace('\n', "$\n")
+}
+
+#[cfg(unix)]
+fn make_executable(path: &Path) -> io::Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+
+    let mut permissions = fs::metadata(path)?.permissions();
+    permissions.set_mode(permissions.mode() | 0o111);
+    fs::set_permissions(path, permissions)
+}
+
+#[cfg(not(unix))]
+fn make_executable(_: &Path) -> io::Result<()> {
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entries() -> Vec<Entry> {
+        vec![
+            Entry::from_arguments_str(
+                "src/main.c",
+                vec!["gcc", "-c", "-o", "main.o", "src/main.c"],
+                "/home/user/project",
+                Some("main.o"),
+            ),
+            Entry::from_command_str(
+                "lib.c",
+                "gcc -c -DNAME='\"my lib\"' lib.c",
+                "/home/user/my project",
+                None,
+            ),
+        ]
+    }
+
+    fn write(format: args::ScriptFormat, compiler: Option<&str>, flags: Vec<&str>) -> String {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("build");
+        let sut = BuildScriptWriter {
+            path: path.clone(),
+            format,
+            compiler: compiler.map(String::from),
+            flags: flags.into_iter().map(String::from).collect(),
+        };
+
+        sut.write(entries().into_iter()).unwrap();
+        fs::read_to_string(&path).unwrap()
+    }
+
+    // Requirements: output-build-script
+    #[test]
+    fn test_write_ninja() {
+        let content = write(args::ScriptFormat::Ninja, None, vec![]);
+
+        assert_eq!(
+            content,
+            [
+                "# Generated by Bear, runs the recorded compilations.",
+                "ninja_required_version = 1.5",
+                "",
+                "rule compile",
+                "  command = cd $directory && $arguments",
+                "  description = $description",
+                "",
+                "build /home/user/project/main.o: compile /home/user/project/src/main.c",
+                "  directory = /home/user/project",
+                "  arguments = gcc -c -o main.o src/main.c",
+                "  description = src/main.c",
+                "",
+                "build bear-entry-1: compile /home/user/my$ project/lib.c",
+                "  directory = '/home/user/my project'",
+                "  arguments = gcc -c '-DNAME=\"my lib\"' lib.c",
+                "  description = lib.c",
+                "",
+            ]
+            .join("\n")
+        );
+    }
+
+    // Requirements: output-build-script
+    #[test]
+    fn test_write_shell_with_compiler_rewrite() {
+        let content = write(args::ScriptFormat::Shell, Some("clang"), vec!["-fsyntax-only"]);
+
+        assert_eq!(
+            content,
+            [
+                "#!/bin/sh",
+                "# Generated by Bear, runs the recorded compilations.",
+                "",
+                "status=0",
+                "(cd /home/user/project && clang -c -o main.o src/main.c -fsyntax-only) || status=1",
+                "(cd '/home/user/my project' && clang -c '-DNAME=\"my lib\"' lib.c -fsyntax-only) || status=1",
+                "exit $status",
+                "",
+            ]
+            .join("\n")
+        );
+    }
+
+    #[test]
+    fn test_ninja_outputs_are_unique() {
+        let commands: Vec<ScriptCommand> = (0..2)
+            .map(|_| ScriptCommand {
+                directory: PathBuf::from("/home/user"),
+                file: PathBuf::from("main.c"),
+                output: Some(PathBuf::from("main.o")),
+                arguments: vec!["cc".into(), "-c".into(), "main.c".into()],
+            })
+            .collect();
+
+        let mut content = vec![];
+        write_ninja(&mut content, &commands).unwrap();
+        let content = String::from_utf8(content).unwrap();
+
+        assert!(content.contains("build /home/user/main.o: compile /home/user/main.c\n"));
+        assert!(content.contains("build bear-entry-1: compile /home/user/main.c\n"));
+    }
+
+    #[test]
+    fn test_invalid_command_is_skipped() {
+        let sut = BuildScriptWriter {
+            path: PathBuf::from("build.sh"),
+            format: args::ScriptFormat::Shell,
+            compiler: None,
+            flags: vec![],
+        };
+
+        let entry = Entry::from_command_str("main.c", "gcc -c 'main.c", "/home/user", None);
+        assert_eq!(sut.command(entry), None);
+    }
+}
@@ -26,7 +26,8 @@ use std::sync::atomic::{AtomicUsize, Ordering};
 /// # Fields by Writer
 ///
 /// - **ConverterClangOutputWriter**: `semantic_commands_received`, `compilation_entries_produced`
-/// - **AppendClangOutputWriter**: `entries_read_from_existing`
+/// - **AppendClangOutputWriter**: `entries_read_from_existing`, `entries_replaced_from_existing`,
+///   `entries_dropped_missing`
 /// - **UniqueOutputWriter**: `duplicates_detected`
 /// - **SourceFilterOutputWriter**: `entries_filtered_by_source`
 /// - **ValidatingOutputWriter**: `entries_dropped_invalid`
@@ -42,6 +43,13 @@ pub struct OutputStatistics {
     /// Number of entries read from an existing compilation database (append mode).
     pub entries_read_from_existing: AtomicUsize,
 
+    /// Number of entries from an existing compilation database replaced by new entries (update mode).
+    pub entries_replaced_from_existing: AtomicUsize,
+
+    /// Number of entries from an existing compilation database dropped because
+    /// their file or directory no longer exists.
+    pub entries_dropped_missing: AtomicUsize,
+
     /// Number of duplicate entries detected and removed.
     pub duplicates_detected: AtomicUsize,
 
@@ -67,18 +75,22 @@ impl fmt::Display for OutputStatistics {
         let semantic = self.semantic_commands_received.load(Ordering::Relaxed);
         let produced = self.compilation_entries_produced.load(Ordering::Relaxed);
         let from_existing = self.entries_read_from_existing.load(Ordering::Relaxed);
+        let replaced = self.entries_replaced_from_existing.load(Ordering::Relaxed);
         let duplicates = self.duplicates_detected.load(Ordering::Relaxed);
         let filtered = self.entries_filtered_by_source.load(Ordering::Relaxed);
         let dropped_invalid = self.entries_dropped_invalid.load(Ordering::Relaxed);
+        let dropped_missing = self.entries_dropped_missing.load(Ordering::Relaxed);
         let written = self.entries_written.load(Ordering::Relaxed);
 
         writeln!(f, "Output pipeline:")?;
         writeln!(f, "  semantic events: {}", semantic)?;
         writeln!(f, "  current entries: {}", produced)?;
         writeln!(f, "  previous entries: {}", from_existing)?;
+        writeln!(f, "  replaced previous entries: {}", replaced)?;
         writeln!(f, "  filtered entries by duplicate: {}", duplicates)?;
         writeln!(f, "  filtered entries by source: {}", filtered)?;
         writeln!(f, "  dropped entries (invalid): {}", dropped_invalid)?;
+        writeln!(f, "  dropped entries (missing): {}", dropped_missing)?;
         write!(f, "  total entries written: {}", written)
     }
 }
@@ -100,9 +112,11 @@ mod tests {
         stats.semantic_commands_received.store(20, Ordering::Relaxed);
         stats.compilation_entries_produced.store(15, Ordering::Relaxed);
         stats.entries_read_from_existing.store(5, Ordering::Relaxed);
+        stats.entries_replaced_from_existing.store(4, Ordering::Relaxed);
         stats.duplicates_detected.store(3, Ordering::Relaxed);
         stats.entries_filtered_by_source.store(2, Ordering::Relaxed);
         stats.entries_dropped_invalid.store(1, Ordering::Relaxed);
+        stats.entries_dropped_missing.store(6, Ordering::Relaxed);
         stats.entries_written.store(10, Ordering::Relaxed);
 
         let output = format!("{}", stats);
@@ -110,9 +124,11 @@ mod tests {
         assert!(output.contains("semantic events: 20"));
         assert!(output.contains("current entries: 15"));
         assert!(output.contains("previous entries: 5"));
+        assert!(output.contains("replaced previous entries: 4"));
         assert!(output.contains("filtered entries by duplicate: 3"));
         assert!(output.contains("filtered entries by source: 2"));
         assert!(output.contains("dropped entries (invalid): 1"));
+        assert!(output.contains("dropped entries (missing): 6"));
         assert!(output.contains("total entries written: 10"));
     }
 
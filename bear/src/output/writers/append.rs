@@ -1,11 +1,15 @@
 // SPDX-License-Identifier: GPL-3.0-or-later
 
 use super::IteratorWriter;
+use crate::config;
 use crate::output::WriterError;
 use crate::output::clang;
-use crate::output::clang::serialization::JsonCompilationDatabase;
+use crate::output::clang::serialization::{
+    JsonCompilationDatabase, JsonLinesCompilationDatabase, is_json_lines,
+};
 use crate::output::formats::{SerializationError, SerializationFormat};
 use crate::output::statistics::OutputStatistics;
+use std::collections::HashSet;
 use std::sync::Arc;
 use std::sync::atomic::Ordering;
 use std::{fs, io, path};
@@ -17,11 +21,20 @@ use std::{fs, io, path};
 /// If the file does not exist and the append option is enabled, it logs a warning
 /// and writes only the new entries.
 ///
+/// With the `prune_missing` option, the existing entries whose file or directory
+/// no longer exists are dropped.
+///
+/// With the `update` strategy, the existing entries which are compiling the same
+/// file (and optionally produce the same output) as a new entry are dropped, so
+/// the new entry replaces the outdated one. This requires collecting the new
+/// entries before the existing ones are read.
+///
 /// # Note
 /// Reading errors will be ignored, and a warning will be logged.
 pub(crate) struct AppendClangOutputWriter<T: IteratorWriter<clang::Entry>> {
     writer: T,
     path: Option<path::PathBuf>,
+    config: config::Append,
     stats: Arc<OutputStatistics>,
 }
 
@@ -30,6 +43,7 @@ impl<T: IteratorWriter<clang::Entry>> AppendClangOutputWriter<T> {
         writer: T,
         input_path: &path::Path,
         append: bool,
+        config: &config::Append,
         stats: Arc<OutputStatistics>,
     ) -> Self {
         let path = if input_path.exists() && append {
@@ -40,22 +54,45 @@ impl<T: IteratorWriter<clang::Entry>> AppendClangOutputWriter<T> {
             }
             None
         };
-        Self { writer, path, stats }
+        Self { writer, path, config: config.clone(), stats }
+    }
+
+    /// Checks that the directory and the file of the entry are still on the disk.
+    fn paths_exist(entry: &clang::Entry) -> bool {
+        entry.directory.is_dir() && entry.directory.join(&entry.file).exists()
     }
 
-    /// Reads the compilation database from a file.
+    /// The key which identifies the entries replacing each other in update mode.
     ///
-    /// NOTE: The function is intentionally not getting any `&self` reference,
-    /// because the logic is not bound to the instance.
-    fn read_from_compilation_db(
-        source: &path::Path,
-    ) -> Result<impl Iterator<Item = clang::Entry>, SerializationError> {
-        let file = fs::File::open(source).map(io::BufReader::new)?;
-
-        let entries = JsonCompilationDatabase::read_and_ignore(file, |error| {
-            log::warn!("Problems to read previous entries: {error:?}");
-        });
-        Ok(entries)
+    /// The paths are joined to the directory, so the same file referred with
+    /// relative and absolute paths is matched.
+    fn update_key(config: &config::Append, entry: &clang::Entry) -> (path::PathBuf, Option<path::PathBuf>) {
+        let file = entry.directory.join(&entry.file);
+        let output = if config.match_output {
+            entry.output.as_ref().map(|output| entry.directory.join(output))
+        } else {
+            None
+        };
+        (file, output)
+    }
+}
+
+/// Reads the compilation database from a file.
+///
+/// The file can be in the JSON array or in the JSON lines format, the format
+/// is detected from the content. The JSON lines format is read incrementally.
+pub(crate) fn read_compilation_db(
+    source: &path::Path,
+) -> Result<Box<dyn Iterator<Item = clang::Entry>>, SerializationError> {
+    let mut file = fs::File::open(source).map(io::BufReader::new)?;
+
+    let warn = |error: &str| {
+        log::warn!("Problems to read previous entries: {error:?}");
+    };
+    if is_json_lines(&mut file)? {
+        Ok(Box::new(JsonLinesCompilationDatabase::read_and_ignore(file, warn)))
+    } else {
+        Ok(Box::new(JsonCompilationDatabase::read_and_ignore(file, warn)))
     }
 }
 
@@ -65,15 +102,49 @@ impl<T: IteratorWriter<clang::Entry>> IteratorWriter<clang::Entry> for AppendCla
             let stats = Arc::clone(&self.stats);
 
             let entries_from_db =
-                Self::read_from_compilation_db(path).map_err(|err| WriterError::Io(path.clone(), err))?;
+                read_compilation_db(path).map_err(|err| WriterError::Io(path.clone(), err))?;
 
             // Count entries read from existing database
-            let counted_existing = entries_from_db.inspect(move |_| {
-                stats.entries_read_from_existing.fetch_add(1, Ordering::Relaxed);
-            });
-
-            let final_entries = counted_existing.chain(entries);
-            self.writer.write(final_entries)
+            let prune_missing = self.config.prune_missing;
+            let counted_existing = entries_from_db
+                .inspect(move |_| {
+                    stats.entries_read_from_existing.fetch_add(1, Ordering::Relaxed);
+                })
+                .filter({
+                    let stats = Arc::clone(&self.stats);
+                    move |entry| {
+                        let keep = !prune_missing || Self::paths_exist(entry);
+                        if !keep {
+                            log::debug!("Dropping entry of missing file: {}", entry.file.display());
+                            stats.entries_dropped_missing.fetch_add(1, Ordering::Relaxed);
+                        }
+                        keep
+                    }
+                });
+
+            match self.config.strategy {
+                config::AppendStrategy::Concat => {
+                    let final_entries = counted_existing.chain(entries);
+                    self.writer.write(final_entries)
+                }
+                config::AppendStrategy::Update => {
+                    let new_entries: Vec<clang::Entry> = entries.collect();
+                    let new_keys: HashSet<_> =
+                        new_entries.iter().map(|entry| Self::update_key(&self.config, entry)).collect();
+
+                    let stats = Arc::clone(&self.stats);
+                    let config = self.config;
+                    let kept_existing = counted_existing.filter(move |entry| {
+                        let replaced = new_keys.contains(&Self::update_key(&config, entry));
+                        if replaced {
+                            stats.entries_replaced_from_existing.fetch_add(1, Ordering::Relaxed);
+                        }
+                        !replaced
+                    });
+
+                    self.writer.write(kept_existing.chain(new_entries))
+                }
+            }
         } else {
             self.writer.write(entries)
         }
@@ -84,6 +155,7 @@ impl<T: IteratorWriter<clang::Entry>> IteratorWriter<clang::Entry> for AppendCla
 mod tests {
     use super::*;
     use crate::output::writers::file::ClangOutputWriter;
+    use crate::output::writers::fixtures::CollectingWriter;
 
     #[test]
     fn test_append_clang_output_writer_no_original_file() {
@@ -97,8 +169,16 @@ mod tests {
             clang::Entry::from_arguments_str("file2.cpp", vec!["clang", "-c"], "/path/to/dir", None),
         ];
 
-        let writer = ClangOutputWriter::create(&result_path, Arc::clone(&stats)).unwrap();
-        let sut = AppendClangOutputWriter::new(writer, &input_path, false, Arc::clone(&stats));
+        let writer =
+            ClangOutputWriter::create(&result_path, config::DatabaseFormat::Json, Arc::clone(&stats))
+                .unwrap();
+        let sut = AppendClangOutputWriter::new(
+            writer,
+            &input_path,
+            false,
+            &config::Append::default(),
+            Arc::clone(&stats),
+        );
         sut.write(entries_to_write.into_iter()).unwrap();
 
         // Verify the result file contains the written entries
@@ -120,7 +200,8 @@ mod tests {
             clang::Entry::from_arguments_str("file3.cpp", vec!["clang", "-c"], "/path/to/dir", None),
             clang::Entry::from_arguments_str("file4.cpp", vec!["clang", "-c"], "/path/to/dir", None),
         ];
-        let writer = ClangOutputWriter::create(&input_path, Arc::clone(&stats)).unwrap();
+        let writer =
+            ClangOutputWriter::create(&input_path, config::DatabaseFormat::Json, Arc::clone(&stats)).unwrap();
         writer.write(original_entries.into_iter()).unwrap();
 
         let new_entries = vec![
@@ -128,8 +209,16 @@ mod tests {
             clang::Entry::from_arguments_str("file2.cpp", vec!["clang", "-c"], "/path/to/dir", None),
         ];
 
-        let writer = ClangOutputWriter::create(&result_path, Arc::clone(&stats)).unwrap();
-        let sut = AppendClangOutputWriter::new(writer, &input_path, true, Arc::clone(&stats));
+        let writer =
+            ClangOutputWriter::create(&result_path, config::DatabaseFormat::Json, Arc::clone(&stats))
+                .unwrap();
+        let sut = AppendClangOutputWriter::new(
+            writer,
+            &input_path,
+            true,
+            &config::Append::default(),
+            Arc::clone(&stats),
+        );
         sut.write(new_entries.into_iter()).unwrap();
 
         // Verify the result file contains both original and new entries
@@ -141,6 +230,39 @@ mod tests {
         assert!(content.contains("file4.cpp"));
     }
 
+    // Requirements: output-json-lines
+    #[test]
+    fn test_append_clang_output_writer_with_json_lines_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let input_path = dir.path().join("file_to_append.jsonl");
+        let stats = OutputStatistics::new();
+
+        let original_entries =
+            vec![clang::Entry::from_arguments_str("file2.cpp", vec!["clang", "-c"], "/path/to/dir", None)];
+        let writer =
+            ClangOutputWriter::create(&input_path, config::DatabaseFormat::JsonLines, Arc::clone(&stats))
+                .unwrap();
+        writer.write(original_entries.into_iter()).unwrap();
+
+        let new_entries =
+            vec![clang::Entry::from_arguments_str("file1.cpp", vec!["clang", "-c"], "/path/to/dir", None)];
+
+        let (writer, collected) = CollectingWriter::new();
+        let sut = AppendClangOutputWriter::new(
+            writer,
+            &input_path,
+            true,
+            &config::Append::default(),
+            Arc::clone(&stats),
+        );
+        sut.write(new_entries.into_iter()).unwrap();
+
+        let result = collected.lock().unwrap();
+        assert_eq!(result.len(), 2);
+        assert_eq!(result[0].file, path::PathBuf::from("file2.cpp"));
+        assert_eq!(result[1].file, path::PathBuf::from("file1.cpp"));
+    }
+
     #[test]
     fn test_append_with_corrupted_database_file() {
         let dir = tempfile::tempdir().unwrap();
@@ -154,8 +276,16 @@ mod tests {
         let new_entries =
             vec![clang::Entry::from_arguments_str("new_file.cpp", vec!["clang", "-c"], "/path/to/dir", None)];
 
-        let writer = ClangOutputWriter::create(&result_path, Arc::clone(&stats)).unwrap();
-        let sut = AppendClangOutputWriter::new(writer, &input_path, true, Arc::clone(&stats));
+        let writer =
+            ClangOutputWriter::create(&result_path, config::DatabaseFormat::Json, Arc::clone(&stats))
+                .unwrap();
+        let sut = AppendClangOutputWriter::new(
+            writer,
+            &input_path,
+            true,
+            &config::Append::default(),
+            Arc::clone(&stats),
+        );
 
         // Should fail because read_from_compilation_db returns an error for non-JSON
         // (the file opens fine, but deserialization fails during iteration —
@@ -187,8 +317,16 @@ mod tests {
         let new_entries =
             vec![clang::Entry::from_arguments_str("new_file.cpp", vec!["clang", "-c"], "/path/to/dir", None)];
 
-        let writer = ClangOutputWriter::create(&result_path, Arc::clone(&stats)).unwrap();
-        let sut = AppendClangOutputWriter::new(writer, &input_path, true, Arc::clone(&stats));
+        let writer =
+            ClangOutputWriter::create(&result_path, config::DatabaseFormat::Json, Arc::clone(&stats))
+                .unwrap();
+        let sut = AppendClangOutputWriter::new(
+            writer,
+            &input_path,
+            true,
+            &config::Append::default(),
+            Arc::clone(&stats),
+        );
         sut.write(new_entries.into_iter()).unwrap();
 
         let content = fs::read_to_string(&result_path).unwrap();
@@ -214,7 +352,8 @@ mod tests {
             clang::Entry::from_arguments_str("old_file1.cpp", vec!["clang", "-c"], "/path/to/dir", None),
             clang::Entry::from_arguments_str("old_file2.cpp", vec!["clang", "-c"], "/path/to/dir", None),
         ];
-        let writer = ClangOutputWriter::create(&input_path, Arc::clone(&stats)).unwrap();
+        let writer =
+            ClangOutputWriter::create(&input_path, config::DatabaseFormat::Json, Arc::clone(&stats)).unwrap();
         writer.write(original_entries.into_iter()).unwrap();
 
         let new_entries = vec![
@@ -222,8 +361,16 @@ mod tests {
             clang::Entry::from_arguments_str("new_file2.cpp", vec!["clang", "-c"], "/path/to/dir", None),
         ];
 
-        let writer = ClangOutputWriter::create(&result_path, Arc::clone(&stats)).unwrap();
-        let sut = AppendClangOutputWriter::new(writer, &input_path, false, Arc::clone(&stats));
+        let writer =
+            ClangOutputWriter::create(&result_path, config::DatabaseFormat::Json, Arc::clone(&stats))
+                .unwrap();
+        let sut = AppendClangOutputWriter::new(
+            writer,
+            &input_path,
+            false,
+            &config::Append::default(),
+            Arc::clone(&stats),
+        );
         sut.write(new_entries.into_iter()).unwrap();
 
         // Verify the result file contains only new entries (no original entries)
@@ -234,4 +381,125 @@ mod tests {
         assert!(!content.contains("old_file1.cpp"));
         assert!(!content.contains("old_file2.cpp"));
     }
+
+    // Requirements: output-append
+    #[test]
+    fn test_append_update_strategy_replaces_existing_entries() {
+        let dir = tempfile::tempdir().unwrap();
+        let input_path = dir.path().join("existing.json");
+        let stats = OutputStatistics::new();
+
+        let original_entries = vec![
+            clang::Entry::from_arguments_str("file1.cpp", vec!["clang", "-c", "-O0"], "/path/to/dir", None),
+            clang::Entry::from_arguments_str("file2.cpp", vec!["clang", "-c", "-O0"], "/path/to/dir", None),
+        ];
+        let writer =
+            ClangOutputWriter::create(&input_path, config::DatabaseFormat::Json, Arc::clone(&stats)).unwrap();
+        writer.write(original_entries.into_iter()).unwrap();
+
+        // The same file referred with absolute path is replaced too.
+        let new_entries = vec![clang::Entry::from_arguments_str(
+            "/path/to/dir/file1.cpp",
+            vec!["clang", "-c", "-O2"],
+            "/path/to/dir",
+            None,
+        )];
+
+        let config = config::Append {
+            strategy: config::AppendStrategy::Update,
+            match_output: false,
+            prune_missing: false,
+        };
+        let (writer, collected) = CollectingWriter::new();
+        let sut = AppendClangOutputWriter::new(writer, &input_path, true, &config, Arc::clone(&stats));
+        sut.write(new_entries.into_iter()).unwrap();
+
+        let collected = collected.lock().unwrap();
+        assert_eq!(collected.len(), 2);
+        assert_eq!(collected[0].file, path::PathBuf::from("file2.cpp"));
+        assert_eq!(collected[1].file, path::PathBuf::from("/path/to/dir/file1.cpp"));
+        assert_eq!(collected[1].arguments, vec!["clang", "-c", "-O2"]);
+        assert_eq!(stats.entries_read_from_existing.load(Ordering::Relaxed), 2);
+        assert_eq!(stats.entries_replaced_from_existing.load(Ordering::Relaxed), 1);
+    }
+
+    // Requirements: output-append
+    #[test]
+    fn test_append_update_strategy_matching_output() {
+        let dir = tempfile::tempdir().unwrap();
+        let input_path = dir.path().join("existing.json");
+        let stats = OutputStatistics::new();
+
+        let original_entries = vec![
+            clang::Entry::from_arguments_str(
+                "file1.cpp",
+                vec!["clang", "-c"],
+                "/path/to/dir",
+                Some("debug.o"),
+            ),
+            clang::Entry::from_arguments_str(
+                "file1.cpp",
+                vec!["clang", "-c"],
+                "/path/to/dir",
+                Some("release.o"),
+            ),
+        ];
+        let writer =
+            ClangOutputWriter::create(&input_path, config::DatabaseFormat::Json, Arc::clone(&stats)).unwrap();
+        writer.write(original_entries.into_iter()).unwrap();
+
+        let new_entries = vec![clang::Entry::from_arguments_str(
+            "file1.cpp",
+            vec!["clang", "-c", "-g"],
+            "/path/to/dir",
+            Some("debug.o"),
+        )];
+
+        let config = config::Append {
+            strategy: config::AppendStrategy::Update,
+            match_output: true,
+            prune_missing: false,
+        };
+        let (writer, collected) = CollectingWriter::new();
+        let sut = AppendClangOutputWriter::new(writer, &input_path, true, &config, Arc::clone(&stats));
+        sut.write(new_entries.into_iter()).unwrap();
+
+        let collected = collected.lock().unwrap();
+        assert_eq!(collected.len(), 2);
+        assert_eq!(collected[0].output, Some(path::PathBuf::from("release.o")));
+        assert_eq!(collected[1].arguments, vec!["clang", "-c", "-g"]);
+        assert_eq!(stats.entries_replaced_from_existing.load(Ordering::Relaxed), 1);
+    }
+
+    // Requirements: output-append-prune
+    #[test]
+    fn test_append_prune_missing_drops_deleted_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let input_path = dir.path().join("existing.json");
+        let stats = OutputStatistics::new();
+
+        let directory = dir.path().to_str().unwrap();
+        fs::write(dir.path().join("kept.c"), "").unwrap();
+
+        let original_entries = vec![
+            clang::Entry::from_arguments_str("kept.c", vec!["cc", "-c"], directory, None),
+            clang::Entry::from_arguments_str("deleted.c", vec!["cc", "-c"], directory, None),
+            clang::Entry::from_arguments_str("kept.c", vec!["cc", "-c"], "/vanished/directory", None),
+        ];
+        let writer =
+            ClangOutputWriter::create(&input_path, config::DatabaseFormat::Json, Arc::clone(&stats)).unwrap();
+        writer.write(original_entries.into_iter()).unwrap();
+
+        let config = config::Append { prune_missing: true, ..config::Append::default() };
+        let (writer, collected) = CollectingWriter::new();
+        let sut = AppendClangOutputWriter::new(writer, &input_path, true, &config, Arc::clone(&stats));
+        sut.write(std::iter::empty()).unwrap();
+
+        let collected = collected.lock().unwrap();
+        assert_eq!(collected.len(), 1);
+        assert_eq!(collected[0].file, path::PathBuf::from("kept.c"));
+        assert_eq!(collected[0].directory, dir.path());
+        assert_eq!(stats.entries_read_from_existing.load(Ordering::Relaxed), 3);
+        assert_eq!(stats.entries_dropped_missing.load(Ordering::Relaxed), 2);
+    }
 }
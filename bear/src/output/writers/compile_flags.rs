@@ -0,0 +1,214 @@
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! `compile_flags.txt` output stage.
+//!
+//! clangd (and some older tools) read the flags of a source file from the
+//! `compile_flags.txt` file in its directory (or in one of the parent
+//! directories), when there is no compilation database. This stage derives
+//! these files from the compiler commands, while the commands are passed
+//! through to the compilation database writer unchanged.
+//!
+//! The file has one flag per line. It holds one set of flags for all files,
+//! therefore only the flags which are common to all translation units of the
+//! directory are written. When the translation units of a directory were
+//! compiled with different flags, a warning is logged.
+//!
+//! The paths in the flags are made absolute, because the tools resolve the
+//! relative paths against the directory of the `compile_flags.txt` file, and
+//! not against the working directory of the compilation.
+
+use super::IteratorWriter;
+use crate::config;
+use crate::output::clang::CommandConverter;
+use crate::output::{SerializationError, WriterError};
+use crate::semantic::Command;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// The name of the file which is written into the source directories.
+const COMPILE_FLAGS_FILE: &str = "compile_flags.txt";
+
+/// A pipeline stage that writes `compile_flags.txt` files (when enabled).
+pub(crate) struct CompileFlagsOutputWriter<T: IteratorWriter<Command>> {
+    writer: T,
+    converter: Option<CommandConverter>,
+}
+
+impl<T: IteratorWriter<Command>> CompileFlagsOutputWriter<T> {
+    pub(crate) fn new(writer: T, config: &config::Main) -> Self {
+        let converter = config.compile_flags.enabled.then(|| {
+            let paths = config::PathFormat {
+                directory: config::PathResolver::Absolute,
+                file: config::PathResolver::Absolute,
+                ..config::PathFormat::default()
+            };
+            CommandConverter::new(config::Format { paths, ..config.format.clone() })
+        });
+        Self { writer, converter }
+    }
+}
+
+impl<T: IteratorWriter<Command>> IteratorWriter<Command> for CompileFlagsOutputWriter<T> {
+    fn write(self, commands: impl Iterator<Item = Command>) -> Result<(), WriterError> {
+        let Some(converter) = self.converter else {
+            return self.writer.write(commands);
+        };
+
+        let mut directories: BTreeMap<PathBuf, DirectoryFlags> = BTreeMap::new();
+        let inspected = commands.inspect(|cmd| {
+            for (source, flags) in converter.to_flags(cmd) {
+                if let Some(directory) = source.parent() {
+                    directories.entry(directory.to_path_buf()).or_default().add(flags);
+                }
+            }
+        });
+        self.writer.write(inspected)?;
+
+        for (directory, flags) in directories {
+            flags.write(&directory)?;
+        }
+        Ok(())
+    }
+}
+
+/// Collects the flags of the translation units of a single directory.
+#[derive(Debug, Default)]
+struct DirectoryFlags {
+    /// The flags common to all translation units (in the order of the first one).
+    common: Vec<Vec<String>>,
+    /// All flags seen in the directory.
+    seen: Vec<Vec<String>>,
+    /// The number of translation units.
+    count: usize,
+}
+
+impl DirectoryFlags {
+    /// Adds the flags of a translation unit.
+    fn add(&mut self, flags: Vec<Vec<String>>) {
+        if self.count == 0 {
+            self.common = flags.clone();
+        } else {
+            self.common.retain(|flag| flags.contains(flag));
+        }
+        for flag in flags {
+            if !self.seen.contains(&flag) {
+                self.seen.push(flag);
+            }
+        }
+        self.count += 1;
+    }
+
+    /// Returns the flags which are not used by all translation units.
+    fn conflicting(&self) -> Vec<&Vec<String>> {
+        self.seen.iter().filter(|flag| !self.common.contains(flag)).collect()
+    }
+
+    /// Writes the common flags into the `compile_flags.txt` of the directory.
+    fn write(&self, directory: &Path) -> Result<(), WriterError> {
+        let path = directory.join(COMPILE_FLAGS_FILE);
+
+        let conflicting = self.conflicting();
+        if !conflicting.is_empty() {
+            let flags: Vec<&str> = conflicting.into_iter().flatten().map(String::as_str).collect();
+            log::warn!(
+                "Translation units in {} are compiled with different flags, these are not written into {}: {}",
+                directory.display(),
+                COMPILE_FLAGS_FILE,
+                flags.join(" ")
+            );
+        }
+
+        let content: String = self.common.iter().flatten().map(|flag| format!("{flag}\n")).collect();
+        fs::write(&path, content).map_err(|err| WriterError::Io(path.clone(), SerializationError::Io(err)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::semantic::{ArgumentKind, CompilerPass, PassEffect};
+    use std::sync::{Arc, Mutex};
+
+    /// A test writer that collects the commands passed through the stage.
+    struct CollectingCommandWriter {
+        collected: Arc<Mutex<Vec<Command>>>,
+    }
+
+    impl IteratorWriter<Command> for CollectingCommandWriter {
+        fn write(self, items: impl Iterator<Item = Command>) -> Result<(), WriterError> {
+            self.collected.lock().unwrap().extend(items);
+            Ok(())
+        }
+    }
+
+    fn make_command(directory: &Path, source: &str, flags: Vec<Vec<&str>>) -> Command {
+        let mut arguments = vec![
+            (ArgumentKind::Compiler, vec!["/usr/bin/gcc"]),
+            (ArgumentKind::Other(PassEffect::StopsAt(CompilerPass::Compiling)), vec!["-c"]),
+        ];
+        arguments.extend(flags.into_iter().map(|flag| (ArgumentKind::Other(PassEffect::None), flag)));
+        arguments.push((ArgumentKind::Source { binary: false }, vec![source]));
+        arguments.push((ArgumentKind::Output, vec!["-o", "out.o"]));
+        Command::from_strings(directory.to_str().unwrap(), "/usr/bin/gcc", arguments)
+    }
+
+    fn enabled_config() -> config::Main {
+        config::Main { compile_flags: config::CompileFlags { enabled: true }, ..config::Main::default() }
+    }
+
+    // Requirements: output-compile-flags
+    #[test]
+    fn test_writes_common_flags_per_directory() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        fs::create_dir(root.join("src")).unwrap();
+        fs::create_dir(root.join("lib")).unwrap();
+
+        let collected = Arc::new(Mutex::new(vec![]));
+        let writer = CollectingCommandWriter { collected: Arc::clone(&collected) };
+        let sut = CompileFlagsOutputWriter::new(writer, &enabled_config());
+
+        let commands = vec![
+            make_command(root, "src/a.c", vec![vec!["-DFOO"], vec!["-I", "include"], vec!["-O2"]]),
+            make_command(root, "src/b.c", vec![vec!["-DFOO"], vec!["-I", "include"], vec!["-O0"]]),
+            make_command(root, "lib/c.c", vec![vec!["-std=c11"]]),
+        ];
+        sut.write(commands.into_iter()).unwrap();
+
+        assert_eq!(collected.lock().unwrap().len(), 3);
+
+        let expected = "-DFOO\n-I\ninclude\n";
+        assert_eq!(fs::read_to_string(root.join("src").join(COMPILE_FLAGS_FILE)).unwrap(), expected);
+        assert_eq!(fs::read_to_string(root.join("lib").join(COMPILE_FLAGS_FILE)).unwrap(), "-std=c11\n");
+        assert!(!root.join(COMPILE_FLAGS_FILE).exists());
+    }
+
+    #[test]
+    fn test_disabled_writes_no_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+
+        let collected = Arc::new(Mutex::new(vec![]));
+        let writer = CollectingCommandWriter { collected: Arc::clone(&collected) };
+        let sut = CompileFlagsOutputWriter::new(writer, &config::Main::default());
+
+        let commands = vec![make_command(root, "a.c", vec![vec!["-DFOO"]])];
+        sut.write(commands.into_iter()).unwrap();
+
+        assert_eq!(collected.lock().unwrap().len(), 1);
+        assert!(!root.join(COMPILE_FLAGS_FILE).exists());
+    }
+
+    #[test]
+    fn test_directory_flags_conflicting() {
+        let mut sut = DirectoryFlags::default();
+        sut.add(vec![vec!["-DFOO".into()], vec!["-O2".into()]]);
+        sut.add(vec![vec!["-O2".into()], vec!["-DFOO".into()]]);
+        assert!(sut.conflicting().is_empty());
+
+        sut.add(vec![vec!["-O2".into()], vec!["-DBAR".into()]]);
+        assert_eq!(sut.common, vec![vec!["-O2".to_string()]]);
+        assert_eq!(sut.conflicting(), vec![&vec!["-DFOO".to_string()], &vec!["-DBAR".to_string()]]);
+    }
+}
@@ -0,0 +1,666 @@
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! Compiler driver query stage for the output pipeline.
+//!
+//! Cross toolchains (like `arm-none-eabi-gcc`) have implicit include directories,
+//! built-in macro definitions and a default target, which tools consuming the
+//! compilation database (clangd, clang-tidy) can't discover on their own. This
+//! stage executes each distinct compiler once to collect these settings, and
+//! appends them as explicit flags to the compiler commands.
+//!
+//! The compiler is executed with `-E -v -x <language> -` (reading an empty
+//! standard input), to get the include search list and the target triple.
+//! When defines are requested, the `-E -dM -x <language> -` execution lists
+//! the built-in macros. This is the same what clangd's `--query-driver` does,
+//! but the result is baked into the compilation database.
+//!
+//! The query results are cached per compiler, language and the flags which
+//! change the implicit settings (like `--sysroot` or `-m32`). Failed queries
+//! are cached too, so a compiler that does not understand these flags is
+//! executed only once.
+
+use super::IteratorWriter;
+use crate::config;
+use crate::output::WriterError;
+use crate::semantic::{
+    Argument, ArgumentKind, ClangCompatibility, Command, CompilerPass, FlagValue, PassEffect,
+};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use thiserror::Error;
+
+/// Flags that change the implicit settings of the compiler driver.
+///
+/// These are forwarded to the query execution and are part of the cache key.
+const FORWARDED_FLAGS: &[&str] = &[
+    "--sysroot",
+    "-isysroot",
+    "--target",
+    "-target",
+    "-m32",
+    "-m64",
+    "-stdlib",
+    "-specs",
+    "-nostdinc",
+    "-nostdinc++",
+    "-nostdlibinc",
+    "--no-standard-includes",
+];
+
+/// Languages accepted in the glued `-x<language>` form.
+///
+/// Other compilers use `-x` as a prefix of unrelated flags (e.g. `-xHost`),
+/// so the glued form is only recognized with these values.
+const GLUED_LANGUAGES: &[&str] = &["c", "c++", "objective-c", "objective-c++", "c-header", "c++-header"];
+
+/// Describes a single query against a compiler driver.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub(crate) struct DriverRequest {
+    /// The compiler executable to run.
+    pub executable: PathBuf,
+    /// The working directory of the original compilation.
+    pub working_dir: PathBuf,
+    /// The value passed to the `-x` flag.
+    pub language: String,
+    /// Flags from the original compilation which affect the implicit settings.
+    pub flags: Vec<String>,
+    /// Whether the built-in macro definitions shall be collected.
+    pub defines: bool,
+}
+
+/// The implicit settings of a compiler driver.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub(crate) struct DriverInfo {
+    /// The system include directories in search order.
+    pub include_dirs: Vec<IncludeDirectory>,
+    /// The built-in macro definitions as `NAME` or `NAME=VALUE`.
+    pub defines: Vec<String>,
+    /// The target triple the compiler generates code for.
+    pub target: Option<String>,
+}
+
+/// A system include directory reported by the compiler driver.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) enum IncludeDirectory {
+    System(PathBuf),
+    Framework(PathBuf),
+}
+
+/// Represents errors that can occur while querying a compiler driver.
+#[derive(Debug, Error)]
+pub(crate) enum DriverQueryError {
+    #[error("Failed to execute: {0}")]
+    Execution(#[from] std::io::Error),
+    #[error("Exited with {0}")]
+    ExitStatus(std::process::ExitStatus),
+    #[error("Include search list not found in the output")]
+    MissingSearchList,
+}
+
+/// Responsible for collecting the implicit settings of a compiler driver.
+#[cfg_attr(test, mockall::automock)]
+pub(crate) trait DriverInspector {
+    fn inspect(&self, request: &DriverRequest) -> Result<DriverInfo, DriverQueryError>;
+}
+
+/// Inspects the compiler driver by executing it.
+pub(crate) struct ExecutingDriverInspector;
+
+impl ExecutingDriverInspector {
+    /// Runs the compiler in preprocessor mode on an empty input with the given flags.
+    ///
+    /// Returns the standard output and standard error of the process.
+    fn run(request: &DriverRequest, flags: &[&str]) -> Result<(String, String), DriverQueryError> {
+        let mut command = std::process::Command::new(&request.executable);
+        command
+            .args(&request.flags)
+            .args(flags)
+            .args(["-x", request.language.as_str(), "-"])
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped());
+        if request.working_dir.is_dir() {
+            command.current_dir(&request.working_dir);
+        }
+
+        let output = command.output()?;
+        if !output.status.success() {
+            return Err(DriverQueryError::ExitStatus(output.status));
+        }
+        Ok((
+            String::from_utf8_lossy(&output.stdout).into_owned(),
+            String::from_utf8_lossy(&output.stderr).into_owned(),
+        ))
+    }
+}
+
+impl DriverInspector for ExecutingDriverInspector {
+    fn inspect(&self, request: &DriverRequest) -> Result<DriverInfo, DriverQueryError> {
+        let (_, verbose) = Self::run(request, &["-E", "-v"])?;
+        let include_dirs = parse_include_dirs(&verbose).ok_or(DriverQueryError::MissingSearchList)?;
+        let target = parse_target(&verbose);
+
+        let defines = if request.defines {
+            let (macros, _) = Self::run(request, &["-E", "-dM"])?;
+            parse_defines(&macros)
+        } else {
+            vec![]
+        };
+
+        Ok(DriverInfo { include_dirs, defines, target })
+    }
+}
+
+/// Parses the `#include <...>` search list from the verbose output of the driver.
+///
+/// Returns `None` if the search list was not found in the output.
+fn parse_include_dirs(output: &str) -> Option<Vec<IncludeDirectory>> {
+    let mut lines = output.lines().skip_while(|line| !line.starts_with("#include <...> search starts here:"));
+    lines.next()?;
+
+    let mut result = vec![];
+    for line in lines {
+        if line.starts_with("End of search list.") {
+            return Some(result);
+        }
+        let line = line.trim();
+        if let Some(path) = line.strip_suffix("(framework directory)") {
+            result.push(IncludeDirectory::Framework(PathBuf::from(path.trim_end())));
+        } else if !line.is_empty() {
+            result.push(IncludeDirectory::System(PathBuf::from(line)));
+        }
+    }
+    None
+}
+
+/// Parses the target triple from the verbose output of the driver.
+fn parse_target(output: &str) -> Option<String> {
+    output
+        .lines()
+        .find_map(|line| line.strip_prefix("Target:"))
+        .map(|target| target.trim().to_string())
+        .filter(|target| !target.is_empty())
+}
+
+/// Parses the `#define` lines of the driver output into `NAME=VALUE` strings.
+///
+/// Function-like macros are skipped, since those can't be defined from the
+/// command line in a portable way.
+fn parse_defines(output: &str) -> Vec<String> {
+    output
+        .lines()
+        .filter_map(|line| line.strip_prefix("#define "))
+        .filter_map(|definition| {
+            let (name, value) = definition.split_once(' ').unwrap_or((definition, ""));
+            if name.is_empty() || name.contains('(') {
+                return None;
+            }
+            if value.is_empty() { Some(name.to_string()) } else { Some(format!("{name}={value}")) }
+        })
+        .collect()
+}
+
+/// Appends the implicit compiler settings to the semantic commands.
+///
+/// The stage is disabled by default; in that case the commands are passed
+/// to the inner writer unchanged.
+pub(crate) struct DriverQueryOutputWriter<
+    T: IteratorWriter<Command>,
+    I: DriverInspector = ExecutingDriverInspector,
+> {
+    writer: T,
+    inspector: Option<I>,
+    defines: bool,
+}
+
+impl<T: IteratorWriter<Command>> DriverQueryOutputWriter<T> {
+    pub(crate) fn new(writer: T, config: &config::DriverQuery) -> Self {
+        let inspector = config.enabled.then_some(ExecutingDriverInspector);
+        Self { writer, inspector, defines: config.defines }
+    }
+}
+
+impl<T: IteratorWriter<Command>, I: DriverInspector> DriverQueryOutputWriter<T, I> {
+    #[cfg(test)]
+    fn with_inspector(writer: T, inspector: I, defines: bool) -> Self {
+        Self { writer, inspector: Some(inspector), defines }
+    }
+}
+
+impl<T: IteratorWriter<Command>, I: DriverInspector> IteratorWriter<Command>
+    for DriverQueryOutputWriter<T, I>
+{
+    fn write(self, commands: impl Iterator<Item = Command>) -> Result<(), WriterError> {
+        let Some(inspector) = self.inspector else {
+            return self.writer.write(commands);
+        };
+
+        let mut cache = DriverCache::new(inspector, self.defines);
+        let extended = commands.map(move |mut cmd| {
+            cache.extend(&mut cmd);
+            cmd
+        });
+
+        self.writer.write(extended)
+    }
+}
+
+/// Caches the driver query results and extends the commands with them.
+struct DriverCache<I: DriverInspector> {
+    inspector: I,
+    defines: bool,
+    results: HashMap<DriverRequest, Option<DriverInfo>>,
+}
+
+impl<I: DriverInspector> DriverCache<I> {
+    fn new(inspector: I, defines: bool) -> Self {
+        Self { inspector, defines, results: HashMap::new() }
+    }
+
+    /// Appends the implicit settings of the compiler to the command arguments.
+    fn extend(&mut self, cmd: &mut Command) {
+        let Some(request) = self.request_for(cmd) else {
+            return;
+        };
+
+        let inspector = &self.inspector;
+        let info = self.results.entry(request).or_insert_with_key(|request| {
+            log::debug!("Querying compiler driver: {:?} ({})", request.executable, request.language);
+            match inspector.inspect(request) {
+                Ok(info) => Some(info),
+                Err(error) => {
+                    log::warn!(
+                        "Failed to query compiler driver {:?} for {}: {}",
+                        request.executable,
+                        request.language,
+                        error
+                    );
+                    None
+                }
+            }
+        });
+
+        if let Some(info) = info {
+            let explicit_target = cmd.has_explicit_target();
+            cmd.arguments.extend(Self::arguments_from(info, explicit_target));
+        }
+    }
+
+    /// Creates the query for the command, or `None` when the language is not known.
+    fn request_for(&self, cmd: &Command) -> Option<DriverRequest> {
+        let language = detect_language(&cmd.arguments)?;
+        let flags = cmd
+            .arguments
+            .iter()
+            .filter_map(|arg| match arg {
+                Argument::Other { arguments, .. } if is_one_of(arguments, FORWARDED_FLAGS) => Some(arguments),
+                _ => None,
+            })
+            .flatten()
+            .cloned()
+            .collect();
+
+        Some(DriverRequest {
+            executable: cmd.executable.clone(),
+            working_dir: cmd.working_dir.clone(),
+            language,
+            flags,
+            defines: self.defines,
+        })
+    }
+
+    /// Converts the driver settings into compiler arguments.
+    fn arguments_from(info: &DriverInfo, has_target: bool) -> Vec<Argument> {
+        let mut result = vec![];
+        if let Some(target) = info.target.as_ref().filter(|_| !has_target) {
+            result.push(Argument::Other {
+                arguments: vec![format!("--target={target}")],
+                kind: ArgumentKind::Other(PassEffect::Configures(CompilerPass::Compiling)),
+                clang: ClangCompatibility::Supported,
+                value: FlagValue::Plain,
+            });
+        }
+        for define in &info.defines {
+            result.push(Argument::Other {
+                arguments: vec![format!("-D{define}")],
+                kind: ArgumentKind::Other(PassEffect::Configures(CompilerPass::Preprocessing)),
+                clang: ClangCompatibility::Supported,
+                value: FlagValue::Plain,
+            });
+        }
+        for directory in &info.include_dirs {
+            let (flag, path) = match directory {
+                IncludeDirectory::System(path) => ("-isystem", path),
+                IncludeDirectory::Framework(path) => ("-iframework", path),
+            };
+            result.push(Argument::Other {
+                arguments: vec![flag.to_string(), path.to_string_lossy().to_string()],
+                kind: ArgumentKind::Other(PassEffect::Configures(CompilerPass::Preprocessing)),
+                clang: ClangCompatibility::Supported,
+                value: FlagValue::Plain,
+            });
+        }
+        result
+    }
+}
+
+/// Checks if the first element of the argument group is one of the flags.
+///
+/// The flag may be followed by its value glued with `=`.
+fn is_one_of(arguments: &[String], flags: &[&str]) -> bool {
+    arguments.first().is_some_and(|first| {
+        flags
+            .iter()
+            .any(|flag| first.strip_prefix(flag).is_some_and(|rest| rest.is_empty() || rest.starts_with('=')))
+    })
+}
+
+/// Detects the language of the compilation.
+///
+/// An explicit `-x` flag takes precedence, otherwise the extension of the
+/// first source file decides. Returns `None` for languages where the driver
+/// query is not meaningful (e.g. Fortran).
+fn detect_language(arguments: &[Argument]) -> Option<String> {
+    let explicit = arguments.iter().find_map(|arg| match arg {
+        Argument::Other { arguments, .. } => match arguments.as_slice() {
+            [flag, value] if flag == "-x" => Some(value.clone()),
+            [flag] => {
+                flag.strip_prefix("-x").filter(|value| GLUED_LANGUAGES.contains(value)).map(str::to_string)
+            }
+            _ => None,
+        },
+        _ => None,
+    });
+    if explicit.is_some() {
+        return explicit;
+    }
+
+    arguments.iter().find_map(|arg| match arg {
+        Argument::Source { path, binary: false } => language_of(Path::new(path)).map(str::to_string),
+        _ => None,
+    })
+}
+
+/// Maps the source file extension to the language name used by `-x`.
+fn language_of(path: &Path) -> Option<&'static str> {
+    match path.extension()?.to_str()? {
+        "c" => Some("c"),
+        "C" | "cc" | "cp" | "cpp" | "CPP" | "cxx" | "c++" | "C++" => Some("c++"),
+        "m" => Some("objective-c"),
+        "mm" | "M" => Some("objective-c++"),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::semantic::ArgumentKind;
+    use std::sync::{Arc, Mutex};
+
+    /// A test writer that collects the commands passed through the stage.
+    struct CollectingCommandWriter {
+        collected: Arc<Mutex<Vec<Command>>>,
+    }
+
+    impl IteratorWriter<Command> for CollectingCommandWriter {
+        fn write(self, items: impl Iterator<Item = Command>) -> Result<(), WriterError> {
+            self.collected.lock().unwrap().extend(items);
+            Ok(())
+        }
+    }
+
+    fn collecting_writer() -> (CollectingCommandWriter, Arc<Mutex<Vec<Command>>>) {
+        let collected = Arc::new(Mutex::new(vec![]));
+        (CollectingCommandWriter { collected: Arc::clone(&collected) }, collected)
+    }
+
+    fn make_command(executable: &str, arguments: Vec<(ArgumentKind, Vec<&str>)>) -> Command {
+        Command::from_strings("/home/user", executable, arguments)
+    }
+
+    fn arguments_of(cmd: &Command) -> Vec<String> {
+        cmd.arguments.iter().flat_map(|arg| arg.as_arguments(&|p| std::borrow::Cow::Borrowed(p))).collect()
+    }
+
+    const GCC_VERBOSE_OUTPUT: &str = "\
+Using built-in specs.
+COLLECT_GCC=arm-none-eabi-gcc
+Target: arm-none-eabi
+Thread model: single
+#include \"...\" search starts here:
+#include <...> search starts here:
+ /opt/arm/lib/gcc/arm-none-eabi/12.2.1/include
+ /opt/arm/lib/gcc/arm-none-eabi/12.2.1/include-fixed
+ /opt/arm/arm-none-eabi/include
+End of search list.
+";
+
+    const CLANG_VERBOSE_OUTPUT: &str = "\
+clang version 17.0.0
+Target: arm64-apple-darwin23.0.0
+#include \"...\" search starts here:
+#include <...> search starts here:
+ /usr/local/include
+ /Library/Developer/CommandLineTools/usr/lib/clang/17/include
+ /Library/Developer/CommandLineTools/SDKs/MacOSX.sdk/System/Library/Frameworks (framework directory)
+End of search list.
+";
+
+    #[test]
+    fn test_parse_include_dirs_gcc() {
+        let result = parse_include_dirs(GCC_VERBOSE_OUTPUT).unwrap();
+
+        assert_eq!(
+            result,
+            vec![
+                IncludeDirectory::System(PathBuf::from("/opt/arm/lib/gcc/arm-none-eabi/12.2.1/include")),
+                IncludeDirectory::System(PathBuf::from(
+                    "/opt/arm/lib/gcc/arm-none-eabi/12.2.1/include-fixed"
+                )),
+                IncludeDirectory::System(PathBuf::from("/opt/arm/arm-none-eabi/include")),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_include_dirs_clang_frameworks() {
+        let result = parse_include_dirs(CLANG_VERBOSE_OUTPUT).unwrap();
+
+        assert_eq!(result.len(), 3);
+        assert_eq!(
+            result[2],
+            IncludeDirectory::Framework(PathBuf::from(
+                "/Library/Developer/CommandLineTools/SDKs/MacOSX.sdk/System/Library/Frameworks"
+            ))
+        );
+    }
+
+    #[test]
+    fn test_parse_include_dirs_missing_or_unterminated() {
+        assert_eq!(parse_include_dirs("Using built-in specs.\n"), None);
+        assert_eq!(parse_include_dirs("#include <...> search starts here:\n /usr/include\n"), None);
+    }
+
+    #[test]
+    fn test_parse_target() {
+        assert_eq!(parse_target(GCC_VERBOSE_OUTPUT), Some("arm-none-eabi".to_string()));
+        assert_eq!(parse_target(CLANG_VERBOSE_OUTPUT), Some("arm64-apple-darwin23.0.0".to_string()));
+        assert_eq!(parse_target("Thread model: posix\n"), None);
+    }
+
+    #[test]
+    fn test_parse_defines() {
+        let output = "\
+#define __ARM_ARCH 7
+#define __GNUC__ 12
+#define __thumb__ 1
+#define __STDC__
+#define __has_include(STR) __has_include__(STR)
+#define __VERSION__ \"12.2.1 20221205\"
+";
+        assert_eq!(
+            parse_defines(output),
+            vec!["__ARM_ARCH=7", "__GNUC__=12", "__thumb__=1", "__STDC__", "__VERSION__=\"12.2.1 20221205\"",]
+        );
+    }
+
+    #[test]
+    fn test_detect_language() {
+        let cases = vec![
+            (vec![(ArgumentKind::Source { binary: false }, vec!["main.c"])], Some("c")),
+            (vec![(ArgumentKind::Source { binary: false }, vec!["main.cpp"])], Some("c++")),
+            (vec![(ArgumentKind::Source { binary: false }, vec!["main.mm"])], Some("objective-c++")),
+            (vec![(ArgumentKind::Source { binary: false }, vec!["main.f90"])], None),
+            (vec![(ArgumentKind::Source { binary: true }, vec!["main.o"])], None),
+            (
+                vec![
+                    (ArgumentKind::Other(PassEffect::Configures(CompilerPass::Compiling)), vec!["-x", "c++"]),
+                    (ArgumentKind::Source { binary: false }, vec!["main.c"]),
+                ],
+                Some("c++"),
+            ),
+            (
+                vec![
+                    (ArgumentKind::Other(PassEffect::None), vec!["-xc"]),
+                    (ArgumentKind::Source { binary: false }, vec!["main.cpp"]),
+                ],
+                Some("c"),
+            ),
+        ];
+
+        for (arguments, expected) in cases {
+            let cmd = make_command("gcc", arguments);
+            assert_eq!(detect_language(&cmd.arguments).as_deref(), expected);
+        }
+    }
+
+    #[test]
+    fn test_disabled_stage_passes_commands_unchanged() {
+        let (writer, collected) = collecting_writer();
+        let sut = DriverQueryOutputWriter::new(writer, &config::DriverQuery::default());
+
+        let cmd = make_command(
+            "/usr/bin/gcc",
+            vec![
+                (ArgumentKind::Compiler, vec!["/usr/bin/gcc"]),
+                (ArgumentKind::Other(PassEffect::StopsAt(CompilerPass::Compiling)), vec!["-c"]),
+                (ArgumentKind::Source { binary: false }, vec!["main.c"]),
+            ],
+        );
+        sut.write(vec![cmd].into_iter()).unwrap();
+
+        let result = collected.lock().unwrap();
+        assert_eq!(arguments_of(&result[0]), vec!["/usr/bin/gcc", "-c", "main.c"]);
+    }
+
+    #[test]
+    fn test_stage_appends_driver_settings() {
+        let mut inspector = MockDriverInspector::new();
+        inspector
+            .expect_inspect()
+            .withf(|request| {
+                request.executable == Path::new("/opt/arm/bin/arm-none-eabi-gcc")
+                    && request.language == "c"
+                    && request.flags == vec!["-m32"]
+                    && request.defines
+            })
+            .times(1)
+            .returning(|_| {
+                Ok(DriverInfo {
+                    include_dirs: vec![IncludeDirectory::System(PathBuf::from("/opt/arm/include"))],
+                    defines: vec!["__ARM_ARCH=7".to_string()],
+                    target: Some("arm-none-eabi".to_string()),
+                })
+            });
+
+        let (writer, collected) = collecting_writer();
+        let sut = DriverQueryOutputWriter::with_inspector(writer, inspector, true);
+
+        let make = |source: &str| {
+            make_command(
+                "/opt/arm/bin/arm-none-eabi-gcc",
+                vec![
+                    (ArgumentKind::Compiler, vec!["arm-none-eabi-gcc"]),
+                    (ArgumentKind::Other(PassEffect::StopsAt(CompilerPass::Compiling)), vec!["-c"]),
+                    (ArgumentKind::Other(PassEffect::Configures(CompilerPass::Compiling)), vec!["-m32"]),
+                    (ArgumentKind::Source { binary: false }, vec![source]),
+                ],
+            )
+        };
+        sut.write(vec![make("main.c"), make("util.c")].into_iter()).unwrap();
+
+        let result = collected.lock().unwrap();
+        assert_eq!(result.len(), 2);
+        assert_eq!(
+            arguments_of(&result[1]),
+            vec![
+                "arm-none-eabi-gcc",
+                "-c",
+                "-m32",
+                "util.c",
+                "--target=arm-none-eabi",
+                "-D__ARM_ARCH=7",
+                "-isystem",
+                "/opt/arm/include",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_stage_keeps_explicit_target() {
+        let mut inspector = MockDriverInspector::new();
+        inspector.expect_inspect().returning(|_| {
+            Ok(DriverInfo {
+                include_dirs: vec![IncludeDirectory::System(PathBuf::from("/usr/include"))],
+                defines: vec![],
+                target: Some("x86_64-pc-linux-gnu".to_string()),
+            })
+        });
+
+        let (writer, collected) = collecting_writer();
+        let sut = DriverQueryOutputWriter::with_inspector(writer, inspector, false);
+
+        let cmd = make_command(
+            "/usr/bin/clang",
+            vec![
+                (ArgumentKind::Compiler, vec!["clang"]),
+                (
+                    ArgumentKind::Other(PassEffect::Configures(CompilerPass::Compiling)),
+                    vec!["--target=aarch64-linux-gnu"],
+                ),
+                (ArgumentKind::Source { binary: false }, vec!["main.c"]),
+            ],
+        );
+        sut.write(vec![cmd].into_iter()).unwrap();
+
+        let result = collected.lock().unwrap();
+        assert_eq!(
+            arguments_of(&result[0]),
+            vec!["clang", "--target=aarch64-linux-gnu", "main.c", "-isystem", "/usr/include"]
+        );
+    }
+
+    #[test]
+    fn test_stage_caches_failures_and_skips_unknown_languages() {
+        let mut inspector = MockDriverInspector::new();
+        inspector.expect_inspect().times(1).returning(|_| Err(DriverQueryError::MissingSearchList));
+
+        let (writer, collected) = collecting_writer();
+        let sut = DriverQueryOutputWriter::with_inspector(writer, inspector, false);
+
+        let commands = vec![
+            make_command("cl.exe", vec![(ArgumentKind::Source { binary: false }, vec!["a.c"])]),
+            make_command("cl.exe", vec![(ArgumentKind::Source { binary: false }, vec!["b.c"])]),
+            make_command("gfortran", vec![(ArgumentKind::Source { binary: false }, vec!["c.f90"])]),
+        ];
+        sut.write(commands.into_iter()).unwrap();
+
+        let result = collected.lock().unwrap();
+        assert_eq!(result.len(), 3);
+        assert_eq!(arguments_of(&result[0]), vec!["a.c"]);
+        assert_eq!(arguments_of(&result[1]), vec!["b.c"]);
+        assert_eq!(arguments_of(&result[2]), vec!["c.f90"]);
+    }
+}
@@ -1,8 +1,9 @@
 // SPDX-License-Identifier: GPL-3.0-or-later
 
 use super::IteratorWriter;
+use crate::config;
 use crate::output::clang;
-use crate::output::clang::serialization::JsonCompilationDatabase;
+use crate::output::clang::serialization::{JsonCompilationDatabase, JsonLinesCompilationDatabase};
 use crate::output::formats::SerializationFormat;
 use crate::output::statistics::OutputStatistics;
 use crate::output::{WriterCreationError, WriterError};
@@ -15,22 +16,25 @@ use std::{fs, io, path};
 /// # Features
 /// - Writes the entries to a file.
 /// - Formats the entries to the configured shape.
+/// - Writes the configured file format (JSON array or JSON lines).
 pub(crate) struct ClangOutputWriter {
     output: io::BufWriter<fs::File>,
     path: path::PathBuf,
+    format: config::DatabaseFormat,
     stats: Arc<OutputStatistics>,
 }
 
 impl ClangOutputWriter {
     pub(crate) fn create(
         path: &path::Path,
+        format: config::DatabaseFormat,
         stats: Arc<OutputStatistics>,
     ) -> Result<Self, WriterCreationError> {
         let output = fs::File::create(path)
             .map(io::BufWriter::new)
             .map_err(|err| WriterCreationError::Io(path.to_path_buf(), err))?;
 
-        Ok(Self { output, path: path.to_path_buf(), stats })
+        Ok(Self { output, path: path.to_path_buf(), format, stats })
     }
 }
 
@@ -43,8 +47,13 @@ impl IteratorWriter<clang::Entry> for ClangOutputWriter {
             stats.entries_written.fetch_add(1, Ordering::Relaxed);
         });
 
-        JsonCompilationDatabase::write(self.output, counted_entries)
-            .map_err(|err| WriterError::Io(self.path, err))
+        match self.format {
+            config::DatabaseFormat::Json => JsonCompilationDatabase::write(self.output, counted_entries),
+            config::DatabaseFormat::JsonLines => {
+                JsonLinesCompilationDatabase::write(self.output, counted_entries)
+            }
+        }
+        .map_err(|err| WriterError::Io(self.path, err))
     }
 }
 
@@ -66,7 +75,8 @@ mod tests {
             clang::Entry::from_arguments_str("file3.c", vec!["gcc", "-c"], "/project", Some("file3.o")),
         ];
 
-        let writer = ClangOutputWriter::create(&path, Arc::clone(&stats)).unwrap();
+        let writer =
+            ClangOutputWriter::create(&path, config::DatabaseFormat::Json, Arc::clone(&stats)).unwrap();
         writer.write(entries.into_iter()).unwrap();
 
         assert_eq!(stats.entries_written.load(Ordering::Relaxed), 3);
@@ -84,6 +94,7 @@ mod tests {
         let stats = OutputStatistics::new();
         let result = ClangOutputWriter::create(
             path::Path::new("/nonexistent/directory/output.json"),
+            config::DatabaseFormat::Json,
             Arc::clone(&stats),
         );
 
@@ -102,7 +113,8 @@ mod tests {
         let path = dir.path().join("output.json");
         let stats = OutputStatistics::new();
 
-        let writer = ClangOutputWriter::create(&path, Arc::clone(&stats)).unwrap();
+        let writer =
+            ClangOutputWriter::create(&path, config::DatabaseFormat::Json, Arc::clone(&stats)).unwrap();
         writer.write(std::iter::empty()).unwrap();
 
         assert_eq!(stats.entries_written.load(Ordering::Relaxed), 0);
@@ -25,6 +25,14 @@ pub(crate) use unique::DuplicateEntryFilter;
 /// output pipeline. Uses `&mut self` to support stateful filters (e.g. dedup).
 pub(crate) trait EntryFilter {
     fn accept(&mut self, entry: &Entry) -> bool;
+
+    /// Whether the filter shall see the entries in reverse order.
+    ///
+    /// The order of the accepted entries is not changed, but the whole stream
+    /// is buffered to reverse it.
+    fn reversed(&self) -> bool {
+        false
+    }
 }
 
 /// A generic pipeline writer that filters entries using an [`EntryFilter`]
@@ -53,15 +61,25 @@ impl<T: IteratorWriter<Entry>, F: EntryFilter> IteratorWriter<Entry> for Filtere
         let stats = Arc::clone(&self.stats);
         let rejected_counter = self.rejected_counter;
 
-        let filtered_entries = entries.filter(move |entry| {
+        let reversed = filter.reversed();
+        let mut accept = move |entry: &Entry| {
             let accepted = filter.accept(entry);
             if !accepted {
                 rejected_counter(&stats).fetch_add(1, Ordering::Relaxed);
             }
             accepted
-        });
-
-        self.writer.write(filtered_entries)
+        };
+
+        if reversed {
+            let mut entries: Vec<Entry> = entries.collect();
+            entries.reverse();
+            let mut accepted_entries: Vec<Entry> =
+                entries.into_iter().filter(|entry| accept(entry)).collect();
+            accepted_entries.reverse();
+            self.writer.write(accepted_entries.into_iter())
+        } else {
+            self.writer.write(entries.filter(move |entry| accept(entry)))
+        }
     }
 }
 
@@ -79,6 +97,7 @@ mod tests {
         let (writer, collected) = CollectingWriter::new();
         let filter = DuplicateEntryFilter::try_from(config::DuplicateFilter {
             match_on: vec![config::OutputFields::File, config::OutputFields::Directory],
+            ..config::DuplicateFilter::default()
         })
         .unwrap();
 
@@ -100,6 +119,38 @@ mod tests {
         assert_eq!(stats.duplicates_detected.load(Ordering::Relaxed), 2);
     }
 
+    // Requirements: output-duplicate-detection
+    #[test]
+    fn test_filtered_writer_keeps_last_duplicates() {
+        let stats = OutputStatistics::new();
+        let (writer, collected) = CollectingWriter::new();
+        let filter = DuplicateEntryFilter::try_from(config::DuplicateFilter {
+            match_on: vec![config::OutputFields::File, config::OutputFields::Directory],
+            keep: config::DuplicateKeep::Last,
+            ..config::DuplicateFilter::default()
+        })
+        .unwrap();
+
+        let sut = FilteredOutputWriter::new(writer, filter, Arc::clone(&stats), |s| &s.duplicates_detected);
+
+        let entries = vec![
+            Entry::from_arguments_str("file1.c", vec!["gcc", "-c"], "/project", None),
+            Entry::from_arguments_str("file2.c", vec!["gcc", "-c"], "/project", None),
+            Entry::from_arguments_str("file3.c", vec!["gcc", "-c"], "/project", None),
+            Entry::from_arguments_str("file1.c", vec!["gcc", "-c", "-O2"], "/project", None),
+        ];
+
+        sut.write(entries.into_iter()).unwrap();
+
+        let result = collected.lock().unwrap();
+        assert_eq!(result.len(), 3);
+        assert_eq!(result[0].file, std::path::PathBuf::from("file2.c"));
+        assert_eq!(result[1].file, std::path::PathBuf::from("file3.c"));
+        assert_eq!(result[2].file, std::path::PathBuf::from("file1.c"));
+        assert_eq!(result[2].arguments, vec!["gcc", "-c", "-O2"]);
+        assert_eq!(stats.duplicates_detected.load(Ordering::Relaxed), 1);
+    }
+
     #[test]
     fn test_filtered_writer_filters_by_source() {
         let stats = OutputStatistics::new();
@@ -141,6 +192,7 @@ mod tests {
         let (writer, collected) = CollectingWriter::new();
         let filter = DuplicateEntryFilter::try_from(config::DuplicateFilter {
             match_on: vec![config::OutputFields::File],
+            ..config::DuplicateFilter::default()
         })
         .unwrap();
 
@@ -30,8 +30,12 @@
 //!
 //! 7. **Directory vs. file matching**: A directory rule matches both files directly in
 //!    that directory and files in any subdirectory (recursive matching).
+//!
+//! 8. **Path remapping**: The entries arrive with remapped paths (`format.paths.remap`),
+//!    so the rule paths are remapped the same way. Rules can be written with the paths
+//!    of the build environment or with the remapped ones.
 
-use crate::config::{DirectoryAction, SourceFilter};
+use crate::config::{DirectoryAction, PathFormat, SourceFilter};
 use crate::output::clang::Entry;
 use std::path::Path;
 
@@ -46,6 +50,14 @@ pub(crate) struct SourceEntryFilter {
 }
 
 impl SourceEntryFilter {
+    /// Creates a filter with the rule paths remapped by the path format configuration.
+    pub(crate) fn new(mut config: SourceFilter, paths: &PathFormat) -> Self {
+        for rule in config.directories.iter_mut() {
+            rule.path = paths.remap(&rule.path);
+        }
+        SourceEntryFilter { config }
+    }
+
     /// Determines whether a compilation database entry should be included.
     fn should_include(&self, entry: &Entry) -> bool {
         self.should_include_path(&entry.file)
@@ -90,7 +102,7 @@ impl From<SourceFilter> for SourceEntryFilter {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::config::{DirectoryAction, DirectoryRule, SourceFilter};
+    use crate::config::{DirectoryAction, DirectoryRule, PathRemap, SourceFilter};
     use crate::output::writers::filtering::EntryFilter;
     use std::path::PathBuf;
 
@@ -258,4 +270,28 @@ mod tests {
         assert!(filter.accept(&create_test_entry("build/config/settings.h", "/project")));
         assert!(filter.accept(&create_test_entry("build/config/generated/defs.h", "/project")));
     }
+
+    #[test]
+    fn test_rules_are_remapped() {
+        let config = SourceFilter {
+            directories: vec![
+                DirectoryRule { path: PathBuf::from("/work/third_party"), action: DirectoryAction::Exclude },
+                DirectoryRule {
+                    path: PathBuf::from("/home/dev/project/generated"),
+                    action: DirectoryAction::Exclude,
+                },
+            ],
+        };
+        let paths = PathFormat {
+            remap: vec![PathRemap { from: PathBuf::from("/work"), to: PathBuf::from("/home/dev/project") }],
+            ..PathFormat::default()
+        };
+        let mut filter = SourceEntryFilter::new(config, &paths);
+
+        assert!(
+            !filter.accept(&create_test_entry("/home/dev/project/third_party/lib.c", "/home/dev/project"))
+        );
+        assert!(!filter.accept(&create_test_entry("/home/dev/project/generated/gen.c", "/home/dev/project")));
+        assert!(filter.accept(&create_test_entry("/home/dev/project/src/main.c", "/home/dev/project")));
+    }
 }
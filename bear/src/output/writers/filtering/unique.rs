@@ -9,50 +9,119 @@
 use crate::config;
 use crate::output::clang::Entry;
 use std::collections::HashSet;
-use std::hash::{DefaultHasher, Hash, Hasher};
+use std::path::PathBuf;
 use thiserror::Error;
 
 // --- Duplicate entry filter ---
 
+/// Flags whose value is an output file name. These are ignored when the
+/// arguments are normalized.
+const OUTPUT_FLAGS: [&str; 4] = ["-o", "-MF", "-MT", "-MQ"];
+
+/// Flags which can be given in any order without changing the meaning of
+/// the compilation. These are compared as a set when the arguments are normalized.
+const UNORDERED_FLAGS: [&str; 2] = ["-D", "-I"];
+
 #[derive(Debug)]
 pub(crate) struct DuplicateEntryFilter {
     /// The fields to use for filtering duplicate entries.
     fields: Vec<config::OutputFields>,
-    /// The cache of seen hashes.
-    hash_values: HashSet<u64>,
+    /// Which occurrence of the duplicates is kept.
+    keep: config::DuplicateKeep,
+    /// Whether to normalize the arguments before the comparison.
+    normalize_arguments: bool,
+    /// The keys of the entries seen so far.
+    ///
+    /// The hash set is a hash index, which compares the full keys on hash
+    /// match, therefore hash collisions do not drop distinct entries.
+    seen: HashSet<DuplicateKey>,
+}
+
+/// The values of the configured fields of an entry.
+#[derive(Debug, Hash, PartialEq, Eq)]
+struct DuplicateKey(Vec<KeyValue>);
+
+#[derive(Debug, Hash, PartialEq, Eq)]
+enum KeyValue {
+    Path(PathBuf),
+    OptionalPath(Option<PathBuf>),
+    Arguments(Vec<String>),
+    Command(String),
 }
 
 unsafe impl Send for DuplicateEntryFilter {}
 
 impl DuplicateEntryFilter {
     fn unique(&mut self, entry: &Entry) -> bool {
-        let hash = self.hash_function(entry);
-        if self.hash_values.contains(&hash) {
-            return false;
-        }
-        self.hash_values.insert(hash);
-        true
-    }
-
-    fn hash_function(&self, entry: &Entry) -> u64 {
-        let mut hasher = DefaultHasher::new();
-        for field in &self.fields {
-            match field {
-                config::OutputFields::Directory => entry.directory.hash(&mut hasher),
-                config::OutputFields::File => entry.file.hash(&mut hasher),
-                config::OutputFields::Arguments => entry.arguments.hash(&mut hasher),
-                config::OutputFields::Command => entry.command.hash(&mut hasher),
-                config::OutputFields::Output => entry.output.hash(&mut hasher),
+        self.seen.insert(self.key(entry))
+    }
+
+    fn key(&self, entry: &Entry) -> DuplicateKey {
+        let values = self
+            .fields
+            .iter()
+            .map(|field| match field {
+                config::OutputFields::Directory => KeyValue::Path(entry.directory.clone()),
+                config::OutputFields::File => KeyValue::Path(entry.file.clone()),
+                config::OutputFields::Arguments if self.normalize_arguments => {
+                    KeyValue::Arguments(normalize(&entry.arguments))
+                }
+                config::OutputFields::Arguments => KeyValue::Arguments(entry.arguments.clone()),
+                config::OutputFields::Command if self.normalize_arguments => {
+                    match shell_words::split(&entry.command) {
+                        Ok(arguments) => KeyValue::Arguments(normalize(&arguments)),
+                        Err(_) => KeyValue::Command(entry.command.clone()),
+                    }
+                }
+                config::OutputFields::Command => KeyValue::Command(entry.command.clone()),
+                config::OutputFields::Output => KeyValue::OptionalPath(entry.output.clone()),
+            })
+            .collect();
+        DuplicateKey(values)
+    }
+}
+
+/// Normalizes the arguments for the duplicate comparison.
+///
+/// The values of the output flags are dropped, and the unordered flags are
+/// moved (in their joined form, like `-DNAME`) to a sorted group at the end.
+fn normalize(arguments: &[String]) -> Vec<String> {
+    let mut result = Vec::with_capacity(arguments.len());
+    let mut unordered = Vec::new();
+
+    let mut iter = arguments.iter();
+    while let Some(argument) = iter.next() {
+        if let Some(flag) = OUTPUT_FLAGS.iter().find(|flag| argument.starts_with(**flag)) {
+            if argument == flag {
+                iter.next();
+            }
+            result.push(flag.to_string());
+        } else if let Some(flag) = UNORDERED_FLAGS.iter().find(|flag| argument.starts_with(**flag)) {
+            if argument == flag {
+                let value = iter.next().map(String::as_str).unwrap_or_default();
+                unordered.push(format!("{flag}{value}"));
+            } else {
+                unordered.push(argument.clone());
             }
+        } else {
+            result.push(argument.clone());
         }
-        hasher.finish()
     }
+
+    unordered.sort();
+    result.extend(unordered);
+    result
 }
 
 impl super::EntryFilter for DuplicateEntryFilter {
     fn accept(&mut self, entry: &Entry) -> bool {
         self.unique(entry)
     }
+
+    /// Keeping the last occurrence is keeping the first one in reverse order.
+    fn reversed(&self) -> bool {
+        self.keep == config::DuplicateKeep::Last
+    }
 }
 
 #[derive(Error, Debug)]
@@ -85,7 +154,12 @@ impl TryFrom<config::DuplicateFilter> for DuplicateEntryFilter {
             return Err(ConfigurationError::CommandAndArgumentsBothSpecified);
         }
 
-        Ok(DuplicateEntryFilter { fields: config.match_on, hash_values: HashSet::new() })
+        Ok(DuplicateEntryFilter {
+            fields: config.match_on,
+            keep: config.keep,
+            normalize_arguments: config.normalize_arguments,
+            seen: HashSet::new(),
+        })
     }
 }
 
@@ -98,6 +172,7 @@ mod tests {
     fn test_try_from_success() {
         let config = config::DuplicateFilter {
             match_on: vec![config::OutputFields::File, config::OutputFields::Directory],
+            ..config::DuplicateFilter::default()
         };
 
         let result = DuplicateEntryFilter::try_from(config);
@@ -106,7 +181,7 @@ mod tests {
 
     #[test]
     fn test_try_from_failure_empty_fields() {
-        let config = config::DuplicateFilter { match_on: vec![] };
+        let config = config::DuplicateFilter { match_on: vec![], ..config::DuplicateFilter::default() };
 
         let result = DuplicateEntryFilter::try_from(config);
         assert!(matches!(result, Err(ConfigurationError::EmptyFieldList)));
@@ -116,6 +191,7 @@ mod tests {
     fn test_try_from_failure_duplicate_fields() {
         let config = config::DuplicateFilter {
             match_on: vec![config::OutputFields::File, config::OutputFields::File],
+            ..config::DuplicateFilter::default()
         };
 
         let result = DuplicateEntryFilter::try_from(config);
@@ -126,6 +202,7 @@ mod tests {
     fn test_try_from_failure_command_and_arguments() {
         let config = config::DuplicateFilter {
             match_on: vec![config::OutputFields::Command, config::OutputFields::Arguments],
+            ..config::DuplicateFilter::default()
         };
         let result = DuplicateEntryFilter::try_from(config);
         assert!(matches!(result, Err(ConfigurationError::CommandAndArgumentsBothSpecified)));
@@ -135,6 +212,7 @@ mod tests {
     fn test_accept_rejects_duplicates_by_file_and_directory() {
         let config = config::DuplicateFilter {
             match_on: vec![config::OutputFields::File, config::OutputFields::Directory],
+            ..config::DuplicateFilter::default()
         };
         let mut sut = DuplicateEntryFilter::try_from(config).unwrap();
 
@@ -157,7 +235,10 @@ mod tests {
 
     #[test]
     fn test_accept_distinguishes_by_output() {
-        let config = config::DuplicateFilter { match_on: vec![config::OutputFields::Output] };
+        let config = config::DuplicateFilter {
+            match_on: vec![config::OutputFields::Output],
+            ..config::DuplicateFilter::default()
+        };
         let mut sut = DuplicateEntryFilter::try_from(config).unwrap();
 
         let entry1 = Entry::from_arguments_str(
@@ -179,7 +260,10 @@ mod tests {
 
     #[test]
     fn test_accept_distinguishes_by_arguments() {
-        let config = config::DuplicateFilter { match_on: vec![config::OutputFields::Arguments] };
+        let config = config::DuplicateFilter {
+            match_on: vec![config::OutputFields::Arguments],
+            ..config::DuplicateFilter::default()
+        };
         let mut sut = DuplicateEntryFilter::try_from(config).unwrap();
 
         let entry1 = Entry::from_arguments_str(
@@ -198,4 +282,63 @@ mod tests {
         assert!(sut.accept(&entry1));
         assert!(sut.accept(&entry2));
     }
+
+    // Requirements: output-duplicate-detection
+    #[test]
+    fn test_accept_with_normalized_arguments() {
+        let config = config::DuplicateFilter {
+            match_on: vec![config::OutputFields::File, config::OutputFields::Arguments],
+            normalize_arguments: true,
+            ..config::DuplicateFilter::default()
+        };
+        let mut sut = DuplicateEntryFilter::try_from(config).unwrap();
+
+        let entry1 = Entry::from_arguments_str(
+            "source.c",
+            vec!["cc", "-c", "-DA", "-I", "inc", "-o", "first.o", "-MFfirst.d", "source.c"],
+            "/home/user/project",
+            None,
+        );
+        let entry2 = Entry::from_arguments_str(
+            "source.c",
+            vec!["cc", "-c", "-Iinc", "-D", "A", "-o", "second.o", "-MF", "second.d", "source.c"],
+            "/home/user/project",
+            None,
+        );
+        let entry3 = Entry::from_arguments_str(
+            "source.c",
+            vec!["cc", "-c", "-DB", "-Iinc", "-o", "third.o", "source.c"],
+            "/home/user/project",
+            None,
+        );
+
+        assert!(sut.accept(&entry1));
+        assert!(!sut.accept(&entry2));
+        assert!(sut.accept(&entry3));
+    }
+
+    // Requirements: output-duplicate-detection
+    #[test]
+    fn test_accept_with_normalized_command() {
+        let config = config::DuplicateFilter {
+            match_on: vec![config::OutputFields::Command],
+            normalize_arguments: true,
+            ..config::DuplicateFilter::default()
+        };
+        let mut sut = DuplicateEntryFilter::try_from(config).unwrap();
+
+        let entry1 = Entry::from_command_str("source.c", "cc -c -DA -DB -o a.o source.c", "/project", None);
+        let entry2 = Entry::from_command_str("source.c", "cc -c -DB -DA -o b.o source.c", "/project", None);
+
+        assert!(sut.accept(&entry1));
+        assert!(!sut.accept(&entry2));
+    }
+
+    #[test]
+    fn test_normalize_keeps_other_arguments_in_order() {
+        let arguments: Vec<String> =
+            ["cc", "-c", "-Wall", "-Ib", "-Ia", "-O2", "-MT", "target", "main.c"].map(String::from).to_vec();
+
+        assert_eq!(normalize(&arguments), vec!["cc", "-c", "-Wall", "-O2", "-MT", "main.c", "-Ia", "-Ib"]);
+    }
 }
@@ -0,0 +1,185 @@
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! Link command database output stage.
+//!
+//! The compilation database has no entries for the linker and archiver calls.
+//! This stage collects these calls into a separate JSON file, while the
+//! commands are passed through to the compilation database writer unchanged.
+//! See the [`crate::output::link`] module for the format of the entries.
+
+use super::IteratorWriter;
+use crate::config;
+use crate::output::link::{LinkCommandDatabase, LinkEntry};
+use crate::output::{SerializationError, SerializationFormat, WriterError};
+use crate::semantic::Command;
+use std::path::PathBuf;
+use std::{fs, io};
+
+/// A pipeline stage that writes the link command database (when enabled).
+pub(crate) struct LinkCommandsOutputWriter<T: IteratorWriter<Command>> {
+    writer: T,
+    path: Option<PathBuf>,
+}
+
+impl<T: IteratorWriter<Command>> LinkCommandsOutputWriter<T> {
+    pub(crate) fn new(writer: T, config: &config::LinkCommands) -> Self {
+        let path = config.enabled.then(|| config.path.clone());
+        Self { writer, path }
+    }
+}
+
+impl<T: IteratorWriter<Command>> IteratorWriter<Command> for LinkCommandsOutputWriter<T> {
+    fn write(self, commands: impl Iterator<Item = Command>) -> Result<(), WriterError> {
+        let Some(path) = self.path else {
+            return self.writer.write(commands);
+        };
+
+        let mut entries = vec![];
+        let inspected = commands.inspect(|cmd| entries.extend(LinkEntry::from_command(cmd)));
+        self.writer.write(inspected)?;
+
+        log::debug!("Writing {} link commands into {}", entries.len(), path.display());
+        let output = fs::File::create(&path)
+            .map(io::BufWriter::new)
+            .map_err(|err| WriterError::Io(path.clone(), SerializationError::Io(err)))?;
+        LinkCommandDatabase::write(output, entries.into_iter()).map_err(|err| WriterError::Io(path, err))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::CompilerType;
+    use crate::output::link::LinkKind;
+    use crate::semantic::{ArgumentKind, CompilerPass, PassEffect};
+    use std::sync::{Arc, Mutex};
+
+    /// A test writer that collects the commands passed through the stage.
+    struct CollectingCommandWriter {
+        collected: Arc<Mutex<Vec<Command>>>,
+    }
+
+    impl IteratorWriter<Command> for CollectingCommandWriter {
+        fn write(self, items: impl Iterator<Item = Command>) -> Result<(), WriterError> {
+            self.collected.lock().unwrap().extend(items);
+            Ok(())
+        }
+    }
+
+    fn commands() -> Vec<Command> {
+        let compile = Command::from_strings(
+            "/home/user",
+            "/usr/bin/gcc",
+            vec![
+                (ArgumentKind::Compiler, vec!["gcc"]),
+                (ArgumentKind::Other(PassEffect::StopsAt(CompilerPass::Compiling)), vec!["-c"]),
+                (ArgumentKind::Source { binary: false }, vec!["main.c"]),
+                (ArgumentKind::Output, vec!["-o", "main.o"]),
+            ],
+        )
+        .with_compiler(CompilerType::Gcc);
+        let archive = Command::from_strings(
+            "/home/user",
+            "/usr/bin/ar",
+            vec![
+                (ArgumentKind::Compiler, vec!["ar"]),
+                (ArgumentKind::Other(PassEffect::None), vec!["rcs"]),
+                (ArgumentKind::Output, vec!["", "libfoo.a"]),
+                (ArgumentKind::Source { binary: true }, vec!["foo.o"]),
+            ],
+        );
+        let link = Command::from_strings(
+            "/home/user",
+            "/usr/bin/gcc",
+            vec![
+                (ArgumentKind::Compiler, vec!["gcc"]),
+                (ArgumentKind::Source { binary: true }, vec!["main.o"]),
+                (ArgumentKind::Source { binary: true }, vec!["libfoo.a"]),
+                (ArgumentKind::Other(PassEffect::Configures(CompilerPass::Linking)), vec!["-L", "/opt/lib"]),
+                (ArgumentKind::Other(PassEffect::Configures(CompilerPass::Linking)), vec!["-lm"]),
+                (ArgumentKind::Output, vec!["-o", "app"]),
+            ],
+        )
+        .with_compiler(CompilerType::Gcc);
+        vec![compile, archive, link]
+    }
+
+    // Requirements: output-link-commands
+    #[test]
+    fn test_writes_link_and_archive_commands() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("link_commands.json");
+
+        let collected = Arc::new(Mutex::new(vec![]));
+        let writer = CollectingCommandWriter { collected: Arc::clone(&collected) };
+        let config = config::LinkCommands { enabled: true, path: path.clone() };
+        let sut = LinkCommandsOutputWriter::new(writer, &config);
+
+        sut.write(commands().into_iter()).unwrap();
+        assert_eq!(collected.lock().unwrap().len(), 3);
+
+        let content = fs::File::open(&path).unwrap();
+        let entries: Vec<LinkEntry> = LinkCommandDatabase::read(content).collect::<Result<_, _>>().unwrap();
+        assert_eq!(
+            entries,
+            vec![
+                LinkEntry {
+                    directory: PathBuf::from("/home/user"),
+                    executable: PathBuf::from("/usr/bin/ar"),
+                    kind: LinkKind::Archive,
+                    output: Some(PathBuf::from("libfoo.a")),
+                    inputs: vec![PathBuf::from("foo.o")],
+                    libraries: vec![],
+                    library_paths: vec![],
+                    arguments: vec!["ar".into(), "rcs".into(), "libfoo.a".into(), "foo.o".into()],
+                },
+                LinkEntry {
+                    directory: PathBuf::from("/home/user"),
+                    executable: PathBuf::from("/usr/bin/gcc"),
+                    kind: LinkKind::Link,
+                    output: Some(PathBuf::from("app")),
+                    inputs: vec![PathBuf::from("main.o"), PathBuf::from("libfoo.a")],
+                    libraries: vec!["m".into()],
+                    library_paths: vec![PathBuf::from("/opt/lib")],
+                    arguments: ["gcc", "main.o", "libfoo.a", "-L", "/opt/lib", "-lm", "-o", "app"]
+                        .map(String::from)
+                        .to_vec(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_disabled_writes_no_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("link_commands.json");
+
+        let collected = Arc::new(Mutex::new(vec![]));
+        let writer = CollectingCommandWriter { collected: Arc::clone(&collected) };
+        let config = config::LinkCommands { enabled: false, path: path.clone() };
+        let sut = LinkCommandsOutputWriter::new(writer, &config);
+
+        sut.write(commands().into_iter()).unwrap();
+        assert_eq!(collected.lock().unwrap().len(), 3);
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn test_link_entry_skips_commands_which_do_not_link() {
+        let commands = commands();
+        assert_eq!(LinkEntry::from_command(&commands[0]), None);
+
+        assert!(LinkEntry::from_command(&commands[2]).is_some_and(|entry| entry.kind == LinkKind::Link));
+
+        let info = Command::from_strings(
+            "/home/user",
+            "/usr/bin/gcc",
+            vec![
+                (ArgumentKind::Compiler, vec!["gcc"]),
+                (ArgumentKind::Other(PassEffect::InfoAndExit), vec!["--version"]),
+            ],
+        )
+        .with_compiler(CompilerType::Gcc);
+        assert_eq!(LinkEntry::from_command(&info), None);
+    }
+}
@@ -12,21 +12,32 @@
 
 mod append;
 mod atomic;
+mod compile_flags;
 mod converter;
+mod driver;
 mod file;
 mod filtering;
+mod link_commands;
+mod sorting;
 mod validating;
 
+use super::clang;
 use super::statistics::OutputStatistics;
 use super::{WriterCreationError, WriterError};
 use crate::{args, config, semantic};
 use std::sync::Arc;
+use std::sync::atomic::Ordering;
 
 use append::AppendClangOutputWriter;
+pub(crate) use append::read_compilation_db;
 use atomic::AtomicClangOutputWriter;
+use compile_flags::CompileFlagsOutputWriter;
 use converter::ConverterClangOutputWriter;
+use driver::DriverQueryOutputWriter;
 use file::ClangOutputWriter;
 use filtering::{DuplicateEntryFilter, FilteredOutputWriter, SourceEntryFilter};
+use link_commands::LinkCommandsOutputWriter;
+use sorting::SortingOutputWriter;
 use validating::ValidatingOutputWriter;
 
 /// A trait representing a writer for iterator type `T`.
@@ -42,14 +53,20 @@ pub(crate) trait IteratorWriter<T> {
 }
 
 /// The assembled writer pipeline type for Clang compilation databases.
-type ClangWriterStack = ConverterClangOutputWriter<
-    AppendClangOutputWriter<
-        AtomicClangOutputWriter<
-            FilteredOutputWriter<
-                FilteredOutputWriter<ValidatingOutputWriter<ClangOutputWriter>, DuplicateEntryFilter>,
-                SourceEntryFilter,
-            >,
+type ClangWriterStack = LinkCommandsOutputWriter<
+    DriverQueryOutputWriter<
+        CompileFlagsOutputWriter<ConverterClangOutputWriter<AppendClangOutputWriter<EntryWriterStack>>>,
+    >,
+>;
+
+/// The entry stages of the pipeline, which filter, validate and write the entries.
+type EntryWriterStack = AtomicClangOutputWriter<
+    FilteredOutputWriter<
+        FilteredOutputWriter<
+            ValidatingOutputWriter<SortingOutputWriter<ClangOutputWriter>>,
+            DuplicateEntryFilter,
         >,
+        SourceEntryFilter,
     >,
 >;
 
@@ -59,36 +76,158 @@ type ClangWriterStack = ConverterClangOutputWriter<
 /// to construct one.
 pub(crate) struct SemanticCommandWriter {
     inner: ClangWriterStack,
+    routes: Vec<RouteWriter>,
+}
+
+/// The pipeline of an output route (configured in the `outputs` section).
+struct RouteWriter {
+    name: String,
+    inner: ClangWriterStack,
+    stats: Arc<OutputStatistics>,
 }
 
+/// The number of commands which can be queued for an output route.
+const ROUTE_CHANNEL_SIZE: usize = 64;
+
 impl SemanticCommandWriter {
     /// Writes semantic commands through the pipeline.
+    ///
+    /// When output routes are configured, each command is sent to the route
+    /// pipelines too. These are running on their own threads, so the commands
+    /// are not buffered.
     pub(crate) fn write(self, semantics: impl Iterator<Item = semantic::Command>) -> Result<(), WriterError> {
-        self.inner.write(semantics)
+        if self.routes.is_empty() {
+            return self.inner.write(semantics);
+        }
+
+        std::thread::scope(|scope| {
+            let (senders, handles): (Vec<_>, Vec<_>) = self
+                .routes
+                .into_iter()
+                .map(|route| {
+                    let (sender, receiver) =
+                        crossbeam_channel::bounded::<semantic::Command>(ROUTE_CHANNEL_SIZE);
+                    let handle = scope.spawn(move || {
+                        let result = route.inner.write(receiver.into_iter());
+                        log::info!("Output {}: {}", route.name, route.stats);
+                        result
+                    });
+                    (sender, handle)
+                })
+                .unzip();
+
+            let fanned_out = semantics.inspect(|cmd| {
+                for sender in &senders {
+                    // A failed route closes its channel, the error is returned by its thread.
+                    let _ = sender.send(cmd.clone());
+                }
+            });
+            let result = self.inner.write(fanned_out);
+            drop(senders);
+
+            handles.into_iter().fold(result, |result, handle| {
+                let route_result = handle.join().unwrap_or_else(|panic| std::panic::resume_unwind(panic));
+                result.and(route_result)
+            })
+        })
     }
 }
 
 /// Assembles the full output writer pipeline from configuration.
 ///
 /// The pipeline processes semantic commands through the following stages:
-/// 1. Convert semantic commands to compilation database entries
-/// 2. Append entries from an existing database (if configured)
-/// 3. Atomic file write (via temp file + rename)
-/// 4. Source file path filtering
-/// 5. Duplicate entry filtering
-/// 6. Entry validation (drop invalid entries with a warning; earlier filters
+/// 1. Write the linker and archiver calls into the link command database (if configured)
+/// 2. Append implicit compiler driver settings (if configured)
+/// 3. Write `compile_flags.txt` files next to the sources (if configured)
+/// 4. Convert semantic commands to compilation database entries
+/// 5. Append entries from an existing database, or update them (if configured)
+/// 6. Atomic file write (via temp file + rename)
+/// 7. Source file path filtering
+/// 8. Duplicate entry filtering
+/// 9. Entry validation (drop invalid entries with a warning; earlier filters
 ///    never see an entry that will be dropped here)
-/// 7. Final file serialization
+/// 10. Sort entries (if configured)
+/// 11. Final file serialization
+///
+/// Each output route gets its own pipeline (with its own statistics), which is
+/// configured by the source rules and the overrides of the route.
 pub(crate) fn create_pipeline(
     args: &args::BuildSemantic,
     config: &config::Main,
     stats: Arc<OutputStatistics>,
 ) -> Result<SemanticCommandWriter, WriterCreationError> {
-    let final_path = &args.path;
-    let temp_path = &args.path.with_extension("tmp");
+    let inner = create_stack(&args.path, args.append, config, stats)?;
+    let routes = config
+        .outputs
+        .iter()
+        .map(|route| {
+            let stats = OutputStatistics::new();
+            let inner =
+                create_stack(&route.path, args.append, &route_config(config, route), Arc::clone(&stats))?;
+            let name = route.name.clone().unwrap_or_else(|| route.path.display().to_string());
+            Ok(RouteWriter { name, inner, stats })
+        })
+        .collect::<Result<Vec<_>, WriterCreationError>>()?;
+
+    Ok(SemanticCommandWriter { inner, routes })
+}
+
+/// Derives the configuration of an output route from the main configuration.
+///
+/// The source rules of the route are evaluated after the global ones, the
+/// duplicate filter and format sections replace the global ones. The
+/// `compile_flags.txt` files and the link command database are written by
+/// the main pipeline only.
+fn route_config(config: &config::Main, route: &config::OutputRoute) -> config::Main {
+    let mut result = config.clone();
+    result.sources.directories.extend(route.sources.directories.iter().cloned());
+    if let Some(duplicates) = &route.duplicates {
+        result.duplicates = duplicates.clone();
+    }
+    if let Some(format) = &route.format {
+        result.format = format.clone();
+    }
+    if let Some(paths) = &route.paths {
+        result.format.paths = paths.clone();
+    }
+    result.compile_flags = config::CompileFlags::default();
+    result.link_commands = config::LinkCommands::default();
+    result.outputs = vec![];
+    result
+}
+
+/// Assembles a single writer pipeline for the given output file.
+fn create_stack(
+    final_path: &std::path::Path,
+    append: bool,
+    config: &config::Main,
+    stats: Arc<OutputStatistics>,
+) -> Result<ClangWriterStack, WriterCreationError> {
+    let atomic_writer = create_entry_stack(final_path, config, Arc::clone(&stats))?;
+    let append_writer =
+        AppendClangOutputWriter::new(atomic_writer, final_path, append, &config.append, Arc::clone(&stats));
+    let formatted_writer = ConverterClangOutputWriter::new(append_writer, &config.format, Arc::clone(&stats));
+    let compile_flags_writer = CompileFlagsOutputWriter::new(formatted_writer, config);
+    let driver_writer = DriverQueryOutputWriter::new(compile_flags_writer, &config.format.query_driver);
+    let link_commands_writer = LinkCommandsOutputWriter::new(driver_writer, &config.link_commands);
 
-    let base_writer = ClangOutputWriter::create(temp_path, Arc::clone(&stats))?;
-    let validating_writer = ValidatingOutputWriter::new(base_writer, Arc::clone(&stats));
+    Ok(link_commands_writer)
+}
+
+/// Assembles the entry stages of the pipeline for the given output file.
+///
+/// Stages: atomic file write, source file path filtering, duplicate entry
+/// filtering, entry validation, sorting and the final file serialization.
+fn create_entry_stack(
+    final_path: &std::path::Path,
+    config: &config::Main,
+    stats: Arc<OutputStatistics>,
+) -> Result<EntryWriterStack, WriterCreationError> {
+    let temp_path = &final_path.with_extension("tmp");
+
+    let base_writer = ClangOutputWriter::create(temp_path, config.format.database, Arc::clone(&stats))?;
+    let sorting_writer = SortingOutputWriter::new(base_writer, config.format.entries.sort);
+    let validating_writer = ValidatingOutputWriter::new(sorting_writer, Arc::clone(&stats));
     let duplicate_filter = DuplicateEntryFilter::try_from(config.duplicates.clone())
         .map_err(|err| WriterCreationError::Configuration(err.to_string()))?;
     let unique_writer =
@@ -97,16 +236,97 @@ pub(crate) fn create_pipeline(
         });
     let source_filter_writer = FilteredOutputWriter::new(
         unique_writer,
-        SourceEntryFilter::from(config.sources.clone()),
+        SourceEntryFilter::new(config.sources.clone(), &config.format.paths),
         Arc::clone(&stats),
         |s| &s.entries_filtered_by_source,
     );
     let atomic_writer = AtomicClangOutputWriter::new(source_filter_writer, temp_path, final_path);
-    let append_writer =
-        AppendClangOutputWriter::new(atomic_writer, final_path, args.append, Arc::clone(&stats));
-    let formatted_writer = ConverterClangOutputWriter::new(append_writer, &config.format, Arc::clone(&stats));
 
-    Ok(SemanticCommandWriter { inner: formatted_writer })
+    Ok(atomic_writer)
+}
+
+/// An opaque writer that merges existing compilation databases into one.
+///
+/// Use [`create_merger`] to construct one.
+pub(crate) struct DatabaseMerger {
+    inputs: Vec<std::path::PathBuf>,
+    converter: clang::CommandConverter,
+    inner: EntryWriterStack,
+    stats: Arc<OutputStatistics>,
+}
+
+impl DatabaseMerger {
+    /// Reads the entries of the input databases (in the given order), formats
+    /// their paths, and writes them through the entry stages of the pipeline.
+    pub(crate) fn merge(self) -> Result<(), WriterError> {
+        let mut sources = Vec::with_capacity(self.inputs.len());
+        for input in &self.inputs {
+            let entries = read_compilation_db(input).map_err(|err| WriterError::Io(input.clone(), err))?;
+            sources.push(entries);
+        }
+
+        let stats = Arc::clone(&self.stats);
+        let converter = self.converter;
+        let entries = sources.into_iter().flatten().map(|entry| {
+            stats.entries_read_from_existing.fetch_add(1, Ordering::Relaxed);
+            converter.format_entry(entry)
+        });
+        self.inner.write(entries)
+    }
+}
+
+/// Assembles the writer for merging compilation databases.
+///
+/// The entries go through the same source filter, duplicate filter,
+/// validation, sorting and atomic write stages as the entries of a build.
+/// The configured path format is applied to the `directory`, `file` and
+/// `output` fields of the entries.
+pub(crate) fn create_merger(
+    inputs: &[std::path::PathBuf],
+    output: &args::BuildSemantic,
+    config: &config::Main,
+    stats: Arc<OutputStatistics>,
+) -> Result<DatabaseMerger, WriterCreationError> {
+    let inner = create_entry_stack(&output.path, config, Arc::clone(&stats))?;
+    let converter = clang::CommandConverter::new(config.format.clone());
+
+    Ok(DatabaseMerger { inputs: inputs.to_vec(), converter, inner, stats })
+}
+
+/// An opaque writer that converts an existing compilation database into another file format.
+///
+/// The input format is detected from the file content. Use [`create_converter`]
+/// to construct one.
+pub(crate) struct DatabaseConverter {
+    input: std::path::PathBuf,
+    inner: AtomicClangOutputWriter<ClangOutputWriter>,
+}
+
+impl DatabaseConverter {
+    /// Reads the entries of the input database and writes them in the output format.
+    pub(crate) fn convert(self) -> Result<(), WriterError> {
+        let entries = append::read_compilation_db(&self.input)
+            .map_err(|err| WriterError::Io(self.input.clone(), err))?;
+        self.inner.write(entries)
+    }
+}
+
+/// Assembles the writer for compilation database format conversion.
+///
+/// The entries are not transformed nor filtered, only serialized in the requested
+/// format. The output is written atomically (via temp file + rename).
+pub(crate) fn create_converter(
+    input: &args::BuildEvents,
+    output: &args::ConvertDatabase,
+    stats: Arc<OutputStatistics>,
+) -> Result<DatabaseConverter, WriterCreationError> {
+    let final_path = &output.path;
+    let temp_path = &output.path.with_extension("tmp");
+
+    let base_writer = ClangOutputWriter::create(temp_path, output.format, stats)?;
+    let atomic_writer = AtomicClangOutputWriter::new(base_writer, temp_path, final_path);
+
+    Ok(DatabaseConverter { input: input.path.clone(), inner: atomic_writer })
 }
 
 #[cfg(test)]
@@ -253,4 +473,170 @@ mod tests {
         assert_eq!(stats.semantic_commands_received.load(Ordering::Relaxed), 0);
         assert_eq!(stats.entries_written.load(Ordering::Relaxed), 0);
     }
+
+    // Requirements: output-routes
+    #[test]
+    fn test_create_pipeline_with_routes() {
+        let dir = tempfile::tempdir().unwrap();
+        let output_path = dir.path().join("compile_commands.json");
+        let src_path = dir.path().join("src.json");
+        let lib_path = dir.path().join("lib.json");
+        let route = |path: &std::path::Path, excluded: &str| config::OutputRoute {
+            path: path.to_path_buf(),
+            sources: config::SourceFilter {
+                directories: vec![config::DirectoryRule {
+                    path: std::path::PathBuf::from(excluded),
+                    action: config::DirectoryAction::Exclude,
+                }],
+            },
+            ..config::OutputRoute::default()
+        };
+        let config = config::Main {
+            outputs: vec![route(&src_path, "lib"), route(&lib_path, "src")],
+            ..config::Main::default()
+        };
+        let args = args::BuildSemantic { path: output_path.clone(), append: false };
+        let stats = OutputStatistics::new();
+
+        let pipeline = create_pipeline(&args, &config, Arc::clone(&stats)).unwrap();
+
+        let commands = vec![
+            make_compile_command("src/main.c"),
+            make_compile_command("lib/util.c"),
+            make_compile_command("src/main.c"),
+        ];
+        pipeline.write(commands.into_iter()).unwrap();
+
+        assert_eq!(stats.entries_written.load(Ordering::Relaxed), 2);
+        assert_eq!(stats.duplicates_detected.load(Ordering::Relaxed), 1);
+
+        let content = std::fs::read_to_string(&src_path).unwrap();
+        assert!(content.contains("src/main.c"));
+        assert!(!content.contains("lib/util.c"));
+
+        let content = std::fs::read_to_string(&lib_path).unwrap();
+        assert!(!content.contains("src/main.c"));
+        assert!(content.contains("lib/util.c"));
+    }
+
+    // Requirements: output-profiles
+    #[test]
+    fn test_route_config_overrides() {
+        let config = config::Main {
+            sources: config::SourceFilter {
+                directories: vec![config::DirectoryRule {
+                    path: std::path::PathBuf::from("/usr"),
+                    action: config::DirectoryAction::Exclude,
+                }],
+            },
+            compile_flags: config::CompileFlags { enabled: true },
+            ..config::Main::default()
+        };
+        let route = config::OutputRoute {
+            path: std::path::PathBuf::from("legacy.json"),
+            sources: config::SourceFilter {
+                directories: vec![config::DirectoryRule {
+                    path: std::path::PathBuf::from("/usr/src"),
+                    action: config::DirectoryAction::Include,
+                }],
+            },
+            duplicates: Some(config::DuplicateFilter {
+                match_on: vec![config::OutputFields::File],
+                ..config::DuplicateFilter::default()
+            }),
+            format: Some(config::Format {
+                entries: config::EntryFormat { use_array_format: false, ..config::EntryFormat::default() },
+                ..config::Format::default()
+            }),
+            paths: Some(config::PathFormat {
+                directory: config::PathResolver::Absolute,
+                file: config::PathResolver::Absolute,
+                ..config::PathFormat::default()
+            }),
+            ..config::OutputRoute::default()
+        };
+        let main = config::Main { outputs: vec![route.clone()], ..config };
+
+        let result = route_config(&main, &route);
+
+        assert_eq!(result.sources.directories.len(), 2);
+        assert_eq!(result.sources.directories[1], route.sources.directories[0]);
+        assert_eq!(result.duplicates, route.duplicates.unwrap());
+        assert!(!result.format.entries.use_array_format);
+        assert_eq!(result.format.paths, route.paths.unwrap());
+        assert!(!result.compile_flags.enabled);
+        assert!(!result.link_commands.enabled);
+        assert!(result.outputs.is_empty());
+    }
+
+    // Requirements: output-json-lines
+    #[test]
+    fn test_create_converter_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let array_path = dir.path().join("compile_commands.json");
+        let lines_path = dir.path().join("compile_commands.jsonl");
+        let back_path = dir.path().join("converted.json");
+        let config = config::Main::default();
+        let args = args::BuildSemantic { path: array_path.clone(), append: false };
+
+        let pipeline = create_pipeline(&args, &config, OutputStatistics::new()).unwrap();
+        pipeline
+            .write(vec![make_compile_command("file1.c"), make_compile_command("file2.c")].into_iter())
+            .unwrap();
+
+        let input = args::BuildEvents { path: array_path.clone() };
+        let output =
+            args::ConvertDatabase { path: lines_path.clone(), format: config::DatabaseFormat::JsonLines };
+        let stats = OutputStatistics::new();
+        create_converter(&input, &output, Arc::clone(&stats)).unwrap().convert().unwrap();
+
+        let content = std::fs::read_to_string(&lines_path).unwrap();
+        assert_eq!(content.lines().count(), 2);
+        assert!(!content.starts_with('['));
+        assert_eq!(stats.entries_written.load(Ordering::Relaxed), 2);
+
+        let input = args::BuildEvents { path: lines_path };
+        let output = args::ConvertDatabase { path: back_path.clone(), format: config::DatabaseFormat::Json };
+        create_converter(&input, &output, OutputStatistics::new()).unwrap().convert().unwrap();
+
+        let original: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(&array_path).unwrap()).unwrap();
+        let converted: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(&back_path).unwrap()).unwrap();
+        assert_eq!(original, converted);
+    }
+
+    // Requirements: output-merge
+    #[test]
+    fn test_create_merger_filters_and_deduplicates() {
+        let dir = tempfile::tempdir().unwrap();
+        let first = dir.path().join("first.json");
+        let second = dir.path().join("second.json");
+        let merged = dir.path().join("merged.json");
+        let config = config::Main::default();
+
+        let args = args::BuildSemantic { path: first.clone(), append: false };
+        create_pipeline(&args, &config, OutputStatistics::new())
+            .unwrap()
+            .write(vec![make_compile_command("file1.c"), make_compile_command("file2.c")].into_iter())
+            .unwrap();
+        let args = args::BuildSemantic { path: second.clone(), append: false };
+        create_pipeline(&args, &config, OutputStatistics::new())
+            .unwrap()
+            .write(vec![make_compile_command("file2.c"), make_compile_command("file3.c")].into_iter())
+            .unwrap();
+
+        let output = args::BuildSemantic { path: merged.clone(), append: false };
+        let stats = OutputStatistics::new();
+        create_merger(&[first, second], &output, &config, Arc::clone(&stats)).unwrap().merge().unwrap();
+
+        let content: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(&merged).unwrap()).unwrap();
+        let files: Vec<&str> =
+            content.as_array().unwrap().iter().map(|e| e["file"].as_str().unwrap()).collect();
+        assert_eq!(files, vec!["file1.c", "file2.c", "file3.c"]);
+        assert_eq!(stats.entries_read_from_existing.load(Ordering::Relaxed), 4);
+        assert_eq!(stats.duplicates_detected.load(Ordering::Relaxed), 1);
+        assert_eq!(stats.entries_written.load(Ordering::Relaxed), 3);
+    }
 }
@@ -0,0 +1,141 @@
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use super::IteratorWriter;
+use crate::config;
+use crate::output::WriterError;
+use crate::output::clang::Entry;
+use std::cmp::Ordering;
+
+/// Sorts the entries before writing them, when it's configured.
+///
+/// The order of the entries otherwise follows the order of the build events,
+/// which varies between parallel builds. Sorting makes the output reproducible.
+///
+/// The configured key is compared first, then all the other fields, so
+/// the order does not depend on the arrival order even for entries with
+/// equal keys. The entries are buffered in memory for sorting.
+pub(crate) struct SortingOutputWriter<T: IteratorWriter<Entry>> {
+    writer: T,
+    sort: Option<config::EntrySort>,
+}
+
+impl<T: IteratorWriter<Entry>> SortingOutputWriter<T> {
+    pub(crate) fn new(writer: T, sort: Option<config::EntrySort>) -> Self {
+        Self { writer, sort }
+    }
+
+    fn compare(sort: config::EntrySort, lhs: &Entry, rhs: &Entry) -> Ordering {
+        let by_key = match sort {
+            config::EntrySort::File => lhs.file.cmp(&rhs.file),
+            config::EntrySort::DirectoryFile => {
+                lhs.directory.cmp(&rhs.directory).then_with(|| lhs.file.cmp(&rhs.file))
+            }
+            config::EntrySort::Output => lhs.output.cmp(&rhs.output),
+        };
+        by_key
+            .then_with(|| lhs.directory.cmp(&rhs.directory))
+            .then_with(|| lhs.file.cmp(&rhs.file))
+            .then_with(|| lhs.output.cmp(&rhs.output))
+            .then_with(|| lhs.arguments.cmp(&rhs.arguments))
+            .then_with(|| lhs.command.cmp(&rhs.command))
+    }
+}
+
+impl<T: IteratorWriter<Entry>> IteratorWriter<Entry> for SortingOutputWriter<T> {
+    fn write(self, entries: impl Iterator<Item = Entry>) -> Result<(), WriterError> {
+        match self.sort {
+            Some(sort) => {
+                let mut entries: Vec<Entry> = entries.collect();
+                entries.sort_by(|lhs, rhs| Self::compare(sort, lhs, rhs));
+                self.writer.write(entries.into_iter())
+            }
+            None => self.writer.write(entries),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::output::writers::fixtures::CollectingWriter;
+
+    fn entries() -> Vec<Entry> {
+        vec![
+            Entry::from_arguments_str("b.c", vec!["gcc", "-c", "-O2"], "/project/lib", Some("b.o")),
+            Entry::from_arguments_str("a.c", vec!["gcc", "-c"], "/project/src", Some("z.o")),
+            Entry::from_arguments_str("b.c", vec!["gcc", "-c", "-O0"], "/project/lib", Some("a.o")),
+            Entry::from_arguments_str("c.c", vec!["gcc", "-c"], "/project/app", None),
+        ]
+    }
+
+    fn sorted(sort: Option<config::EntrySort>, entries: Vec<Entry>) -> Vec<Entry> {
+        let (writer, collected) = CollectingWriter::new();
+        let sut = SortingOutputWriter::new(writer, sort);
+        sut.write(entries.into_iter()).unwrap();
+
+        std::mem::take(&mut *collected.lock().unwrap())
+    }
+
+    fn files(entries: &[Entry]) -> Vec<(&str, &str)> {
+        entries
+            .iter()
+            .map(|entry| (entry.directory.to_str().unwrap(), entry.file.to_str().unwrap()))
+            .collect()
+    }
+
+    #[test]
+    fn test_without_sort_keeps_order() {
+        let result = sorted(None, entries());
+        assert_eq!(result, entries());
+    }
+
+    // Requirements: output-sorted-entries
+    #[test]
+    fn test_sort_by_file() {
+        let result = sorted(Some(config::EntrySort::File), entries());
+        assert_eq!(
+            files(&result),
+            vec![
+                ("/project/src", "a.c"),
+                ("/project/lib", "b.c"),
+                ("/project/lib", "b.c"),
+                ("/project/app", "c.c")
+            ]
+        );
+        // Equal keys are ordered by the other fields.
+        assert_eq!(result[1].output, Some("a.o".into()));
+    }
+
+    // Requirements: output-sorted-entries
+    #[test]
+    fn test_sort_by_directory_and_file() {
+        let result = sorted(Some(config::EntrySort::DirectoryFile), entries());
+        assert_eq!(
+            files(&result),
+            vec![
+                ("/project/app", "c.c"),
+                ("/project/lib", "b.c"),
+                ("/project/lib", "b.c"),
+                ("/project/src", "a.c")
+            ]
+        );
+    }
+
+    // Requirements: output-sorted-entries
+    #[test]
+    fn test_sort_by_output() {
+        let result = sorted(Some(config::EntrySort::Output), entries());
+        let outputs: Vec<_> = result.iter().map(|entry| entry.output.clone()).collect();
+        assert_eq!(outputs, vec![None, Some("a.o".into()), Some("b.o".into()), Some("z.o".into())]);
+    }
+
+    // Requirements: output-sorted-entries
+    #[test]
+    fn test_sort_does_not_depend_on_arrival_order() {
+        let mut reversed = entries();
+        reversed.reverse();
+
+        let sort = Some(config::EntrySort::File);
+        assert_eq!(sorted(sort, entries()), sorted(sort, reversed));
+    }
+}
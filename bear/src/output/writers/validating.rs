@@ -12,7 +12,7 @@ use std::sync::atomic::Ordering;
 ///
 /// Placed last in the pipeline so earlier filter stages (duplicate, source)
 /// never see an entry that will be dropped. Downstream writers
-/// (`ClangOutputWriter`) may therefore assume every entry they receive is
+/// (`SortingOutputWriter`, `ClangOutputWriter`) may therefore assume every entry they receive is
 /// valid per `Entry::validate`.
 pub(crate) struct ValidatingOutputWriter<T: IteratorWriter<Entry>> {
     writer: T,
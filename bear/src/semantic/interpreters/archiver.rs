@@ -0,0 +1,158 @@
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! Recognizes the static library archiver (`ar`) invocations.
+//!
+//! The archiver calls are not compilations, these are not written into the
+//! compilation database. But they tell which object files end up in which
+//! static library, so these are recognized for the link command database.
+//!
+//! Only the calls which create or update an archive (the `r` and `q`
+//! operations) are recognized. The archive is classified as the output, and
+//! the members as binary sources of the command.
+
+use crate::semantic::{
+    Argument, ArgumentKind, ClangCompatibility, Command, Execution, FlagValue, Interpreter, PassEffect,
+    RecognizeResult,
+};
+
+/// Options which take a separate value (before the operation).
+const OPTIONS_WITH_VALUE: [&str; 2] = ["--plugin", "--target"];
+
+/// A tool to recognize the `ar` (and compatible) archiver calls.
+pub(super) struct ArchiverInterpreter;
+
+impl ArchiverInterpreter {
+    /// Checks if the executable filename is an archiver.
+    ///
+    /// Matches `ar`, `gcc-ar`, `llvm-ar` (with an optional version suffix)
+    /// and the cross toolchain variants (like `aarch64-linux-gnu-ar`).
+    fn is_archiver(execution: &Execution) -> bool {
+        let Some(name) = execution.executable.file_stem().and_then(|stem| stem.to_str()) else {
+            return false;
+        };
+        let name = name.trim_end_matches(|c: char| c.is_ascii_digit() || c == '.' || c == '-');
+        name == "ar" || name.ends_with("-ar")
+    }
+
+    /// Parses the arguments of an archive creating call.
+    ///
+    /// Returns `None` when the operation does not create or update an archive.
+    fn parse(arguments: &[String]) -> Option<Vec<Argument>> {
+        let (program, rest) = arguments.split_first()?;
+        let mut result = vec![other(vec![program.clone()], ArgumentKind::Compiler)];
+
+        let mut iter = rest.iter();
+        // Options may precede the operation.
+        let operation = loop {
+            let arg = iter.next()?;
+            if OPTIONS_WITH_VALUE.contains(&arg.as_str()) {
+                let value = iter.next()?;
+                result.push(other(vec![arg.clone(), value.clone()], ArgumentKind::Other(PassEffect::None)));
+            } else if arg.starts_with("--") {
+                result.push(other(vec![arg.clone()], ArgumentKind::Other(PassEffect::None)));
+            } else {
+                break arg;
+            }
+        };
+        let letters = operation.trim_start_matches('-');
+        if !letters.contains(['r', 'q']) {
+            return None;
+        }
+        result.push(other(vec![operation.clone()], ArgumentKind::Other(PassEffect::None)));
+
+        // The position and count modifiers take a positional value before the archive.
+        let positional_values =
+            letters.matches(['a', 'b', 'i']).count().min(1) + letters.matches('N').count();
+        for _ in 0..positional_values {
+            result.push(other(vec![iter.next()?.clone()], ArgumentKind::Other(PassEffect::None)));
+        }
+
+        let archive = iter.next()?;
+        result.push(Argument::Output { flag: String::new(), path: archive.clone() });
+        result.extend(iter.map(|member| Argument::Source { path: member.clone(), binary: true }));
+        Some(result)
+    }
+}
+
+impl Interpreter for ArchiverInterpreter {
+    fn recognize(&self, execution: Execution) -> RecognizeResult {
+        if !Self::is_archiver(&execution) {
+            return RecognizeResult::NotRecognized(execution);
+        }
+        match Self::parse(&execution.arguments) {
+            Some(arguments) => RecognizeResult::Recognized(Command::new(
+                execution.working_dir,
+                execution.executable,
+                arguments,
+            )),
+            None => RecognizeResult::Ignored("archiver call without archive update"),
+        }
+    }
+}
+
+fn other(arguments: Vec<String>, kind: ArgumentKind) -> Argument {
+    Argument::Other { arguments, kind, clang: ClangCompatibility::Supported, value: FlagValue::Plain }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn execution(executable: &str, arguments: Vec<&str>) -> Execution {
+        Execution::from_strings(executable, arguments, "/home/user", HashMap::new())
+    }
+
+    fn recognize(executable: &str, arguments: Vec<&str>) -> Option<Command> {
+        match ArchiverInterpreter.recognize(execution(executable, arguments)) {
+            RecognizeResult::Recognized(command) => Some(command),
+            _ => None,
+        }
+    }
+
+    // Requirements: output-link-commands
+    #[test]
+    fn test_recognize_archive_creation() {
+        let command = recognize("/usr/bin/ar", vec!["ar", "rcs", "libfoo.a", "a.o", "b.o"]).unwrap();
+
+        assert_eq!(command.compiler, None);
+        assert_eq!(command.arguments.len(), 5);
+        assert_eq!(command.arguments[0].kind(), ArgumentKind::Compiler);
+        assert_eq!(command.arguments[2], Argument::Output { flag: String::new(), path: "libfoo.a".into() });
+        assert_eq!(command.arguments[3], Argument::Source { path: "a.o".into(), binary: true });
+        assert_eq!(command.arguments[4], Argument::Source { path: "b.o".into(), binary: true });
+    }
+
+    #[test]
+    fn test_recognize_archive_with_options_and_modifiers() {
+        let command = recognize(
+            "/usr/bin/llvm-ar-17",
+            vec!["llvm-ar-17", "--format=gnu", "--plugin", "lto.so", "-rb", "x.o", "libfoo.a", "a.o"],
+        )
+        .unwrap();
+
+        let outputs: Vec<_> =
+            command.arguments.iter().filter(|arg| arg.kind() == ArgumentKind::Output).collect();
+        assert_eq!(outputs, vec![&Argument::Output { flag: String::new(), path: "libfoo.a".into() }]);
+        assert_eq!(command.arguments.last(), Some(&Argument::Source { path: "a.o".into(), binary: true }));
+    }
+
+    #[test]
+    fn test_recognize_archiver_names() {
+        for executable in ["ar", "/usr/bin/gcc-ar", "aarch64-linux-gnu-ar", "llvm-ar", "ar.exe"] {
+            assert!(recognize(executable, vec![executable, "qc", "lib.a", "a.o"]).is_some(), "{executable}");
+        }
+        for executable in ["/usr/bin/gcc", "/usr/bin/tar", "/usr/bin/jar"] {
+            assert!(recognize(executable, vec![executable, "rc", "lib.a", "a.o"]).is_none(), "{executable}");
+        }
+    }
+
+    #[test]
+    fn test_ignore_archive_inspection() {
+        let result = ArchiverInterpreter.recognize(execution("/usr/bin/ar", vec!["ar", "t", "libfoo.a"]));
+        assert!(matches!(result, RecognizeResult::Ignored(_)));
+
+        let result = ArchiverInterpreter.recognize(execution("/usr/bin/ar", vec!["ar", "x", "libfoo.a"]));
+        assert!(matches!(result, RecognizeResult::Ignored(_)));
+    }
+}
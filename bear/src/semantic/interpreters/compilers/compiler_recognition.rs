@@ -64,7 +64,7 @@ fn create_compiler_regex(executables: &[&str], cross_compilation: bool, versione
     let alternation = escaped.join("|");
 
     let base = if cross_compilation {
-        format!(r"(?:[^/]*-)?(?:{})", alternation)
+        format!(r"(?:(?P<prefix>[^/]*)-)?(?:{})", alternation)
     } else {
         format!(r"(?:{})", alternation)
     };
@@ -224,6 +224,30 @@ impl CompilerRecognizer {
         None
     }
 
+    /// Returns the target triple encoded in a cross-compiler executable name.
+    ///
+    /// Cross toolchains are installed with the target triple as a prefix
+    /// (e.g. `aarch64-linux-gnu-gcc`, `arm-none-eabi-g++-12`). A prefix is
+    /// accepted as a triple only when it has at least two components, so
+    /// names like `my-gcc` do not produce a bogus target.
+    ///
+    /// # Returns
+    ///
+    /// `Some(triple)` if the filename carries a cross-compilation prefix, `None` otherwise
+    pub fn recognize_target(&self, executable_path: &Path) -> Option<String> {
+        let filename = executable_path.file_name()?.to_str()?;
+        let filename = filename.strip_suffix(".exe").unwrap_or(filename);
+
+        let captures = self.patterns.iter().find_map(|(_, pattern)| pattern.captures(filename))?;
+        let prefix = captures.name("prefix")?.as_str();
+
+        let components: Vec<&str> = prefix.split('-').collect();
+        if components.len() < 2 || components.iter().any(|component| component.is_empty()) {
+            return None;
+        }
+        Some(prefix.to_string())
+    }
+
     /// Internal regex-based recognition
     ///
     /// This function ignores the directory path and only looks at the filename
@@ -284,6 +308,36 @@ mod tests {
         assert_eq!(recognizer.recognize(path("/opt/gcc/bin/g++")), Some(CompilerType::Gcc));
     }
 
+    #[test]
+    fn test_target_recognition() {
+        let recognizer = CompilerRecognizer::new();
+
+        assert_eq!(
+            recognizer.recognize_target(path("aarch64-linux-gnu-gcc")),
+            Some("aarch64-linux-gnu".to_string())
+        );
+        assert_eq!(
+            recognizer.recognize_target(path("/opt/arm/bin/arm-none-eabi-g++-12")),
+            Some("arm-none-eabi".to_string())
+        );
+        assert_eq!(
+            recognizer.recognize_target(path("x86_64-w64-mingw32-gcc.exe")),
+            Some("x86_64-w64-mingw32".to_string())
+        );
+        assert_eq!(
+            recognizer.recognize_target(path("riscv64-unknown-elf-clang")),
+            Some("riscv64-unknown-elf".to_string())
+        );
+
+        // Not cross compilers, or prefixes that do not look like a triple
+        assert_eq!(recognizer.recognize_target(path("gcc")), None);
+        assert_eq!(recognizer.recognize_target(path("gcc-12")), None);
+        assert_eq!(recognizer.recognize_target(path("clang++-16")), None);
+        assert_eq!(recognizer.recognize_target(path("avr-gcc")), None);
+        assert_eq!(recognizer.recognize_target(path("ccache")), None);
+        assert_eq!(recognizer.recognize_target(path("unknown-tool")), None);
+    }
+
     #[test]
     fn test_clang_recognition() {
         let recognizer = CompilerRecognizer::new();
@@ -11,7 +11,8 @@ use super::super::matchers::{
     EnvMapping, EnvPosition, EnvRule, EnvSeparator, FlagAnalyzer, FlagPattern, FlagRule,
 };
 use crate::semantic::{
-    Argument, ArgumentKind, Command, CompilerPass, Execution, Interpreter, PassEffect, RecognizeResult,
+    Argument, ArgumentKind, ClangCompatibility, Command, CompilerPass, Execution, FlagValue, Interpreter,
+    PassEffect, RecognizeResult,
 };
 
 /// A generic compiler interpreter parameterized by a flag table and ignore filters.
@@ -98,6 +99,8 @@ fn parse_arguments_owned(
             result.push(Argument::Other {
                 arguments: vec![std::mem::take(&mut args[0])],
                 kind: ArgumentKind::Compiler,
+                clang: ClangCompatibility::Supported,
+                value: FlagValue::Plain,
             });
             i += 1;
             continue;
@@ -112,12 +115,16 @@ fn parse_arguments_owned(
                 result.push(Argument::Other {
                     arguments: vec![std::mem::take(&mut args[i])],
                     kind: ArgumentKind::Other(PassEffect::PassThrough),
+                    clang: ClangCompatibility::Supported,
+                    value: FlagValue::Plain,
                 });
                 i += 1;
                 while i < args.len() {
                     result.push(Argument::Other {
                         arguments: vec![std::mem::take(&mut args[i])],
                         kind: ArgumentKind::Other(PassEffect::Configures(CompilerPass::Linking)),
+                        clang: ClangCompatibility::Supported,
+                        value: FlagValue::Plain,
                     });
                     i += 1;
                 }
@@ -129,6 +136,8 @@ fn parse_arguments_owned(
                 ArgumentKind::Compiler => Argument::Other {
                     arguments: vec![std::mem::take(&mut args[i])],
                     kind: ArgumentKind::Compiler,
+                    clang: ClangCompatibility::Supported,
+                    value: FlagValue::Plain,
                 },
                 ArgumentKind::Source { .. } => {
                     unreachable!("Source files should be detected by heuristic, not flag matching")
@@ -157,7 +166,12 @@ fn parse_arguments_owned(
                 ArgumentKind::Other(compiler_pass) => {
                     let moved: Vec<String> =
                         (i..i + consumed_count).map(|j| std::mem::take(&mut args[j])).collect();
-                    Argument::Other { arguments: moved, kind: ArgumentKind::Other(compiler_pass) }
+                    Argument::Other {
+                        arguments: moved,
+                        kind: ArgumentKind::Other(compiler_pass),
+                        clang: match_result.rule.clang,
+                        value: match_result.rule.value(),
+                    }
                 }
             };
 
@@ -167,6 +181,8 @@ fn parse_arguments_owned(
             result.push(Argument::Other {
                 arguments: vec![std::mem::take(&mut args[i])],
                 kind: ArgumentKind::Other(PassEffect::None),
+                clang: ClangCompatibility::Supported,
+                value: FlagValue::Plain,
             });
             i += 1;
         } else {
@@ -198,9 +214,12 @@ fn parse_environment(
                 let parts = split_env_value(value, separator);
                 for part in parts {
                     if !part.is_empty() {
+                        // The variables with flag mappings are all search path lists.
                         append.push(Argument::Other {
                             arguments: vec![flag.to_string(), part],
                             kind: rule.kind,
+                            clang: ClangCompatibility::Supported,
+                            value: FlagValue::Path { flag, glue: None },
                         });
                     }
                 }
@@ -212,7 +231,12 @@ fn parse_environment(
                     EnvPosition::Append => &mut append,
                 };
                 for word in words {
-                    target.push(Argument::Other { arguments: vec![word], kind: rule.kind });
+                    target.push(Argument::Other {
+                        arguments: vec![word],
+                        kind: rule.kind,
+                        clang: ClangCompatibility::Supported,
+                        value: FlagValue::Plain,
+                    });
                 }
             }
         }
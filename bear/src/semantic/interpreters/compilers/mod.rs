@@ -108,8 +108,14 @@ impl Interpreter for CompilerInterpreter {
             };
         }
 
+        let target = self.recognizer.recognize_target(&execution.executable);
         match self.interpreters.get(&compiler_type) {
-            Some(interpreter) => interpreter.recognize(execution),
+            Some(interpreter) => match interpreter.recognize(execution) {
+                RecognizeResult::Recognized(command) => {
+                    RecognizeResult::Recognized(command.with_compiler(compiler_type).with_target(target))
+                }
+                other => other,
+            },
             None => RecognizeResult::NotRecognized(execution),
         }
     }
@@ -124,7 +130,7 @@ impl Interpreter for Arc<CompilerInterpreter> {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::semantic::{ArgumentKind, CompilerPass, PassEffect};
+    use crate::semantic::{Argument, ArgumentKind, ClangCompatibility, CompilerPass, FlagValue, PassEffect};
     use std::borrow::Cow;
     use std::collections::HashMap;
     use std::path::{Path, PathBuf};
@@ -223,6 +229,27 @@ mod tests {
             }
         }
 
+        #[test]
+        fn cross_compiler_prefix_is_captured_as_target() {
+            let sut = CompilerInterpreter::new_with_config(&[]);
+            let execution = create_execution(
+                "/usr/bin/aarch64-linux-gnu-gcc",
+                vec!["aarch64-linux-gnu-gcc", "-c", "test.c"],
+                "/tmp",
+            );
+            let RecognizeResult::Recognized(cmd) = sut.recognize(execution) else {
+                panic!("Expected compiler command");
+            };
+            assert_eq!(cmd.target, Some("aarch64-linux-gnu".to_string()));
+            assert_eq!(cmd.compiler, Some(CompilerType::Gcc));
+
+            let execution = create_execution("/usr/bin/gcc", vec!["gcc", "-c", "test.c"], "/tmp");
+            let RecognizeResult::Recognized(cmd) = sut.recognize(execution) else {
+                panic!("Expected compiler command");
+            };
+            assert_eq!(cmd.target, None);
+        }
+
         #[test]
         fn unrecognized_compiler() {
             let sut = CompilerInterpreter::new_with_config(&[]);
@@ -340,6 +367,70 @@ mod tests {
             );
         }
 
+        #[test]
+        fn gcc_only_flags_are_annotated_for_clang() {
+            let sut = CompilerInterpreter::new_with_config(&[]);
+            let execution = create_execution(
+                "gcc",
+                vec!["gcc", "-c", "-fconserve-stack", "-Wno-maybe-uninitialized", "-fPIC", "main.c"],
+                "/project",
+            );
+            let RecognizeResult::Recognized(cmd) = sut.recognize(execution) else {
+                panic!("Expected compiler command");
+            };
+            let annotations: Vec<ClangCompatibility> = cmd
+                .arguments
+                .iter()
+                .filter_map(|arg| match arg {
+                    Argument::Other { kind: Other(_), clang, .. } => Some(*clang),
+                    _ => None,
+                })
+                .collect();
+            assert_eq!(
+                annotations,
+                vec![
+                    ClangCompatibility::Supported,
+                    ClangCompatibility::Unsupported,
+                    ClangCompatibility::Equivalent {
+                        flag: "-Wno-maybe-uninitialized",
+                        equivalent: "-Wno-uninitialized"
+                    },
+                    ClangCompatibility::Supported,
+                ]
+            );
+        }
+
+        #[test]
+        fn path_flags_are_annotated() {
+            let sut = CompilerInterpreter::new_with_config(&[]);
+            let execution = create_execution(
+                "gcc",
+                vec!["gcc", "-c", "-Iinclude", "-isystem", "sys", "--sysroot=/opt/sysroot", "-O2", "main.c"],
+                "/project",
+            );
+            let RecognizeResult::Recognized(cmd) = sut.recognize(execution) else {
+                panic!("Expected compiler command");
+            };
+            let values: Vec<FlagValue> = cmd
+                .arguments
+                .iter()
+                .filter_map(|arg| match arg {
+                    Argument::Other { kind: Other(_), value, .. } => Some(*value),
+                    _ => None,
+                })
+                .collect();
+            assert_eq!(
+                values,
+                vec![
+                    FlagValue::Plain,
+                    FlagValue::Path { flag: "-I", glue: None },
+                    FlagValue::Path { flag: "-isystem", glue: None },
+                    FlagValue::Path { flag: "--sysroot", glue: Some('=') },
+                    FlagValue::Plain,
+                ]
+            );
+        }
+
         #[test]
         fn combined_flags() {
             let sut = CompilerInterpreter::new_with_config(&[]);
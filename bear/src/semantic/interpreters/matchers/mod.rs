@@ -11,7 +11,7 @@
 
 pub(super) mod source;
 
-use crate::semantic::ArgumentKind;
+use crate::semantic::{ArgumentKind, ClangCompatibility, FlagValue};
 
 pub use source::looks_like_a_source_file;
 
@@ -68,6 +68,12 @@ pub(super) struct FlagRule {
 
     /// What this flag represents semantically
     pub kind: ArgumentKind,
+
+    /// How Clang understands this flag
+    pub clang: ClangCompatibility,
+
+    /// Whether the value of this flag is a file system path
+    pub path_value: bool,
 }
 
 /// Result of matching a flag against command line arguments.
@@ -221,7 +227,31 @@ impl FlagAnalyzer {
 impl FlagRule {
     /// Create a new flag definition
     pub const fn new(pattern: FlagPattern, kind: ArgumentKind) -> Self {
-        Self { pattern, kind }
+        Self { pattern, kind, clang: ClangCompatibility::Supported, path_value: false }
+    }
+
+    /// Create a new flag definition which Clang does not understand as it is
+    pub const fn with_clang(pattern: FlagPattern, kind: ArgumentKind, clang: ClangCompatibility) -> Self {
+        Self { pattern, kind, clang, path_value: false }
+    }
+
+    /// Marks the value of the flag as a file system path
+    pub const fn with_path_value(mut self) -> Self {
+        self.path_value = true;
+        self
+    }
+
+    /// Describes the value of the matched flag
+    pub fn value(&self) -> FlagValue {
+        if !self.path_value {
+            return FlagValue::Plain;
+        }
+        let glue = match self.pattern {
+            FlagPattern::ExactlyWithEq(_) | FlagPattern::ExactlyWithEqOrSep(_) => Some('='),
+            FlagPattern::ExactlyWithColon(_) | FlagPattern::ExactlyWithColonOrSep(_) => Some(':'),
+            _ => None,
+        };
+        FlagValue::Path { flag: self.pattern.flag(), glue }
     }
 }
 
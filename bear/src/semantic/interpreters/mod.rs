@@ -4,6 +4,7 @@
 //! recognize compiler calls. Based on the configuration, it sets up the
 //! interpreter chain to include or exclude specific compilers.
 
+mod archiver;
 mod combinators;
 pub mod compilers;
 mod ignore;
@@ -13,6 +14,7 @@ pub mod resolve;
 use super::Interpreter;
 use crate::config;
 
+use archiver::ArchiverInterpreter;
 use combinators::{Any, InputLogger, OutputLogger};
 use compilers::CompilerInterpreter;
 use ignore::IgnoreByPath;
@@ -24,6 +26,7 @@ use resolve::ResolveExecutable;
 /// 1. Generic programs to exclude
 /// 2. Compilers specified to exclude
 /// 3. All other compilers to include
+/// 4. Archivers (when the link command database is enabled)
 pub fn create<'a>(config: &config::Main, confstr_path: String) -> impl Interpreter + 'a {
     // Build the base interpreter chain
     let mut interpreters: Vec<Box<dyn Interpreter>> = vec![
@@ -42,6 +45,12 @@ pub fn create<'a>(config: &config::Main, confstr_path: String) -> impl Interpret
     let tool = CompilerInterpreter::new_with_config(&config.compilers);
     interpreters.push(Box::new(tool));
 
+    // Recognize the archiver calls only for the link command database,
+    // these are not compilations.
+    if config.link_commands.enabled {
+        interpreters.push(Box::new(OutputLogger::new(ArchiverInterpreter, "archivers")));
+    }
+
     // Wrap the chain with executable path resolution so bare filenames
     // from preload p-variant interceptions are resolved to absolute paths.
     ResolveExecutable::new(InputLogger::new(Any::new(interpreters)), confstr_path)
@@ -133,6 +142,28 @@ mod test {
         assert!(matches!(interpreter.recognize(clang), RecognizeResult::Recognized(_)));
     }
 
+    #[test]
+    fn test_create_interpreter_recognizes_archiver_when_enabled() {
+        let execution = || {
+            Execution::from_strings(
+                "/usr/bin/ar",
+                vec!["ar", "rcs", "libfoo.a", "foo.o"],
+                "/home/user",
+                HashMap::new(),
+            )
+        };
+
+        let interpreter = create(&config::Main::default(), "/usr/bin:/bin".to_string());
+        assert!(matches!(interpreter.recognize(execution()), RecognizeResult::NotRecognized(_)));
+
+        let config = config::Main {
+            link_commands: config::LinkCommands { enabled: true, ..Default::default() },
+            ..Default::default()
+        };
+        let interpreter = create(&config, "/usr/bin:/bin".to_string());
+        assert!(matches!(interpreter.recognize(execution()), RecognizeResult::Recognized(_)));
+    }
+
     #[test]
     fn test_windows_gcc_exe_regression() {
         let config = config::Main::default();
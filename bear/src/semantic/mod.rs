@@ -32,6 +32,7 @@ pub mod interpreters;
 #[cfg(test)]
 pub mod testing;
 
+use super::config::CompilerType;
 use super::intercept::Execution;
 use interpreters::matchers::looks_like_a_source_file;
 
@@ -56,21 +57,27 @@ pub enum RecognizeResult {
 }
 
 /// Represents a full compiler command invocation.
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub struct Command {
     pub working_dir: PathBuf,
     pub executable: PathBuf,
     pub arguments: Vec<Argument>,
+    /// The recognized type of the compiler.
+    pub compiler: Option<CompilerType>,
+    /// Target triple implied by a cross-compiler prefix (e.g. `aarch64-linux-gnu`).
+    pub target: Option<String>,
 }
 
 /// A compiler command-line argument with semantic classification.
 #[derive(Debug, Clone, PartialEq)]
 pub enum Argument {
     /// Flags and other non-file arguments (e.g. `-c`, `-Wall`, `-I /usr/include`).
-    Other { arguments: Vec<String>, kind: ArgumentKind },
+    Other { arguments: Vec<String>, kind: ArgumentKind, clang: ClangCompatibility, value: FlagValue },
     /// A source or object file argument.
     Source { path: String, binary: bool },
     /// An output file argument (e.g. `-o main.o`).
+    ///
+    /// The flag is empty when the output is a positional argument (e.g. the archive of `ar`).
     Output { flag: String, path: String },
 }
 
@@ -99,8 +106,8 @@ impl Argument {
             }
             Self::Output { flag, path } => {
                 let p = Path::new(path);
-                let updated = path_updater(p);
-                vec![flag.clone(), updated.to_string_lossy().to_string()]
+                let updated = path_updater(p).to_string_lossy().to_string();
+                if flag.is_empty() { vec![updated] } else { vec![flag.clone(), updated] }
             }
         }
     }
@@ -142,6 +149,35 @@ pub enum PassEffect {
     None,
 }
 
+/// Represents how Clang understands a flag of another compiler.
+///
+/// Used to make command lines of GCC builds consumable by Clang based tools.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum ClangCompatibility {
+    /// Clang accepts the flag as it is.
+    #[default]
+    Supported,
+    /// Clang does not know the flag, it shall be removed.
+    Unsupported,
+    /// Clang knows the flag by another name, the `flag` prefix shall be renamed.
+    Equivalent { flag: &'static str, equivalent: &'static str },
+}
+
+/// Describes the value of a flag argument.
+///
+/// Used to format the paths embedded in flags (e.g. `-I include`) the same
+/// way as the source and output files.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum FlagValue {
+    /// The flag has no value, or the value is not a path.
+    #[default]
+    Plain,
+    /// The value of the `flag` is a file system path. The value is either a
+    /// separate argument, or glued to the flag (after the `glue` character
+    /// when the flag has one, like `--sysroot=/opt/sysroot`).
+    Path { flag: &'static str, glue: Option<char> },
+}
+
 /// Represents different compiler passes that an argument might affect.
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub enum CompilerPass {
@@ -153,6 +189,31 @@ pub enum CompilerPass {
 
 impl Command {
     pub fn new(working_dir: PathBuf, executable: PathBuf, arguments: Vec<Argument>) -> Self {
-        Self { working_dir, executable, arguments }
+        Self { working_dir, executable, arguments, compiler: None, target: None }
+    }
+
+    /// Sets the recognized type of the compiler.
+    pub fn with_compiler(mut self, compiler: CompilerType) -> Self {
+        self.compiler = Some(compiler);
+        self
+    }
+
+    /// Sets the target triple implied by the compiler executable name.
+    pub fn with_target(mut self, target: Option<String>) -> Self {
+        self.target = target;
+        self
+    }
+
+    /// Checks if the arguments select the target architecture explicitly
+    /// (with `--target=<triple>`, `--target <triple>` or `-target <triple>`).
+    pub fn has_explicit_target(&self) -> bool {
+        self.arguments.iter().any(|arg| match arg {
+            Argument::Other { arguments, .. } => arguments.first().is_some_and(|first| {
+                ["--target", "-target"].iter().any(|flag| {
+                    first.strip_prefix(flag).is_some_and(|rest| rest.is_empty() || rest.starts_with('='))
+                })
+            }),
+            _ => false,
+        })
     }
 }
@@ -1,6 +1,6 @@
 // SPDX-License-Identifier: GPL-3.0-or-later
 
-use super::{Argument, ArgumentKind, Command, PassEffect};
+use super::{Argument, ArgumentKind, ClangCompatibility, Command, FlagValue, PassEffect};
 use std::path::PathBuf;
 
 impl Command {
@@ -24,9 +24,13 @@ impl Command {
                     other_kind => Argument::Other {
                         arguments: args.into_iter().map(String::from).collect(),
                         kind: other_kind,
+                        clang: ClangCompatibility::Supported,
+                        value: FlagValue::Plain,
                     },
                 })
                 .collect(),
+            compiler: None,
+            target: None,
         }
     }
 
@@ -116,6 +120,8 @@ mod tests {
         let other_arg = Argument::Other {
             arguments: vec!["-Wall".to_string()],
             kind: ArgumentKind::Other(PassEffect::None),
+            clang: ClangCompatibility::Supported,
+            value: FlagValue::Plain,
         };
 
         assert_eq!(source_arg.kind(), ArgumentKind::Source { binary: false });
@@ -266,6 +266,480 @@ fn append_works_as_expected() -> Result<()> {
     Ok(())
 }
 
+/// Test the update append strategy replaces the entries of recompiled files
+// Requirements: output-append
+#[test]
+#[cfg(all(has_executable_compiler_c, has_executable_shell))]
+fn append_update_replaces_recompiled_entries() -> Result<()> {
+    let env = TestEnvironment::new("append_update_replaces_recompiled_entries")?;
+
+    env.create_source_files(&[
+        ("test1.c", "int func1() { return 1; }"),
+        ("test2.c", "int func2() { return 2; }"),
+    ])?;
+    let config = r#"
+schema: "4.1"
+
+append:
+  strategy: update
+"#;
+    let config_path = env.test_dir().join("config.yaml");
+    std::fs::write(&config_path, config)?;
+
+    let build_command1 = [
+        format!("{} -c -O0 -o test1.o test1.c", filename_of(COMPILER_C_PATH)),
+        format!("{} -c -o test2.o test2.c", filename_of(COMPILER_C_PATH)),
+    ]
+    .join("\n");
+    let build_script1_path = env.create_shell_script("build1.sh", &build_command1)?;
+
+    let build_command2 = format!("{} -c -O2 -o test1.o test1.c", filename_of(COMPILER_C_PATH));
+    let build_script2_path = env.create_shell_script("build2.sh", &build_command2)?;
+
+    env.run_bear_success(&[
+        "--config",
+        config_path.to_str().unwrap(),
+        "--output",
+        "compile_commands.json",
+        "--",
+        SHELL_PATH,
+        build_script1_path.to_str().unwrap(),
+    ])?;
+
+    // Run bear again with append, recompiling one file with different flags
+    env.run_bear_success(&[
+        "--config",
+        config_path.to_str().unwrap(),
+        "--output",
+        "compile_commands.json",
+        "--append",
+        "--",
+        SHELL_PATH,
+        build_script2_path.to_str().unwrap(),
+    ])?;
+
+    let db = env.load_compilation_database("compile_commands.json")?;
+    db.assert_count(2)?;
+
+    let temp_dir = env.test_dir().to_str().unwrap();
+    db.assert_contains(&compilation_entry!(
+        file: "test1.c".to_string(),
+        directory: temp_dir.to_string(),
+        arguments: vec![
+            COMPILER_C_PATH.to_string(),
+            "-c".to_string(),
+            "-O2".to_string(),
+            "-o".to_string(),
+            "test1.o".to_string(),
+            "test1.c".to_string(),
+        ]
+    ))?;
+
+    Ok(())
+}
+
+/// Test the prune command drops the entries of deleted source files
+// Requirements: output-append-prune
+#[test]
+#[cfg(all(has_executable_compiler_c, has_executable_shell))]
+fn prune_drops_entries_of_deleted_files() -> Result<()> {
+    let env = TestEnvironment::new("prune_drops_entries_of_deleted_files")?;
+
+    env.create_source_files(&[
+        ("test1.c", "int func1() { return 1; }"),
+        ("test2.c", "int func2() { return 2; }"),
+    ])?;
+
+    let build_command = [
+        format!("{} -c -o test1.o test1.c", filename_of(COMPILER_C_PATH)),
+        format!("{} -c -o test2.o test2.c", filename_of(COMPILER_C_PATH)),
+    ]
+    .join("\n");
+    let build_script_path = env.create_shell_script("build.sh", &build_command)?;
+
+    env.run_bear_success(&[
+        "--output",
+        "compile_commands.json",
+        "--",
+        SHELL_PATH,
+        build_script_path.to_str().unwrap(),
+    ])?;
+    let db = env.load_compilation_database("compile_commands.json")?;
+    db.assert_count(2)?;
+
+    std::fs::remove_file(env.test_dir().join("test2.c"))?;
+
+    env.run_bear_success(&["prune", "--output", "compile_commands.json"])?;
+
+    let db = env.load_compilation_database("compile_commands.json")?;
+    db.assert_count(1)?;
+    db.assert_contains(&compilation_entry!(
+        file: "test1.c".to_string(),
+        directory: env.test_dir().to_str().unwrap().to_string(),
+        arguments: vec![
+            COMPILER_C_PATH.to_string(),
+            "-c".to_string(),
+            "-o".to_string(),
+            "test1.o".to_string(),
+            "test1.c".to_string(),
+        ]
+    ))?;
+
+    // Without a compilation database there is nothing to prune
+    env.run_bear_failure(&["prune", "--output", "missing.json"])?;
+
+    Ok(())
+}
+
+/// Test the JSON lines output format and the conversion to the array form
+// Requirements: output-json-lines
+#[test]
+#[cfg(all(has_executable_compiler_c, has_executable_shell))]
+fn json_lines_output_and_convert() -> Result<()> {
+    let env = TestEnvironment::new("json_lines_output_and_convert")?;
+
+    env.create_source_files(&[
+        ("test1.c", "int func1() { return 1; }"),
+        ("test2.c", "int func2() { return 2; }"),
+    ])?;
+
+    let build_command = [
+        format!("{} -c test1.c", filename_of(COMPILER_C_PATH)),
+        format!("{} -c test2.c", filename_of(COMPILER_C_PATH)),
+    ]
+    .join("\n");
+    let build_script_path = env.create_shell_script("build.sh", &build_command)?;
+
+    let config = r#"
+schema: "4.1"
+
+format:
+  database: json-lines
+"#;
+    let config_path = env.test_dir().join("config.yaml");
+    std::fs::write(&config_path, config)?;
+
+    env.run_bear_success(&[
+        "--output",
+        "compile_commands.jsonl",
+        "--config",
+        config_path.to_str().unwrap(),
+        "--",
+        SHELL_PATH,
+        build_script_path.to_str().unwrap(),
+    ])?;
+
+    let content = std::fs::read_to_string(env.test_dir().join("compile_commands.jsonl"))?;
+    let lines: Vec<&str> = content.lines().collect();
+    assert_eq!(lines.len(), 2);
+    for line in lines {
+        let entry: Value = serde_json::from_str(line)?;
+        assert!(entry.is_object());
+    }
+
+    env.run_bear_success(&[
+        "convert",
+        "--input",
+        "compile_commands.jsonl",
+        "--output",
+        "compile_commands.json",
+    ])?;
+
+    let db = env.load_compilation_database("compile_commands.json")?;
+    db.assert_count(2)?;
+    db.assert_contains(&compilation_entry!(
+        file: "test1.c".to_string(),
+        directory: env.test_dir().to_str().unwrap().to_string(),
+        arguments: vec![COMPILER_C_PATH.to_string(), "-c".to_string(), "test1.c".to_string()]
+    ))?;
+
+    // Without the input there is nothing to convert
+    env.run_bear_failure(&["convert", "--input", "missing.jsonl", "--output", "compile_commands.json"])?;
+
+    Ok(())
+}
+
+/// The `merge` command combines compilation databases, with the configured
+/// source and duplicate filters and the path format applied.
+// Requirements: output-merge
+#[test]
+#[cfg(target_family = "unix")]
+fn merge_compilation_databases() -> Result<()> {
+    use serde_json::json;
+
+    let env = TestEnvironment::new("merge_compilation_databases")?;
+    let temp_dir = env.test_dir().to_str().unwrap();
+
+    let entry = |directory: &str, file: &str| json!({ "directory": directory, "file": file, "arguments": ["cc", "-c", file] });
+    let debug = json!([entry(temp_dir, "src/main.c"), entry(temp_dir, "test/unit.c")]);
+    let release = json!([entry(temp_dir, "src/main.c"), entry(temp_dir, "src/lib.c")]);
+    env.create_source_files(&[("debug.json", &debug.to_string()), ("release.json", &release.to_string())])?;
+
+    // The rules match the formatted (absolute) file paths.
+    let config = format!(
+        r#"
+schema: "4.1"
+
+sources:
+  directories:
+    - path: "{temp_dir}/test"
+      action: exclude
+
+format:
+  paths:
+    directory: absolute
+    file: absolute
+"#
+    );
+    let config_path = env.test_dir().join("config.yaml");
+    std::fs::write(&config_path, config)?;
+
+    env.run_bear_success(&[
+        "--config",
+        config_path.to_str().unwrap(),
+        "merge",
+        "debug.json",
+        "release.json",
+        "--output",
+        "compile_commands.json",
+    ])?;
+
+    let db = env.load_compilation_database("compile_commands.json")?;
+    db.assert_count(2)?;
+    for file in ["src/main.c", "src/lib.c"] {
+        db.assert_contains(&compilation_entry!(
+            file: env.test_dir().join(file).to_str().unwrap().to_string(),
+            directory: temp_dir.to_string(),
+            arguments: vec!["cc".to_string(), "-c".to_string(), file.to_string()]
+        ))?;
+    }
+
+    // Every input has to exist
+    env.run_bear_failure(&["merge", "debug.json", "missing.json", "--output", "compile_commands.json"])?;
+
+    Ok(())
+}
+
+/// The `diff` command reports the added, removed and changed translation
+/// units, and exits with failure when the databases are different.
+// Requirements: output-diff
+#[test]
+#[cfg(target_family = "unix")]
+fn diff_compilation_databases() -> Result<()> {
+    use serde_json::json;
+
+    let env = TestEnvironment::new("diff_compilation_databases")?;
+    let temp_dir = env.test_dir().to_str().unwrap();
+
+    let entry = |file: &str, flags: &[&str]| {
+        let mut arguments = vec!["cc", "-c"];
+        arguments.extend_from_slice(flags);
+        arguments.push(file);
+        json!({ "directory": temp_dir, "file": file, "arguments": arguments })
+    };
+    let old = json!([entry("main.c", &["-std=c11", "-Wall"]), entry("old.c", &[])]);
+    let new = json!([entry("main.c", &["-std=c17", "-DNEW"]), entry("new.c", &[])]);
+    env.create_source_files(&[("old.json", &old.to_string()), ("new.json", &new.to_string())])?;
+
+    let output = env.run_bear_failure(&["diff", "old.json", "new.json"])?;
+    let report = output.stdout();
+    assert!(report.contains(&format!("added: {temp_dir}/new.c\n")), "{report}");
+    assert!(report.contains(&format!("removed: {temp_dir}/old.c\n")), "{report}");
+    assert!(report.contains(&format!("changed: {temp_dir}/main.c\n")), "{report}");
+    assert!(report.contains("  + -DNEW\n"), "{report}");
+    assert!(report.contains("  - -Wall\n"), "{report}");
+    assert!(report.contains("  ~ -std=c11 -> -std=c17\n"), "{report}");
+
+    env.run_bear_failure(&["diff", "old.json", "new.json", "--format", "json", "--output", "diff.json"])?;
+    let report: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(env.test_dir().join("diff.json"))?)?;
+    assert_eq!(report["added"], json!([{ "file": format!("{temp_dir}/new.c") }]));
+    assert_eq!(report["removed"], json!([{ "file": format!("{temp_dir}/old.c") }]));
+    assert_eq!(
+        report["changed"][0]["changed"],
+        json!([{ "flag": "-std", "old": "-std=c11", "new": "-std=c17" }])
+    );
+
+    // The same databases are not different
+    env.run_bear_success(&["diff", "old.json", "old.json"])?;
+
+    Ok(())
+}
+
+/// The `check` command reports the problems of a compilation database as
+/// JSON findings, and exits with failure when any of them is an error.
+// Requirements: output-check
+#[test]
+#[cfg(target_family = "unix")]
+fn check_compilation_database() -> Result<()> {
+    use serde_json::json;
+
+    let env = TestEnvironment::new("check_compilation_database")?;
+    let temp_dir = env.test_dir().to_str().unwrap();
+    env.create_source_files(&[("main.c", "int main() { return 0; }")])?;
+
+    let entry = |file: &str| json!({ "directory": temp_dir, "file": file, "arguments": ["cc", "-c", file] });
+    let valid = json!([entry("main.c")]);
+    let invalid = json!([entry("main.c"), entry("missing.c"), entry("main.c")]);
+    env.create_source_files(&[("valid.json", &valid.to_string()), ("invalid.json", &invalid.to_string())])?;
+
+    let output = env.run_bear_success(&["check", "valid.json"])?;
+    let findings: serde_json::Value = serde_json::from_str(&output.stdout())?;
+    assert_eq!(findings, json!([]));
+
+    let output = env.run_bear_failure(&["check", "invalid.json"])?;
+    let findings: serde_json::Value = serde_json::from_str(&output.stdout())?;
+    let findings: Vec<_> = findings
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|finding| (finding["severity"].clone(), finding["check"].clone(), finding["index"].clone()))
+        .collect();
+    assert_eq!(
+        findings,
+        vec![
+            (json!("error"), json!("missing_file"), json!(1)),
+            (json!("warning"), json!("duplicate_entry"), json!(2)),
+        ]
+    );
+
+    Ok(())
+}
+
+/// The `normalize` command analyzes the commands of a compilation database
+/// from another generator, and writes them with the configured format and
+/// filters.
+// Requirements: output-normalize
+#[test]
+#[cfg(target_family = "unix")]
+fn normalize_compilation_database() -> Result<()> {
+    use serde_json::json;
+
+    let env = TestEnvironment::new("normalize_compilation_database")?;
+    let temp_dir = env.test_dir().to_str().unwrap();
+
+    let entry =
+        |file: &str, command: &str| json!({ "directory": temp_dir, "file": file, "command": command });
+    let input = json!([
+        entry("src/main.c", "/usr/bin/cc -c -o main.o src/main.c"),
+        entry("src/main.c", "/usr/bin/cc -c -o main.o src/main.c"),
+        entry("lib.c", "/usr/bin/cc -c -DNAME='\"my lib\"' lib.c"),
+        entry("lib.c", "/usr/bin/ls lib.c"),
+    ]);
+    env.create_source_files(&[("cmake.json", &input.to_string())])?;
+
+    let config = r#"
+schema: "4.1"
+
+format:
+  paths:
+    directory: absolute
+    file: absolute
+"#;
+    let config_path = env.test_dir().join("config.yaml");
+    std::fs::write(&config_path, config)?;
+
+    env.run_bear_success(&[
+        "--config",
+        config_path.to_str().unwrap(),
+        "normalize",
+        "--input",
+        "cmake.json",
+        "--output",
+        "compile_commands.json",
+    ])?;
+
+    let db = env.load_compilation_database("compile_commands.json")?;
+    db.assert_count(2)?;
+    db.assert_contains(
+        &compilation_entry!(
+            file: format!("{temp_dir}/src/main.c"),
+            directory: temp_dir.to_string(),
+            arguments: vec![
+                "/usr/bin/cc".to_string(),
+                "-c".to_string(),
+                "-o".to_string(),
+                format!("{temp_dir}/main.o"),
+                format!("{temp_dir}/src/main.c"),
+            ]
+        )
+        .output(format!("{temp_dir}/main.o")),
+    )?;
+    db.assert_contains(&compilation_entry!(
+        file: format!("{temp_dir}/lib.c"),
+        directory: temp_dir.to_string(),
+        arguments: vec![
+            "/usr/bin/cc".to_string(),
+            "-c".to_string(),
+            "-DNAME=\"my lib\"".to_string(),
+            format!("{temp_dir}/lib.c"),
+        ]
+    ))?;
+
+    // The input has to exist
+    env.run_bear_failure(&["normalize", "--input", "missing.json"])?;
+
+    Ok(())
+}
+
+/// The `export` command turns the compilation database or the events into a
+/// Ninja file or a shell script, which runs the compilations again.
+// Requirements: output-build-script
+#[test]
+#[cfg(target_family = "unix")]
+#[cfg(all(has_executable_compiler_c, has_executable_shell))]
+fn export_build_script() -> Result<()> {
+    use serde_json::json;
+
+    let env = TestEnvironment::new("export_build_script")?;
+    let temp_dir = env.test_dir().to_str().unwrap();
+
+    let event = json!({
+        "pid": 6601,
+        "execution": {
+            "executable": COMPILER_C_PATH,
+            "arguments": [COMPILER_C_PATH, "-c", "test.c", "-o", "test.o"],
+            "working_dir": temp_dir,
+            "environment": {}
+        }
+    });
+    env.create_source_files(&[
+        ("events.json", &event.to_string()),
+        ("test.c", "int main(void) { return 0; }"),
+    ])?;
+    env.run_bear_success(&["semantic", "--input", "events.json", "--output", "compile_commands.json"])?;
+
+    env.run_bear_success(&["export", "--output", "build.ninja"])?;
+
+    let content = env.read_file("build.ninja")?;
+    let test_o = env.test_dir().join("test.o");
+    let test_c = env.test_dir().join("test.c");
+    assert!(content.contains(&format!("build {}: compile {}\n", test_o.display(), test_c.display())));
+
+    env.run_bear_success(&[
+        "export",
+        "--events",
+        "events.json",
+        "--output",
+        "build.sh",
+        "--format",
+        "shell",
+        "--flag=-fsyntax-only",
+    ])?;
+
+    let status = std::process::Command::new(SHELL_PATH)
+        .arg(env.test_dir().join("build.sh"))
+        .current_dir(env.test_dir())
+        .status()?;
+    assert!(status.success(), "exported script failed");
+    assert!(!test_o.exists(), "syntax only run must not write the object file");
+
+    // Without the input there is nothing to export
+    env.run_bear_failure(&["export", "--input", "missing.json", "--output", "build.ninja"])?;
+
+    Ok(())
+}
+
 /// Test build with compilation failures - should still generate partial database
 /// Verifies Bear can handle partial build failures
 // Requirements: output-json-compilation-database
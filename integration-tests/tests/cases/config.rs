@@ -1166,3 +1166,691 @@ duplicates:
 
     Ok(())
 }
+
+/// The `format.arguments` rules rewrite the flags of the output entries in
+/// the configured order, and keep the compiler and the source file intact.
+// Requirements: output-argument-rewrite
+#[test]
+#[cfg(target_family = "unix")]
+fn argument_rewrite_rules_are_applied() -> Result<()> {
+    use serde_json::json;
+
+    let env = TestEnvironment::new("argument_rewrite")?;
+    let temp_dir = env.test_dir().to_str().unwrap().to_string();
+
+    let event = json!({
+        "pid": 4001,
+        "execution": {
+            "executable": COMPILER_C_PATH,
+            "arguments": [COMPILER_C_PATH, "-c", "-Werror", "-std=gnu11", "test.c"],
+            "working_dir": temp_dir,
+            "environment": {}
+        }
+    });
+    env.create_source_files(&[("events.json", &event.to_string()), ("test.c", "int main() { return 0; }")])?;
+
+    let config = r#"
+schema: "4.1"
+
+format:
+  arguments:
+    - action: remove
+      match: { exact: "-Werror" }
+    - action: replace
+      match: { regex: "-std=gnu(.*)" }
+      arguments: ["-std=c$1"]
+    - action: append
+      arguments: ["-Wno-unknown-warning-option"]
+"#;
+    let config_path = env.test_dir().join("config.yaml");
+    std::fs::write(&config_path, config)?;
+
+    env.run_bear_success(&[
+        "--config",
+        config_path.to_str().unwrap(),
+        "semantic",
+        "--input",
+        "events.json",
+        "--output",
+        "compile_commands.json",
+    ])?;
+
+    let db = env.load_compilation_database("compile_commands.json")?;
+    db.assert_count(1)?;
+    db.assert_contains(&CompilationEntryMatcher::new().file("test.c").arguments(vec![
+        COMPILER_C_PATH.to_string(),
+        "-c".to_string(),
+        "-std=c11".to_string(),
+        "test.c".to_string(),
+        "-Wno-unknown-warning-option".to_string(),
+    ]))?;
+
+    Ok(())
+}
+
+/// Argument rules without the fields their action needs are rejected.
+// Requirements: output-argument-rewrite
+#[test]
+fn argument_rewrite_rule_without_match_is_rejected() -> Result<()> {
+    let env = TestEnvironment::new("argument_rewrite_invalid")?;
+
+    let config = r#"
+schema: "4.1"
+
+format:
+  arguments:
+    - action: remove
+"#;
+    let config_path = env.test_dir().join("config.yaml");
+    std::fs::write(&config_path, config)?;
+
+    let output = env.run_bear(&[
+        "--config",
+        config_path.to_str().unwrap(),
+        "semantic",
+        "--input",
+        "events.json",
+        "--output",
+        "compile_commands.json",
+    ])?;
+    assert!(output.exit_code() != Some(0), "config with invalid argument rule must be rejected");
+
+    Ok(())
+}
+
+/// The `format.executables` rules substitute the compiler executable of the
+/// output entries, and leave the rest of the arguments untouched.
+// Requirements: output-executable-substitution
+#[test]
+#[cfg(target_family = "unix")]
+fn executable_substitution_rules_are_applied() -> Result<()> {
+    use serde_json::json;
+
+    let env = TestEnvironment::new("executable_substitution")?;
+    let temp_dir = env.test_dir().to_str().unwrap().to_string();
+
+    let events = [
+        json!({
+            "pid": 4101,
+            "execution": {
+                "executable": COMPILER_C_PATH,
+                "arguments": [COMPILER_C_PATH, "-c", "-O2", "first.c"],
+                "working_dir": temp_dir,
+                "environment": {}
+            }
+        }),
+        json!({
+            "pid": 4102,
+            "execution": {
+                "executable": "/opt/toolchain/bin/clang",
+                "arguments": ["/opt/toolchain/bin/clang", "-c", "-O2", "second.c"],
+                "working_dir": temp_dir,
+                "environment": {}
+            }
+        }),
+    ];
+    let events = events.iter().map(|event| event.to_string()).collect::<Vec<_>>().join("\n");
+    env.create_source_files(&[
+        ("events.json", &events),
+        ("first.c", "int first() { return 1; }"),
+        ("second.c", "int second() { return 2; }"),
+    ])?;
+
+    let config = format!(
+        r#"
+schema: "4.1"
+
+format:
+  executables:
+    - path: {}
+      replacement: basename
+    - compiler: clang
+      replacement: {{ path: /usr/bin/clang }}
+"#,
+        COMPILER_C_PATH
+    );
+    let config_path = env.test_dir().join("config.yaml");
+    std::fs::write(&config_path, config)?;
+
+    env.run_bear_success(&[
+        "--config",
+        config_path.to_str().unwrap(),
+        "semantic",
+        "--input",
+        "events.json",
+        "--output",
+        "compile_commands.json",
+    ])?;
+
+    let compiler_name = std::path::Path::new(COMPILER_C_PATH).file_name().unwrap().to_str().unwrap();
+    let db = env.load_compilation_database("compile_commands.json")?;
+    db.assert_count(2)?;
+    db.assert_contains(&CompilationEntryMatcher::new().file("first.c").arguments(vec![
+        compiler_name.to_string(),
+        "-c".to_string(),
+        "-O2".to_string(),
+        "first.c".to_string(),
+    ]))?;
+    db.assert_contains(&CompilationEntryMatcher::new().file("second.c").arguments(vec![
+        "/usr/bin/clang".to_string(),
+        "-c".to_string(),
+        "-O2".to_string(),
+        "second.c".to_string(),
+    ]))?;
+
+    Ok(())
+}
+
+/// With `file: absolute`, the relative paths embedded in flags (like `-I`
+/// and `--sysroot=`) are made absolute too, in their original flag form.
+// Requirements: output-path-format
+#[test]
+#[cfg(target_family = "unix")]
+fn absolute_file_format_applies_to_paths_in_flags() -> Result<()> {
+    use serde_json::json;
+
+    let env = TestEnvironment::new("flag_path_format")?;
+    let temp_dir = env.test_dir().to_str().unwrap().to_string();
+
+    let event = json!({
+        "pid": 4201,
+        "execution": {
+            "executable": COMPILER_C_PATH,
+            "arguments": [COMPILER_C_PATH, "-c", "-Iinclude", "-isystem", "third_party", "--sysroot=sysroot", "test.c"],
+            "working_dir": temp_dir,
+            "environment": {}
+        }
+    });
+    env.create_source_files(&[("events.json", &event.to_string()), ("test.c", "int main() { return 0; }")])?;
+
+    let config = r#"
+schema: "4.1"
+
+format:
+  paths:
+    directory: absolute
+    file: absolute
+"#;
+    let config_path = env.test_dir().join("config.yaml");
+    std::fs::write(&config_path, config)?;
+
+    env.run_bear_success(&[
+        "--config",
+        config_path.to_str().unwrap(),
+        "semantic",
+        "--input",
+        "events.json",
+        "--output",
+        "compile_commands.json",
+    ])?;
+
+    let db = env.load_compilation_database("compile_commands.json")?;
+    db.assert_count(1)?;
+    db.assert_contains(&CompilationEntryMatcher::new().file(format!("{}/test.c", temp_dir)).arguments(
+        vec![
+            COMPILER_C_PATH.to_string(),
+            "-c".to_string(),
+            format!("-I{}/include", temp_dir),
+            "-isystem".to_string(),
+            format!("{}/third_party", temp_dir),
+            format!("--sysroot={}/sysroot", temp_dir),
+            format!("{}/test.c", temp_dir),
+        ],
+    ))?;
+
+    Ok(())
+}
+
+/// The `format.paths.remap` rules translate the directory, file and the
+/// paths in flags, and the source filter rules are remapped the same way.
+// Requirements: output-path-remap
+#[test]
+#[cfg(target_family = "unix")]
+fn path_remap_rules_translate_paths() -> Result<()> {
+    use serde_json::json;
+
+    let env = TestEnvironment::new("path_remap")?;
+
+    let events = [
+        json!({
+            "pid": 4301,
+            "execution": {
+                "executable": COMPILER_C_PATH,
+                "arguments": [COMPILER_C_PATH, "-c", "-I/work/include", "src/main.c"],
+                "working_dir": "/work",
+                "environment": {}
+            }
+        }),
+        json!({
+            "pid": 4302,
+            "execution": {
+                "executable": COMPILER_C_PATH,
+                "arguments": [COMPILER_C_PATH, "-c", "/work/third_party/lib.c"],
+                "working_dir": "/work",
+                "environment": {}
+            }
+        }),
+    ];
+    let events = events.iter().map(|event| event.to_string()).collect::<Vec<_>>().join("\n");
+    env.create_source_files(&[("events.json", &events)])?;
+
+    let config = r#"
+schema: "4.1"
+
+sources:
+  directories:
+    - path: /work/third_party
+      action: exclude
+
+format:
+  paths:
+    directory: absolute
+    file: absolute
+    remap:
+      - from: /work
+        to: /home/dev/project
+"#;
+    let config_path = env.test_dir().join("config.yaml");
+    std::fs::write(&config_path, config)?;
+
+    env.run_bear_success(&[
+        "--config",
+        config_path.to_str().unwrap(),
+        "semantic",
+        "--input",
+        "events.json",
+        "--output",
+        "compile_commands.json",
+    ])?;
+
+    let db = env.load_compilation_database("compile_commands.json")?;
+    db.assert_count(1)?;
+    db.assert_contains(
+        &CompilationEntryMatcher::new()
+            .file("/home/dev/project/src/main.c")
+            .directory("/home/dev/project")
+            .arguments(vec![
+                COMPILER_C_PATH.to_string(),
+                "-c".to_string(),
+                "-I/home/dev/project/include".to_string(),
+                "/home/dev/project/src/main.c".to_string(),
+            ]),
+    )?;
+
+    Ok(())
+}
+
+/// The `root-relative` directory resolver makes the database independent of
+/// the checkout location, and the placeholder replaces the project root.
+// Requirements: output-path-root
+#[test]
+#[cfg(target_family = "unix")]
+fn path_root_relative_and_placeholder() -> Result<()> {
+    use serde_json::json;
+
+    let env = TestEnvironment::new("path_root")?;
+
+    let events = json!({
+        "pid": 4401,
+        "execution": {
+            "executable": COMPILER_C_PATH,
+            "arguments": [COMPILER_C_PATH, "-c", "-I/work/include", "main.c"],
+            "working_dir": "/work/src",
+            "environment": {}
+        }
+    });
+    env.create_source_files(&[("events.json", &events.to_string())])?;
+
+    let config = r#"
+schema: "4.1"
+
+format:
+  paths:
+    directory: root-relative
+    file: relative
+    root: /work
+"#;
+    let config_path = env.test_dir().join("config.yaml");
+    std::fs::write(&config_path, config)?;
+
+    env.run_bear_success(&[
+        "--config",
+        config_path.to_str().unwrap(),
+        "semantic",
+        "--input",
+        "events.json",
+        "--output",
+        "compile_commands.json",
+    ])?;
+
+    let db = env.load_compilation_database("compile_commands.json")?;
+    db.assert_count(1)?;
+    db.assert_contains(&CompilationEntryMatcher::new().file("main.c").directory("src"))?;
+
+    let config = r#"
+schema: "4.1"
+
+format:
+  paths:
+    directory: absolute
+    file: absolute
+    root: /work
+    root_placeholder: "${root}"
+"#;
+    std::fs::write(&config_path, config)?;
+
+    env.run_bear_success(&[
+        "--config",
+        config_path.to_str().unwrap(),
+        "semantic",
+        "--input",
+        "events.json",
+        "--output",
+        "compile_commands_placeholder.json",
+    ])?;
+
+    let db = env.load_compilation_database("compile_commands_placeholder.json")?;
+    db.assert_count(1)?;
+    db.assert_contains(
+        &CompilationEntryMatcher::new().file("${root}/src/main.c").directory("${root}/src").arguments(vec![
+            COMPILER_C_PATH.to_string(),
+            "-c".to_string(),
+            "-I${root}/include".to_string(),
+            "${root}/src/main.c".to_string(),
+        ]),
+    )?;
+
+    Ok(())
+}
+
+/// With `compile_flags.enabled`, a `compile_flags.txt` is written into the
+/// source directories with the flags common to all sources there.
+// Requirements: output-compile-flags
+#[test]
+#[cfg(target_family = "unix")]
+fn compile_flags_written_per_directory() -> Result<()> {
+    use serde_json::json;
+
+    let env = TestEnvironment::new("compile_flags_per_directory")?;
+    let temp_dir = env.test_dir().to_str().unwrap().to_string();
+
+    let event = |pid: u32, arguments: Vec<&str>| {
+        json!({
+            "pid": pid,
+            "execution": {
+                "executable": COMPILER_C_PATH,
+                "arguments": arguments,
+                "working_dir": temp_dir,
+                "environment": {}
+            }
+        })
+        .to_string()
+    };
+    let events = [
+        event(4301, vec![COMPILER_C_PATH, "-c", "-Iinclude", "-DFOO", "-O2", "src/a.c", "-o", "a.o"]),
+        event(4302, vec![COMPILER_C_PATH, "-c", "-Iinclude", "-DFOO", "-O0", "src/b.c", "-o", "b.o"]),
+    ]
+    .join("\n");
+    std::fs::create_dir_all(env.test_dir().join("src"))?;
+    env.create_source_files(&[
+        ("events.json", &events),
+        ("src/a.c", "int a() { return 0; }"),
+        ("src/b.c", "int b() { return 0; }"),
+    ])?;
+
+    let config = r#"
+schema: "4.1"
+
+compile_flags:
+  enabled: true
+"#;
+    let config_path = env.test_dir().join("config.yaml");
+    std::fs::write(&config_path, config)?;
+
+    env.run_bear_success(&[
+        "--config",
+        config_path.to_str().unwrap(),
+        "semantic",
+        "--input",
+        "events.json",
+        "--output",
+        "compile_commands.json",
+    ])?;
+
+    let db = env.load_compilation_database("compile_commands.json")?;
+    db.assert_count(2)?;
+
+    let flags = std::fs::read_to_string(env.test_dir().join("src").join("compile_flags.txt"))?;
+    assert_eq!(flags, format!("-I{}/include\n-DFOO\n", temp_dir));
+    assert!(!env.test_dir().join("compile_flags.txt").exists());
+
+    Ok(())
+}
+
+/// With `link_commands.enabled`, the linker and archiver calls are written
+/// into a separate file, while the compilation database is not changed.
+// Requirements: output-link-commands
+#[test]
+#[cfg(target_family = "unix")]
+fn link_commands_written_for_linker_and_archiver() -> Result<()> {
+    use serde_json::json;
+
+    let env = TestEnvironment::new("link_commands")?;
+    let temp_dir = env.test_dir().to_str().unwrap().to_string();
+
+    let event = |pid: u32, executable: &str, arguments: Vec<&str>| {
+        json!({
+            "pid": pid,
+            "execution": {
+                "executable": executable,
+                "arguments": arguments,
+                "working_dir": temp_dir,
+                "environment": {}
+            }
+        })
+        .to_string()
+    };
+    let events = [
+        event(4401, COMPILER_C_PATH, vec![COMPILER_C_PATH, "-c", "foo.c", "-o", "foo.o"]),
+        event(4402, "/usr/bin/ar", vec!["ar", "rcs", "libfoo.a", "foo.o"]),
+        event(4403, "/usr/bin/ar", vec!["ar", "t", "libfoo.a"]),
+        event(
+            4404,
+            COMPILER_C_PATH,
+            vec![COMPILER_C_PATH, "main.o", "libfoo.a", "-L/opt/lib", "-lm", "-o", "app"],
+        ),
+    ]
+    .join("\n");
+    env.create_source_files(&[("events.json", &events), ("foo.c", "int foo() { return 0; }")])?;
+
+    let config = r#"
+schema: "4.1"
+
+link_commands:
+  enabled: true
+"#;
+    let config_path = env.test_dir().join("config.yaml");
+    std::fs::write(&config_path, config)?;
+
+    env.run_bear_success(&[
+        "--config",
+        config_path.to_str().unwrap(),
+        "semantic",
+        "--input",
+        "events.json",
+        "--output",
+        "compile_commands.json",
+    ])?;
+
+    let db = env.load_compilation_database("compile_commands.json")?;
+    db.assert_count(1)?;
+
+    let content = std::fs::read_to_string(env.test_dir().join("link_commands.json"))?;
+    let entries: serde_json::Value = serde_json::from_str(&content)?;
+    assert_eq!(
+        entries,
+        json!([
+            {
+                "directory": temp_dir,
+                "executable": "/usr/bin/ar",
+                "kind": "archive",
+                "output": "libfoo.a",
+                "inputs": ["foo.o"],
+                "arguments": ["ar", "rcs", "libfoo.a", "foo.o"]
+            },
+            {
+                "directory": temp_dir,
+                "executable": COMPILER_C_PATH,
+                "kind": "link",
+                "output": "app",
+                "inputs": ["main.o", "libfoo.a"],
+                "libraries": ["m"],
+                "library_paths": ["/opt/lib"],
+                "arguments": [COMPILER_C_PATH, "main.o", "libfoo.a", "-L/opt/lib", "-lm", "-o", "app"]
+            }
+        ])
+    );
+
+    Ok(())
+}
+
+/// The `outputs` routes write the entries of the sub-projects into their own
+/// compilation databases, while the main output has all entries.
+// Requirements: output-routes
+#[test]
+#[cfg(target_family = "unix")]
+fn output_routes_split_entries_by_directory() -> Result<()> {
+    use serde_json::json;
+
+    let env = TestEnvironment::new("output_routes")?;
+    let temp_dir = env.test_dir().to_str().unwrap().to_string();
+
+    let event = |pid: u32, source: &str| {
+        json!({
+            "pid": pid,
+            "execution": {
+                "executable": COMPILER_C_PATH,
+                "arguments": [COMPILER_C_PATH, "-c", source],
+                "working_dir": temp_dir,
+                "environment": {}
+            }
+        })
+        .to_string()
+    };
+    let events = [event(4401, "frontend/ui.c"), event(4402, "backend/db.c")].join("\n");
+    std::fs::create_dir_all(env.test_dir().join("frontend"))?;
+    std::fs::create_dir_all(env.test_dir().join("backend"))?;
+    env.create_source_files(&[
+        ("events.json", &events),
+        ("frontend/ui.c", "int ui() { return 0; }"),
+        ("backend/db.c", "int db() { return 0; }"),
+    ])?;
+
+    let config = r#"
+schema: "4.1"
+
+outputs:
+  - path: frontend/compile_commands.json
+    sources:
+      directories:
+        - path: backend
+          action: exclude
+  - path: backend/compile_commands.json
+    sources:
+      directories:
+        - path: frontend
+          action: exclude
+"#;
+    let config_path = env.test_dir().join("config.yaml");
+    std::fs::write(&config_path, config)?;
+
+    env.run_bear_success(&[
+        "--config",
+        config_path.to_str().unwrap(),
+        "semantic",
+        "--input",
+        "events.json",
+        "--output",
+        "compile_commands.json",
+    ])?;
+
+    let db = env.load_compilation_database("compile_commands.json")?;
+    db.assert_count(2)?;
+
+    let db = env.load_compilation_database("frontend/compile_commands.json")?;
+    db.assert_count(1)?;
+    db.assert_contains(&CompilationEntryMatcher::new().file("frontend/ui.c"))?;
+
+    let db = env.load_compilation_database("backend/compile_commands.json")?;
+    db.assert_count(1)?;
+    db.assert_contains(&CompilationEntryMatcher::new().file("backend/db.c"))?;
+
+    Ok(())
+}
+
+/// The named `outputs` write differently formatted compilation databases
+/// from the same semantic analysis run.
+// Requirements: output-profiles
+#[test]
+#[cfg(target_family = "unix")]
+fn output_profiles_with_own_format() -> Result<()> {
+    use serde_json::json;
+
+    let env = TestEnvironment::new("output_profiles")?;
+    let temp_dir = env.test_dir().to_str().unwrap().to_string();
+
+    let event = json!({
+        "pid": 4501,
+        "execution": {
+            "executable": COMPILER_C_PATH,
+            "arguments": [COMPILER_C_PATH, "-c", "test.c"],
+            "working_dir": temp_dir,
+            "environment": {}
+        }
+    });
+    env.create_source_files(&[("events.json", &event.to_string()), ("test.c", "int main() { return 0; }")])?;
+
+    let config = r#"
+schema: "4.1"
+
+format:
+  paths:
+    directory: absolute
+    file: absolute
+
+outputs:
+  - name: legacy
+    path: legacy.json
+    format:
+      paths:
+        directory: as-is
+        file: as-is
+      entries:
+        use_array_format: false
+"#;
+    let config_path = env.test_dir().join("config.yaml");
+    std::fs::write(&config_path, config)?;
+
+    env.run_bear_success(&[
+        "--config",
+        config_path.to_str().unwrap(),
+        "semantic",
+        "--input",
+        "events.json",
+        "--output",
+        "compile_commands.json",
+    ])?;
+
+    let db = env.load_compilation_database("compile_commands.json")?;
+    db.assert_count(1)?;
+    db.assert_contains(&CompilationEntryMatcher::new().file(format!("{}/test.c", temp_dir)))?;
+
+    let db = env.load_compilation_database("legacy.json")?;
+    db.assert_count(1)?;
+    let entry = &db.entries()[0];
+    assert_eq!(entry["file"], "test.c");
+    assert_eq!(entry["command"], format!("{} -c test.c", COMPILER_C_PATH));
+    assert!(entry.get("arguments").is_none());
+
+    Ok(())
+}
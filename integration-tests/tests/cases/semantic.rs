@@ -752,3 +752,189 @@ fn clang_cl_inherits_msvc_per_warning_options() -> Result<()> {
 
     Ok(())
 }
+
+/// The `graph` command connects the sources to the objects, archives and
+/// executables they end up in, and writes it as JSON or DOT.
+// Requirements: output-artifact-graph
+#[test]
+#[cfg(all(target_family = "unix", has_executable_compiler_c))]
+fn graph_export_from_events() -> Result<()> {
+    let env = TestEnvironment::new("graph_export")?;
+    let temp_dir = env.test_dir().to_str().unwrap().to_string();
+
+    let event = |pid: u32, executable: &str, arguments: Vec<&str>| {
+        json!({
+            "pid": pid,
+            "execution": {
+                "executable": executable,
+                "arguments": arguments,
+                "working_dir": temp_dir,
+                "environment": {}
+            }
+        })
+        .to_string()
+    };
+    let events = [
+        event(5501, COMPILER_C_PATH, vec![COMPILER_C_PATH, "-c", "foo.c"]),
+        event(5502, COMPILER_C_PATH, vec![COMPILER_C_PATH, "-c", "main.c", "-o", "main.o"]),
+        event(5503, "/usr/bin/ar", vec!["ar", "rcs", "libfoo.a", "foo.o"]),
+        event(5504, COMPILER_C_PATH, vec![COMPILER_C_PATH, "main.o", "libfoo.a", "-o", "app"]),
+    ]
+    .join("\n");
+    env.create_source_files(&[("events.json", &events)])?;
+
+    env.run_bear_success(&["graph", "--input", "events.json", "--output", "graph.json"])?;
+
+    let content = std::fs::read_to_string(env.test_dir().join("graph.json"))?;
+    let graph: serde_json::Value = serde_json::from_str(&content)?;
+    let path = |name: &str| env.test_dir().join(name).to_str().unwrap().to_string();
+    assert_eq!(
+        graph["nodes"],
+        json!([
+            { "id": 0, "path": path("foo.c") },
+            { "id": 1, "path": path("foo.o") },
+            { "id": 2, "path": path("main.c") },
+            { "id": 3, "path": path("main.o") },
+            { "id": 4, "path": path("libfoo.a") },
+            { "id": 5, "path": path("app") }
+        ])
+    );
+    let edges: Vec<(u64, u64, &str)> = graph["edges"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|edge| {
+            (edge["from"].as_u64().unwrap(), edge["to"].as_u64().unwrap(), edge["kind"].as_str().unwrap())
+        })
+        .collect();
+    assert_eq!(
+        edges,
+        vec![(0, 1, "compile"), (2, 3, "compile"), (1, 4, "archive"), (3, 5, "link"), (4, 5, "link")]
+    );
+
+    env.run_bear_success(&["graph", "--input", "events.json", "--output", "graph.dot", "--format", "dot"])?;
+
+    let content = std::fs::read_to_string(env.test_dir().join("graph.dot"))?;
+    assert!(content.starts_with("digraph artifacts {"));
+    assert!(content.contains("n1 -> n4 [label=\"archive\"];"));
+
+    // Without the input there is nothing to export
+    env.run_bear_failure(&["graph", "--input", "missing.json", "--output", "graph.json"])?;
+
+    Ok(())
+}
+
+/// The `import` command turns the log of `strace -f` into an event file,
+/// which is analyzed like the intercepted events.
+// Requirements: interception-strace-import
+#[test]
+#[cfg(all(target_family = "unix", has_executable_compiler_c))]
+fn import_strace_log() -> Result<()> {
+    let env = TestEnvironment::new("import_strace_log")?;
+    let temp_dir = env.test_dir().to_str().unwrap().to_string();
+
+    let log = [
+        r#"4100  execve("/usr/bin/make", ["make"], 0x7ffd5d8e0a58 /* 24 vars */) = 0"#.to_string(),
+        r#"4100  clone(child_stack=NULL, flags=CLONE_CHILD_CLEARTID|SIGCHLD) = 4101"#.to_string(),
+        r#"4101  chdir("src") = 0"#.to_string(),
+        format!(r#"4101  execve("{COMPILER_C_PATH}", ["{COMPILER_C_PATH}", "-c", "-DNAME=\"my lib\"", "lib.c"], 0x5581 /* 24 vars */ <unfinished ...>"#),
+        r#"4100  wait4(-1,  <unfinished ...>"#.to_string(),
+        r#"4101  <... execve resumed>) = 0"#.to_string(),
+        r#"4101  +++ exited with 0 +++"#.to_string(),
+        r#"4100  <... wait4 resumed>[{WIFEXITED(s) && WEXITSTATUS(s) == 0}], 0, NULL) = 4101"#.to_string(),
+        format!(r#"4100  execve("{COMPILER_C_PATH}", ["{COMPILER_C_PATH}", "-c", "missing.c"], 0x5581 /* 24 vars */) = -1 ENOENT (No such file or directory)"#),
+        r#"4100  +++ exited with 0 +++"#.to_string(),
+    ]
+    .join("\n");
+    env.create_source_files(&[("build.log", &log)])?;
+
+    env.run_bear_success(&["import", "--input", "build.log", "--directory", &temp_dir])?;
+    env.run_bear_success(&["semantic", "--input", "events.json", "--output", "compile_commands.json"])?;
+
+    let db = env.load_compilation_database("compile_commands.json")?;
+    db.assert_count(1)?;
+    db.assert_contains(&compilation_entry!(
+        file: "lib.c".to_string(),
+        directory: format!("{temp_dir}/src"),
+        arguments: vec![
+            COMPILER_C_PATH.to_string(),
+            "-c".to_string(),
+            "-DNAME=\"my lib\"".to_string(),
+            "lib.c".to_string(),
+        ]
+    ))?;
+
+    // The log has to exist
+    env.run_bear_failure(&["import", "--input", "missing.log"])?;
+
+    Ok(())
+}
+
+/// The `import` command turns the compile actions of a Bazel action graph
+/// into an event file, in both the JSON and the text output format.
+// Requirements: interception-bazel-aquery-import
+#[test]
+#[cfg(all(target_family = "unix", has_executable_compiler_c))]
+fn import_bazel_action_graph() -> Result<()> {
+    let env = TestEnvironment::new("import_bazel_action_graph")?;
+    let execroot = env.test_dir().join("execroot").to_str().unwrap().to_string();
+
+    let json = json!({
+        "artifacts": [{ "id": 1, "pathFragmentId": 1 }],
+        "actions": [{
+            "targetId": 1,
+            "mnemonic": "CppCompile",
+            "arguments": [COMPILER_C_PATH, "-iquote", ".", "-c", "src/main.c", "-o", "bazel-out/main.o"],
+            "environmentVariables": [{ "key": "PATH", "value": "/bin:/usr/bin" }]
+        }, {
+            "targetId": 1,
+            "mnemonic": "CppLink",
+            "arguments": [COMPILER_C_PATH, "bazel-out/main.o", "-o", "bazel-out/main"]
+        }]
+    });
+    let text = [
+        "actions {".to_string(),
+        "  target_id: 1".to_string(),
+        r#"  mnemonic: "CppCompile""#.to_string(),
+        format!(r#"  arguments: "{COMPILER_C_PATH}""#),
+        r#"  arguments: "-iquote""#.to_string(),
+        r#"  arguments: ".""#.to_string(),
+        r#"  arguments: "-c""#.to_string(),
+        r#"  arguments: "src/main.c""#.to_string(),
+        r#"  arguments: "-o""#.to_string(),
+        r#"  arguments: "bazel-out/main.o""#.to_string(),
+        "}".to_string(),
+    ]
+    .join("\n");
+    env.create_source_files(&[("actions.json", &json.to_string()), ("actions.txtpb", &text)])?;
+
+    let expected = compilation_entry!(
+        file: "src/main.c".to_string(),
+        directory: execroot.clone(),
+        arguments: vec![
+            COMPILER_C_PATH.to_string(),
+            "-iquote".to_string(),
+            ".".to_string(),
+            "-c".to_string(),
+            "src/main.c".to_string(),
+            "-o".to_string(),
+            "bazel-out/main.o".to_string(),
+        ]
+    )
+    .output("bazel-out/main.o".to_string());
+
+    for (input, format) in [("actions.json", "aquery-jsonproto"), ("actions.txtpb", "aquery-textproto")] {
+        env.run_bear_success(&["import", "--input", input, "--format", format, "--directory", &execroot])?;
+        env.run_bear_success(&["semantic", "--input", "events.json", "--output", "compile_commands.json"])?;
+
+        let db = env.load_compilation_database("compile_commands.json")?;
+        db.assert_count(1)?;
+        db.assert_contains(&expected)?;
+    }
+
+    // The action graph has to be valid
+    env.create_source_files(&[("invalid.json", "{")])?;
+    env.run_bear_failure(&["import", "--input", "invalid.json", "--format", "aquery-jsonproto"])?;
+
+    Ok(())
+}